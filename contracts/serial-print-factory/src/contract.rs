@@ -3,7 +3,8 @@ use base_factory::ContractError as BaseContractError;
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    ensure_eq, to_binary, Binary, Deps, DepsMut, Env, MessageInfo, StdResult, WasmMsg,
+    coins, ensure_eq, to_binary, BankMsg, Binary, Decimal, Deps, DepsMut, Env, MessageInfo,
+    StdResult, WasmMsg,
 };
 use cw2::set_contract_version;
 use cw_utils::must_pay;
@@ -16,7 +17,7 @@ use crate::msg::{
     ExecuteMsg, InstantiateMsg, ParamsResponse, SudoMsg, VendingMinterCreateMsg,
     VendingUpdateParamsMsg,
 };
-use crate::state::{SUDO_PARAMS};
+use crate::state::SUDO_PARAMS;
 
 // version info for migration info
 const CONTRACT_NAME: &str = "crates.io:serial-print-factory";
@@ -49,23 +50,59 @@ pub fn execute(
     }
 }
 
+/// Mint prices can be in the native denom, an IBC voucher (`ibc/{64 hex chars}`)
+/// or a token factory denom (`factory/{creator}/{subdenom}`)
+fn is_valid_mint_denom(denom: &str) -> bool {
+    if denom == NATIVE_DENOM {
+        return true;
+    }
+    if let Some(hash) = denom.strip_prefix("ibc/") {
+        return hash.len() == 64 && hash.chars().all(|c| c.is_ascii_hexdigit());
+    }
+    if let Some(rest) = denom.strip_prefix("factory/") {
+        return match rest.split_once('/') {
+            Some((creator, subdenom)) => {
+                !creator.is_empty()
+                    && creator.chars().all(|c| c.is_ascii_alphanumeric())
+                    && !subdenom.is_empty()
+                    && subdenom
+                        .chars()
+                        .all(|c| c.is_ascii_alphanumeric() || "/:._-".contains(c))
+            }
+            None => false,
+        };
+    }
+    false
+}
+
 pub fn execute_create_minter(
     deps: DepsMut,
     _env: Env,
     info: MessageInfo,
     msg: VendingMinterCreateMsg,
 ) -> Result<Response, ContractError> {
-    must_pay(&info, NATIVE_DENOM)?;
+    let paid = must_pay(&info, NATIVE_DENOM)?;
 
     let params = SUDO_PARAMS.load(deps.storage)?;
 
     let mut res = Response::new();
     let creation_fee = params.extension.creation_fee_per_token * (msg.init_msg.num_tokens as u128);
     checked_fair_burn(&info, creation_fee, None, &mut res)?;
-    
+
+    // Only the fee is burned, return any overpayment to the creator
+    let refund = paid.u128() - creation_fee;
+    if refund > 0 {
+        res = res
+            .add_attribute("creation_fee_refund", refund.to_string())
+            .add_message(BankMsg::Send {
+                to_address: info.sender.to_string(),
+                amount: coins(refund, NATIVE_DENOM),
+            });
+    }
+
     // Check the number of tokens is more than zero
     if msg.init_msg.num_tokens == 0 {
-        return Err(ContractError::InvalidNumTokens { });
+        return Err(ContractError::InvalidNumTokens {});
     }
 
     // Check per address limit is valid
@@ -79,18 +116,48 @@ pub fn execute_create_minter(
         });
     }
 
-    if NATIVE_DENOM != msg.init_msg.mint_price.denom {
+    if !is_valid_mint_denom(&msg.init_msg.mint_price.denom)
+        || msg
+            .init_msg
+            .alt_mint_prices
+            .iter()
+            .flatten()
+            .any(|price| !is_valid_mint_denom(&price.denom))
+    {
         return Err(ContractError::BaseError(BaseContractError::InvalidDenom {}));
     }
 
-    // Check that the price is greater than the minimum
-    if params.min_mint_price.amount > msg.init_msg.mint_price.amount {
+    // Check that the prices are greater than the minimum, which only applies in its own denom
+    if let Some(price) = std::iter::once(&msg.init_msg.mint_price)
+        .chain(msg.init_msg.alt_mint_prices.iter().flatten())
+        .find(|price| {
+            price.denom == params.min_mint_price.denom
+                && price.amount < params.min_mint_price.amount
+        })
+    {
         return Err(ContractError::InsufficientMintPrice {
             expected: params.min_mint_price.amount.u128(),
-            got: msg.init_msg.mint_price.amount.into(),
+            got: price.amount.u128(),
         });
     }
 
+    if params.extension.require_explicit_content
+        && msg.collection_params.info.explicit_content.is_none()
+    {
+        return Err(ContractError::ExplicitContentRequired {});
+    }
+
+    // Checked before the minter or the collection are instantiated
+    if let Some(royalty_info) = &msg.collection_params.info.royalty_info {
+        let max = Decimal::from_ratio(params.extension.max_royalty_bps, 10_000u128);
+        if royalty_info.share > max {
+            return Err(ContractError::RoyaltyTooHigh {
+                max,
+                got: royalty_info.share,
+            });
+        }
+    }
+
     let wasm_msg = WasmMsg::Instantiate {
         admin: Some(info.sender.to_string()),
         code_id: params.code_id,
@@ -140,6 +207,31 @@ pub fn sudo_update_params(
         .airdrop_mint_fee_bps
         .unwrap_or(params.extension.airdrop_mint_fee_bps);
 
+    params.extension.require_explicit_content = param_msg
+        .extension
+        .require_explicit_content
+        .unwrap_or(params.extension.require_explicit_content);
+
+    params.extension.max_start_offset_secs = param_msg
+        .extension
+        .max_start_offset_secs
+        .unwrap_or(params.extension.max_start_offset_secs);
+
+    params.extension.max_mint_price = param_msg
+        .extension
+        .max_mint_price
+        .unwrap_or(params.extension.max_mint_price);
+
+    params.extension.max_royalty_bps = param_msg
+        .extension
+        .max_royalty_bps
+        .unwrap_or(params.extension.max_royalty_bps);
+
+    params.extension.max_token_limit = param_msg
+        .extension
+        .max_token_limit
+        .unwrap_or(params.extension.max_token_limit);
+
     SUDO_PARAMS.save(deps.storage, &params)?;
 
     Ok(Response::new().add_attribute("action", "sudo_update_params"))
@@ -1,5 +1,5 @@
 use base_factory::ContractError as BaseContractError;
-use cosmwasm_std::StdError;
+use cosmwasm_std::{Decimal, StdError};
 use cw_utils::PaymentError;
 use sg1::FeeError;
 use thiserror::Error;
@@ -27,6 +27,12 @@ pub enum ContractError {
     #[error("Minimum network mint price {expected} got {got}")]
     InsufficientMintPrice { expected: u128, got: u128 },
 
+    #[error("Collections must set explicit_content")]
+    ExplicitContentRequired {},
+
+    #[error("Royalty share {got} is above the maximum of {max}")]
+    RoyaltyTooHigh { max: Decimal, got: Decimal },
+
     #[error("{0}")]
     BaseError(#[from] BaseContractError),
 }
@@ -1,6 +1,6 @@
 #[cfg(test)]
 mod tests {
-    use crate::msg::{InstantiateMsg, VendingMinterCreateMsg};
+    use crate::msg::InstantiateMsg;
     use crate::state::ParamsExtension;
     use crate::{helpers::FactoryContract, state::VendingMinterParams};
     use cosmwasm_std::{coin, Addr};
@@ -28,6 +28,10 @@ mod tests {
     pub const MINT_FEE_BPS: u64 = 1_000; // 10%
     pub const AIRDROP_MINT_FEE_BPS: u64 = 10_000; // 100%
     pub const MAX_PER_ADDRESS_LIMIT: u32 = 50;
+    pub const MAX_MINT_PRICE: u128 = 1_000_000_000_000;
+    pub const MAX_ROYALTY_BPS: u64 = 1_000; // 10%
+    pub const MAX_TOKEN_LIMIT: u32 = 10_000;
+    pub const MAX_START_OFFSET_SECS: u64 = 5 * 365 * 24 * 60 * 60; // the mock chain starts years before genesis
 
     fn custom_mock_app() -> StargazeApp {
         StargazeApp::default()
@@ -45,6 +49,11 @@ mod tests {
                 max_per_address_limit: MAX_PER_ADDRESS_LIMIT,
                 airdrop_mint_price: coin(AIRDROP_MINT_PRICE, NATIVE_DENOM),
                 airdrop_mint_fee_bps: AIRDROP_MINT_FEE_BPS,
+                require_explicit_content: false,
+                max_start_offset_secs: MAX_START_OFFSET_SECS,
+                max_mint_price: coin(MAX_MINT_PRICE, NATIVE_DENOM),
+                max_royalty_bps: MAX_ROYALTY_BPS,
+                max_token_limit: MAX_TOKEN_LIMIT,
             },
         }
     }
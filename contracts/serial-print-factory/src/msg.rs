@@ -1,5 +1,5 @@
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Coin, Timestamp};
+use cosmwasm_std::{Binary, Coin, HexBinary, Timestamp, Uint128};
 use sg2::msg::{CreateMinterMsg, Sg2ExecuteMsg, UpdateMinterParamsMsg};
 
 use crate::state::VendingMinterParams;
@@ -18,6 +18,123 @@ pub struct VendingMinterInitMsgExtension {
     pub mint_price: Coin,
     pub per_address_limit: u32,
     pub whitelist: Option<String>,
+    /// Number of leading token ids minted to the creator when the collection is created
+    pub creator_reserve: Option<u32>,
+    /// Number of paid mints required before the proceeds can be withdrawn.
+    /// When set, proceeds are held by the minter instead of being paid out on each mint.
+    pub soft_cap: Option<u32>,
+    /// Ordered mint phases. When set, they replace the whitelist / public pricing.
+    pub phases: Option<Vec<PhaseMsg>>,
+    /// Record every mint so it can be read back with the `MintHistory` query
+    pub record_history: Option<bool>,
+    /// Restricts `MintTo` / `MintFor` recipients to these addresses
+    pub mint_to_allowlist: Option<Vec<String>>,
+    /// Releases the held proceeds linearly from the mint start time
+    pub vesting: Option<VestingConfig>,
+    /// Share of the mint price paid to the referrer passed to `Mint`
+    pub referral_bps: Option<u64>,
+    /// Maximum number of tokens sold through the whitelist
+    pub whitelist_total_cap: Option<u32>,
+    /// Fee charged to burn a minted token and mint a replacement. `None` disables rerolls.
+    pub reroll_fee: Option<Coin>,
+    /// Merkle root of `address:allocation` leaves. Members mint with `MerkleMint`.
+    pub allocation_merkle_root: Option<HexBinary>,
+    /// Operational admin of the minter. Defaults to the collection creator.
+    pub admin: Option<String>,
+    /// Minimum number of seconds between any two public mints
+    pub global_mint_interval_secs: Option<u64>,
+    /// Hold the proceeds and sweep them to the payment address once they reach this amount
+    pub auto_withdraw_threshold: Option<Uint128>,
+    /// Whether tokens can be minted to contract addresses. Defaults to true.
+    pub allow_contract_recipients: Option<bool>,
+    /// Open the public mint before the start time once the whitelist sells `whitelist_total_cap`
+    pub public_on_whitelist_sellout: Option<bool>,
+    /// Proceeds are held until the collection is revealed.
+    /// If it isn't revealed by this time, buyers can claim a refund.
+    pub reveal_deadline: Option<Timestamp>,
+    /// Splits the proceeds between these recipients instead of paying the payment address.
+    /// Shares must add up to 10,000 bps.
+    pub payout_splits: Option<Vec<PayoutSplitMsg>>,
+    /// Seconds between `RequestFreeze` and `ConfirmFreeze`. Defaults to one day, must be non-zero.
+    pub freeze_delay_secs: Option<u64>,
+    /// Appended to every token uri, e.g. `.json`
+    pub token_uri_suffix: Option<String>,
+    /// Give every address a fresh per-address limit once the public mint opens,
+    /// so whitelist mints don't count against it
+    pub reset_limit_on_public: Option<bool>,
+    /// Floor for the mint price in its denom, on top of the factory minimum
+    pub min_mint_price: Option<Coin>,
+    /// Denom shown to users, e.g. `STARS`. Defaults to the mint price denom.
+    pub display_denom: Option<String>,
+    /// Decimals between the mint price denom and `display_denom`. Defaults to 6.
+    pub exponent: Option<u32>,
+    /// Also reject referrals to the minter contract or its admin. Self-referrals are always rejected.
+    pub block_privileged_referrals: Option<bool>,
+    /// Higher per-address limit while the mint is in its first `early_window_secs`
+    pub early_per_address_limit: Option<u32>,
+    /// Length of the early window after the start time
+    pub early_window_secs: Option<u64>,
+    /// Minimum time between two start time updates
+    pub start_time_update_cooldown_secs: Option<u64>,
+    /// Accept refundable `Deposit`s before the start time, claimed as mints once it opens
+    pub presale_deposits: Option<bool>,
+    /// Record the block height and price of every mint, read back with the `MintReceipt` query
+    pub record_receipts: Option<bool>,
+    /// Public mints also wait for this block height, as a backstop to the start time
+    pub require_height_gate: Option<u64>,
+    /// Maximum public mints per address per UTC day
+    pub daily_mint_limit: Option<u32>,
+    /// Where the proceeds are paid instead of the payment address, a bank send or a contract execute.
+    /// Can't be combined with `payout_splits`.
+    pub proceeds_target: Option<ProceedsTargetMsg>,
+    /// Assign every minted token a pseudo-random seed for on-chain generative art, read back with the `TokenSeed` query
+    pub generative: Option<bool>,
+    /// Token ids taken out of the mintable pool at instantiate, as with `ReserveTokens`
+    pub reserved_token_ids: Option<Vec<u32>>,
+    /// Mints guaranteed to every whitelist member during the claim window.
+    /// Whatever members leave unclaimed goes to the public once the window closes.
+    pub claim_allocation: Option<u32>,
+    /// Length of the claim window after the whitelist start time, cut short by the whitelist end time
+    pub claim_window_secs: Option<u64>,
+    /// Public mint prices in other denoms, one per denom. Buyers pay `mint_price` or any of these.
+    /// Can't be combined with options that hold proceeds in the minter.
+    /// Fixed at instantiate, `UpdateMintPrice` only changes `mint_price`.
+    pub alt_mint_prices: Option<Vec<Coin>>,
+}
+
+#[cw_serde]
+pub struct PayoutSplitMsg {
+    pub address: String,
+    pub bps: u64,
+}
+
+#[cw_serde]
+pub enum ProceedsTargetMsg {
+    Bank {
+        address: String,
+    },
+    /// Executes `msg` on `contract` with the proceeds as funds
+    Wasm {
+        contract: String,
+        msg: Binary,
+    },
+}
+
+/// Proceeds vest linearly over `duration_secs` from the mint start time.
+/// Nothing can be withdrawn before `cliff_time`.
+#[cw_serde]
+pub struct VestingConfig {
+    pub cliff_time: Timestamp,
+    pub duration_secs: u64,
+}
+
+#[cw_serde]
+pub struct PhaseMsg {
+    pub start_time: Timestamp,
+    pub price: Coin,
+    pub per_address_limit: u32,
+    /// Whitelist contract gating the phase. `None` means the phase is public.
+    pub allowlist: Option<String>,
 }
 pub type VendingMinterCreateMsg = CreateMinterMsg<VendingMinterInitMsgExtension>;
 
@@ -34,6 +151,11 @@ pub struct VendingUpdateParamsExtension {
     pub max_per_address_limit: Option<u32>,
     pub airdrop_mint_price: Option<Coin>,
     pub airdrop_mint_fee_bps: Option<u64>,
+    pub require_explicit_content: Option<bool>,
+    pub max_start_offset_secs: Option<u64>,
+    pub max_mint_price: Option<Coin>,
+    pub max_royalty_bps: Option<u64>,
+    pub max_token_limit: Option<u32>,
 }
 pub type VendingUpdateParamsMsg = UpdateMinterParamsMsg<VendingUpdateParamsExtension>;
 
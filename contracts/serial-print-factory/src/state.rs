@@ -9,6 +9,18 @@ pub struct ParamsExtension {
     pub max_per_address_limit: u32,
     pub airdrop_mint_price: Coin,
     pub airdrop_mint_fee_bps: u64,
+    /// Reject collections that leave `explicit_content` unset
+    pub require_explicit_content: bool,
+    /// How far past the current block time a mint start time can be set
+    pub max_start_offset_secs: u64,
+    /// Highest mint price a minter can be created with. Prices in other denoms aren't capped.
+    pub max_mint_price: Coin,
+    /// Highest royalty share, in basis points. Checked when the minter is created and by the
+    /// minter's `SetTokenRoyalty`. A collection created without a royalty can still add one
+    /// through sg721 `UpdateCollectionInfo`, which doesn't know the factory params.
+    pub max_royalty_bps: u64,
+    /// Largest collection a minter can hold, including ids added by `SetTokenUri` and `ReopenMint`
+    pub max_token_limit: u32,
 }
 
 pub type VendingMinterParams = MinterParams<ParamsExtension>;
@@ -1,33 +1,59 @@
 use crate::error::ContractError;
 use crate::msg::{
-    ConfigResponse, ExecuteMsg, MintCountResponse, MintPriceResponse, MintableNumTokensResponse,
-    QueryMsg, StartTimeResponse,
+    AcceptedDenomsResponse, AdminResponse, AllowlistOnlyResponse, CanBatchMintResponse,
+    ClaimAllocationResponse, ConfigResponse, CurrentPhaseResponse,
+    EffectivePerAddressLimitResponse, EligibilityAtResponse, ExecuteMsg, IsTokenMintableResponse,
+    MintCountResponse, MintEvent, MintHistoryResponse, MintInfoResponse, MintPriceResponse,
+    MintReceiptResponse, MintableNumTokensResponse, NetworkFeesResponse, NextTokenIdResponse,
+    PayoutTotal, PayoutTotalsResponse, PhaseResponse, PhasesResponse, QueryMsg,
+    ReferralRewardsResponse, ReservedTokensResponse, StartTimeResponse, SudoMsg,
+    SupplyConsistencyResponse, TokenSeedResponse, UniqueMintersResponse,
 };
 use crate::state::{
-    Config, ConfigExtension, BASE_TOKEN_ID, CONFIG, MINTABLE_NUM_TOKENS, MINTABLE_TOKEN_IDS,
-    MINTED_NUM_TOKENS, MINTER_ADDRS, MINTING_PAUSED, SG721_ADDRESS, STATUS,
+    Config, ConfigExtension, MintReceipt, PayoutSplit, Phase, ProceedsTarget, AIRDROP_CURSOR,
+    AIRDROP_LEN, AIRDROP_RECIPIENTS, BASE_TOKEN_ID, CLAIMED_ALLOCATIONS,
+    COLLECTION_INSTANTIATE_MSG, CONFIG, CREATOR_RESERVE, DAILY_MINTS, DEPOSITS, EARLY_PUBLIC,
+    FREEZE_REQUESTED_AT, FROZEN_TOKEN_URIS, FULLY_FROZEN, LAST_GLOBAL_MINT, LAST_START_TIME_UPDATE,
+    LAUNCH_CANCELLED, MINTABLE_NUM_TOKENS, MINTABLE_TOKEN_IDS, MINTED_NUM_TOKENS, MINTER_ADDRS,
+    MINTING_PAUSED, MINT_EVENTS, MINT_RECEIPTS, MINT_REFUNDS, NETWORK_FEES, PAID_MINTED,
+    PAYOUT_TOTALS, PHASES, PUBLIC_MINTER_ADDRS, PUBLIC_OPENED, REFERRAL_REWARDS,
+    RESERVED_TOKEN_IDS, REVEALED, SG721_ADDRESS, STATUS, TOKEN_SEEDS, TOTAL_CLAIMED,
+    TOTAL_DEPOSITS, TOTAL_MINTED, UNIQUE_MINTERS, WHITELIST_MINTED, WITHDRAWN,
 };
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    coin, to_binary, Addr, BankMsg, Binary, Coin, CosmosMsg, Deps, DepsMut, Empty, Env, Event,
-    MessageInfo, Order, Reply, ReplyOn, StdError, StdResult, Timestamp, Uint128, WasmMsg,
+    coin, to_binary, Addr, BankMsg, Binary, Coin, CosmosMsg, Decimal, Deps, DepsMut, Empty, Env,
+    Event, HexBinary, MessageInfo, Order, Reply, ReplyOn, StdError, StdResult, Storage,
+    SubMsgResult, Timestamp, Uint128, WasmMsg,
 };
-use cw2::set_contract_version;
+use cw2::{get_contract_version, set_contract_version};
+use cw721::{Cw721QueryMsg, OwnerOfResponse};
 use cw721_base::{Extension, MintMsg};
-use cw_utils::{may_pay, maybe_addr, nonpayable, parse_reply_instantiate_data};
+use cw_storage_plus::Bound;
+use cw_utils::{may_pay, maybe_addr, must_pay, nonpayable, parse_reply_instantiate_data};
 use sg1::checked_fair_burn;
 use sg2::query::Sg2QueryMsg;
-use sg4::{Status, StatusResponse, SudoMsg};
-use sg721::{ExecuteMsg as Sg721ExecuteMsg, InstantiateMsg as Sg721InstantiateMsg};
+use sg4::{Status, StatusResponse};
+use sg721::{
+    ExecuteMsg as Sg721ExecuteMsg, InstantiateMsg as Sg721InstantiateMsg, RoyaltyInfoResponse,
+};
+use sg721_base::msg::{ExtensionMsg, FrozenCollectionInfoResponse, QueryMsg as Sg721QueryMsg};
 use sg_std::math::U64Ext;
-use sg_std::{StargazeMsgWrapper, GENESIS_MINT_START_TIME};
+use sg_std::{
+    create_fund_fairburn_pool_msg, StargazeMsgWrapper, GENESIS_MINT_START_TIME, NATIVE_DENOM,
+};
 use sg_whitelist::msg::{
     ConfigResponse as WhitelistConfigResponse, HasMemberResponse, QueryMsg as WhitelistQueryMsg,
+    NEVER_EXPIRES,
 };
+use sha2::{Digest, Sha256};
 use url::Url;
 
-use serial_print_factory::msg::{ParamsResponse, VendingMinterCreateMsg};
+use serial_print_factory::msg::{
+    ParamsResponse, ProceedsTargetMsg, VendingMinterCreateMsg, VestingConfig,
+};
+use serial_print_factory::state::VendingMinterParams;
 
 pub type Response = cosmwasm_std::Response<StargazeMsgWrapper>;
 pub type SubMsg = cosmwasm_std::SubMsg<StargazeMsgWrapper>;
@@ -37,6 +63,21 @@ const CONTRACT_NAME: &str = "crates.io:sg-minter";
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
 const INSTANTIATE_SG721_REPLY_ID: u64 = 1;
+const PAYOUT_REPLY_ID: u64 = 2;
+
+const MAX_COLLECTION_NAME_LENGTH: usize = 64;
+const MAX_COLLECTION_SYMBOL_LENGTH: usize = 12;
+
+const MAX_MINTS_PER_TX: u32 = 50;
+const MAX_AIRDROP_RECIPIENTS: u32 = 500;
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+const MAX_PHASES: usize = 10;
+const DEFAULT_FREEZE_DELAY_SECS: u64 = 24 * 60 * 60;
+const DEFAULT_DISPLAY_EXPONENT: u32 = 6;
+
+const PAGINATION_DEFAULT_LIMIT: u32 = 25;
+const PAGINATION_MAX_LIMIT: u32 = 100;
+const MAX_MINT_COUNTS_ADDRESSES: usize = 50;
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
@@ -59,6 +100,23 @@ pub fn instantiate(
     // set default status so it can be queried without failing
     STATUS.save(deps.storage, &Status::default())?;
 
+    // An empty collection would be sold out from the start
+    if msg.init_msg.num_tokens == 0 {
+        return Err(ContractError::InvalidNumTokens {});
+    }
+    check_max_token_limit(msg.init_msg.num_tokens, &factory_params)?;
+
+    // The ceiling is set by governance in the factory params, not hardcoded here
+    if msg.init_msg.per_address_limit == 0
+        || msg.init_msg.per_address_limit > factory_params.extension.max_per_address_limit
+    {
+        return Err(ContractError::InvalidPerAddressLimit {
+            max: factory_params.extension.max_per_address_limit,
+            min: 1,
+            got: msg.init_msg.per_address_limit,
+        });
+    }
+
     if !check_dynamic_per_address_limit(
         msg.init_msg.per_address_limit,
         msg.init_msg.num_tokens,
@@ -70,15 +128,57 @@ pub fn instantiate(
             got: msg.init_msg.per_address_limit,
         });
     }
+    match (
+        msg.init_msg.claim_allocation,
+        msg.init_msg.claim_window_secs,
+    ) {
+        (Some(allocation), Some(window_secs)) if allocation > 0 && window_secs > 0 => {}
+        (None, None) => {}
+        _ => return Err(ContractError::InvalidClaimWindow {}),
+    }
+    match (
+        msg.init_msg.early_per_address_limit,
+        msg.init_msg.early_window_secs,
+    ) {
+        (Some(early_limit), Some(window_secs)) if window_secs > 0 => {
+            if early_limit < msg.init_msg.per_address_limit
+                || early_limit > factory_params.extension.max_per_address_limit
+            {
+                return Err(ContractError::InvalidPerAddressLimit {
+                    max: factory_params.extension.max_per_address_limit,
+                    min: msg.init_msg.per_address_limit,
+                    got: early_limit,
+                });
+            }
+        }
+        (None, None) => {}
+        _ => return Err(ContractError::InvalidEarlyWindow {}),
+    }
 
-    // sanitize base token uri
-    let mut base_token_uri = msg.init_msg.base_token_uri.trim().to_string();
-    // Check that base_token_uri is a valid IPFS uri
-    let parsed_token_uri = Url::parse(&base_token_uri)?;
-    if parsed_token_uri.scheme() != "ipfs" {
-        return Err(ContractError::InvalidBaseTokenURI {});
+    // sanitize collection name and symbol
+    let name = msg.collection_params.name.trim().to_string();
+    if name.is_empty() || name.chars().count() > MAX_COLLECTION_NAME_LENGTH {
+        return Err(ContractError::InvalidCollectionName {
+            max_length: MAX_COLLECTION_NAME_LENGTH,
+        });
+    }
+    let symbol = msg.collection_params.symbol.trim().to_string();
+    if symbol.is_empty() || symbol.chars().count() > MAX_COLLECTION_SYMBOL_LENGTH {
+        return Err(ContractError::InvalidCollectionSymbol {
+            max_length: MAX_COLLECTION_SYMBOL_LENGTH,
+        });
+    }
+
+    // A royalty needs a share and a recipient, collections without royalties leave it unset.
+    // Checked here because a failed collection instantiation doesn't fail the minter.
+    if let Some(royalty_info) = &msg.collection_params.info.royalty_info {
+        if royalty_info.share.is_zero() {
+            return Err(ContractError::ZeroRoyaltyShare {});
+        }
+        deps.api.addr_validate(&royalty_info.payment_address)?;
     }
-    base_token_uri = parsed_token_uri.to_string();
+
+    let base_token_uri = parse_base_token_uri(&msg.init_msg.base_token_uri)?;
 
     let genesis_time = Timestamp::from_nanos(GENESIS_MINT_START_TIME);
     // If start time is before genesis time return error
@@ -93,6 +193,11 @@ pub fn instantiate(
             env.block.time,
         ));
     }
+    check_start_time_offset(
+        &env,
+        msg.init_msg.start_time,
+        factory_params.extension.max_start_offset_secs,
+    )?;
 
     // Validate address for the optional whitelist contract
     let whitelist_addr = msg
@@ -100,6 +205,7 @@ pub fn instantiate(
         .whitelist
         .and_then(|w| deps.api.addr_validate(w.as_str()).ok());
 
+    let mut whitelist_config = None;
     if let Some(wl) = whitelist_addr.clone() {
         // check the whitelist exists
         let res: WhitelistConfigResponse = deps
@@ -108,8 +214,18 @@ pub fn instantiate(
         if res.is_active {
             return Err(ContractError::WhitelistAlreadyStarted {});
         }
+        check_whitelist_price(&res.mint_price, &msg.init_msg.mint_price)?;
+        whitelist_config = Some(res);
     }
 
+    let creator = deps
+        .api
+        .addr_validate(&msg.collection_params.info.creator)?;
+    let admin = match msg.init_msg.admin {
+        Some(admin) => deps.api.addr_validate(&admin)?,
+        None => creator.clone(),
+    };
+
     // Use default start trading time if not provided
     let mut collection_info = msg.collection_params.info.clone();
     let offset = factory_params.max_trading_offset_secs;
@@ -129,54 +245,336 @@ pub fn instantiate(
         .start_trading_time
         .or(Some(default_start_time_with_offset));
     collection_info.start_trading_time = start_trading_time;
+    collection_info.creator = creator.to_string();
+
+    let proceeds_target = match msg.init_msg.proceeds_target.clone() {
+        Some(ProceedsTargetMsg::Bank { address }) => Some(ProceedsTarget::Bank {
+            address: deps.api.addr_validate(&address)?,
+        }),
+        Some(ProceedsTargetMsg::Wasm { contract, msg }) => Some(ProceedsTarget::Wasm {
+            contract: deps.api.addr_validate(&contract)?,
+            msg,
+        }),
+        None => None,
+    };
 
     let config = Config {
         factory: factory.clone(),
         collection_code_id: msg.collection_params.code_id,
         extension: ConfigExtension {
-            admin: deps
-                .api
-                .addr_validate(&msg.collection_params.info.creator)?,
+            admin: admin.clone(),
+            creator,
             payment_address: maybe_addr(deps.api, msg.init_msg.payment_address)?,
             base_token_uri,
             num_tokens: msg.init_msg.num_tokens,
             per_address_limit: msg.init_msg.per_address_limit,
             whitelist: whitelist_addr,
             start_time: msg.init_msg.start_time,
+            soft_cap: msg.init_msg.soft_cap,
+            batch_mint_enabled: true,
+            record_history: msg.init_msg.record_history.unwrap_or(false),
+            mint_to_allowlist: msg
+                .init_msg
+                .mint_to_allowlist
+                .map(|addrs| {
+                    addrs
+                        .iter()
+                        .map(|addr| deps.api.addr_validate(addr))
+                        .collect::<StdResult<Vec<_>>>()
+                })
+                .transpose()?,
+            vesting: msg.init_msg.vesting,
+            referral_bps: msg.init_msg.referral_bps,
+            whitelist_total_cap: msg.init_msg.whitelist_total_cap,
+            reroll_fee: msg.init_msg.reroll_fee,
+            allocation_merkle_root: msg.init_msg.allocation_merkle_root,
+            global_mint_interval_secs: msg.init_msg.global_mint_interval_secs,
+            auto_withdraw_threshold: msg.init_msg.auto_withdraw_threshold,
+            allow_contract_recipients: msg.init_msg.allow_contract_recipients.unwrap_or(true),
+            public_on_whitelist_sellout: msg.init_msg.public_on_whitelist_sellout.unwrap_or(false),
+            reveal_deadline: msg.init_msg.reveal_deadline,
+            payout_splits: msg
+                .init_msg
+                .payout_splits
+                .map(|splits| {
+                    splits
+                        .into_iter()
+                        .map(|split| {
+                            Ok(PayoutSplit {
+                                address: deps.api.addr_validate(&split.address)?,
+                                bps: split.bps,
+                            })
+                        })
+                        .collect::<StdResult<Vec<_>>>()
+                })
+                .transpose()?,
+            freeze_delay_secs: msg
+                .init_msg
+                .freeze_delay_secs
+                .unwrap_or(DEFAULT_FREEZE_DELAY_SECS),
+            token_uri_suffix: msg.init_msg.token_uri_suffix,
+            reset_limit_on_public: msg.init_msg.reset_limit_on_public.unwrap_or(false),
+            min_mint_price: msg.init_msg.min_mint_price,
+            display_denom: msg
+                .init_msg
+                .display_denom
+                .unwrap_or_else(|| msg.init_msg.mint_price.denom.clone()),
+            exponent: msg.init_msg.exponent.unwrap_or(DEFAULT_DISPLAY_EXPONENT),
+            block_privileged_referrals: msg.init_msg.block_privileged_referrals.unwrap_or(false),
+            early_per_address_limit: msg.init_msg.early_per_address_limit,
+            early_window_secs: msg.init_msg.early_window_secs,
+            start_time_update_cooldown_secs: msg.init_msg.start_time_update_cooldown_secs,
+            presale_deposits: msg.init_msg.presale_deposits.unwrap_or(false),
+            record_receipts: msg.init_msg.record_receipts.unwrap_or(false),
+            require_height_gate: msg.init_msg.require_height_gate,
+            proceeds_target,
+            generative: msg.init_msg.generative.unwrap_or(false),
+            claim_allocation: msg.init_msg.claim_allocation,
+            claim_window_secs: msg.init_msg.claim_window_secs,
+            alt_mint_prices: msg.init_msg.alt_mint_prices.unwrap_or_default(),
+            daily_mint_limit: msg.init_msg.daily_mint_limit,
         },
         mint_price: msg.init_msg.mint_price,
     };
 
+    let floor = min_mint_price(
+        &factory_params.min_mint_price,
+        &config,
+        &config.mint_price.denom,
+    );
+    if config.mint_price.amount < floor {
+        return Err(ContractError::InsufficientMintPrice {
+            expected: floor.u128(),
+            got: config.mint_price.amount.u128(),
+        });
+    }
+    check_max_mint_price(&config.mint_price, &factory_params.extension.max_mint_price)?;
+
+    if let Some(reroll_fee) = &config.extension.reroll_fee {
+        if reroll_fee.denom != config.mint_price.denom {
+            return Err(ContractError::InvalidDenom {
+                expected: config.mint_price.denom,
+                got: reroll_fee.denom.clone(),
+            });
+        }
+    }
+
+    // The referral share comes out of the creator proceeds, after the network fee
+    if let Some(referral_bps) = config.extension.referral_bps {
+        let max = 10_000u64.checked_sub(factory_params.mint_fee_bps).ok_or(
+            ContractError::InvalidMintFeeBps {
+                got: factory_params.mint_fee_bps,
+            },
+        )?;
+        if referral_bps > max {
+            return Err(ContractError::InvalidReferralBps {
+                max,
+                got: referral_bps,
+            });
+        }
+    }
+
+    if let Some(suffix) = &config.extension.token_uri_suffix {
+        if !suffix.starts_with('.') {
+            return Err(ContractError::InvalidTokenUriSuffix {});
+        }
+    }
+
+    if config.extension.freeze_delay_secs == 0 {
+        return Err(ContractError::InvalidFreezeDelay {});
+    }
+
+    let mut price_denoms = vec![&config.mint_price.denom];
+    for price in &config.extension.alt_mint_prices {
+        if price.amount.is_zero() || price_denoms.contains(&&price.denom) {
+            return Err(ContractError::InvalidAltMintPrice {
+                denom: price.denom.clone(),
+            });
+        }
+        price_denoms.push(&price.denom);
+        let floor = min_mint_price(&factory_params.min_mint_price, &config, &price.denom);
+        if price.amount < floor {
+            return Err(ContractError::InsufficientMintPrice {
+                expected: floor.u128(),
+                got: price.amount.u128(),
+            });
+        }
+        check_max_mint_price(price, &factory_params.extension.max_mint_price)?;
+    }
+    // Held proceeds, refunds and withdrawals are accounted in the mint price denom only
+    if !config.extension.alt_mint_prices.is_empty()
+        && (config.extension.soft_cap.is_some()
+            || config.extension.vesting.is_some()
+            || config.extension.reveal_deadline.is_some()
+            || config.extension.auto_withdraw_threshold.is_some())
+    {
+        return Err(ContractError::AltMintPricesWithHeldProceeds {});
+    }
+
+    if config.extension.payout_splits.is_some() && config.extension.proceeds_target.is_some() {
+        return Err(ContractError::ConflictingProceedsTarget {});
+    }
+    if let Some(splits) = &config.extension.payout_splits {
+        let total_bps = splits
+            .iter()
+            .try_fold(0u64, |total, split| total.checked_add(split.bps));
+        let mut addresses: Vec<&Addr> = splits.iter().map(|split| &split.address).collect();
+        addresses.sort();
+        addresses.dedup();
+        if total_bps != Some(10_000)
+            || splits.iter().any(|split| split.bps == 0)
+            || addresses.len() != splits.len()
+        {
+            return Err(ContractError::InvalidPayoutSplits {});
+        }
+    }
+
+    if let Some(vesting) = &config.extension.vesting {
+        let vesting_end = config
+            .extension
+            .start_time
+            .plus_seconds(vesting.duration_secs);
+        if vesting.duration_secs == 0
+            || vesting.cliff_time < config.extension.start_time
+            || vesting.cliff_time > vesting_end
+        {
+            return Err(ContractError::InvalidVesting {});
+        }
+    }
+
+    // The first `creator_reserve` token ids are minted to the creator in the reply
+    let creator_reserve = msg.init_msg.creator_reserve.unwrap_or(0);
+    if creator_reserve > msg.init_msg.num_tokens {
+        return Err(ContractError::InvalidCreatorReserve {
+            max: msg.init_msg.num_tokens,
+            got: creator_reserve,
+        });
+    }
+    CREATOR_RESERVE.save(deps.storage, &creator_reserve)?;
+    if let Some(wl_config) = &whitelist_config {
+        check_claim_allocation(
+            msg.init_msg.claim_allocation,
+            msg.init_msg.num_tokens - creator_reserve,
+            wl_config,
+        )?;
+    }
+
+    // Reserved ids must be distinct ids of the mintable pool
+    let mut reserved_token_ids = msg.init_msg.reserved_token_ids.clone().unwrap_or_default();
+    reserved_token_ids.sort_unstable();
+    if let Some(ids) = reserved_token_ids.windows(2).find(|ids| ids[0] == ids[1]) {
+        return Err(ContractError::DuplicateTokenId { token_id: ids[0] });
+    }
+    if let Some(token_id) = reserved_token_ids
+        .iter()
+        .find(|token_id| **token_id <= creator_reserve || **token_id > msg.init_msg.num_tokens)
+    {
+        return Err(ContractError::InvalidReservedTokenId {
+            token_id: *token_id,
+        });
+    }
+
+    let phases = msg.init_msg.phases.unwrap_or_default();
+    if phases.len() > MAX_PHASES {
+        return Err(ContractError::TooManyPhases {
+            max: MAX_PHASES,
+            got: phases.len(),
+        });
+    }
+    let phases = phases
+        .into_iter()
+        .map(|phase| {
+            if phase.price.denom != config.mint_price.denom {
+                return Err(ContractError::InvalidDenom {
+                    expected: config.mint_price.denom.clone(),
+                    got: phase.price.denom,
+                });
+            }
+            let min_price =
+                min_mint_price(&factory_params.min_mint_price, &config, &phase.price.denom);
+            if phase.price.amount < min_price {
+                return Err(ContractError::InsufficientMintPrice {
+                    expected: min_price.u128(),
+                    got: phase.price.amount.u128(),
+                });
+            }
+            check_max_mint_price(&phase.price, &factory_params.extension.max_mint_price)?;
+            if phase.per_address_limit == 0
+                || phase.per_address_limit > factory_params.extension.max_per_address_limit
+            {
+                return Err(ContractError::InvalidPerAddressLimit {
+                    max: factory_params.extension.max_per_address_limit,
+                    min: 1,
+                    got: phase.per_address_limit,
+                });
+            }
+            // Check the allowlist is a whitelist contract
+            let allowlist = maybe_addr(deps.api, phase.allowlist)?;
+            if let Some(allowlist) = &allowlist {
+                deps.querier
+                    .query_wasm_smart::<WhitelistConfigResponse>(
+                        allowlist,
+                        &WhitelistQueryMsg::Config {},
+                    )
+                    .map_err(|_| ContractError::InvalidPhaseAllowlist {
+                        allowlist: allowlist.to_string(),
+                    })?;
+            }
+            Ok(Phase {
+                start_time: phase.start_time,
+                price: phase.price,
+                per_address_limit: phase.per_address_limit,
+                allowlist,
+            })
+        })
+        .collect::<Result<Vec<_>, ContractError>>()?;
+    let mut phase_start = config.extension.start_time;
+    for (i, phase) in phases.iter().enumerate() {
+        if phase.start_time < phase_start || (i > 0 && phase.start_time == phase_start) {
+            return Err(ContractError::InvalidPhaseOrder {});
+        }
+        phase_start = phase.start_time;
+    }
+    PHASES.save(deps.storage, &phases)?;
+
     CONFIG.save(deps.storage, &config)?;
-    MINTABLE_NUM_TOKENS.save(deps.storage, &msg.init_msg.num_tokens)?;
+    MINTABLE_NUM_TOKENS.save(deps.storage, &(msg.init_msg.num_tokens - creator_reserve))?;
 
     // Save mintable token ids map
-    for token_id in 1..=msg.init_msg.num_tokens {
+    for token_id in (creator_reserve + 1)..=msg.init_msg.num_tokens {
         MINTABLE_TOKEN_IDS.save(deps.storage, token_id, &true)?;
     }
+    for token_id in reserved_token_ids {
+        consume_mintable_token_id(deps.storage, token_id)?;
+        RESERVED_TOKEN_IDS.save(deps.storage, token_id, &true)?;
+    }
 
     // Submessage to instantiate sg721 contract
-    let submsg = SubMsg {
-        msg: WasmMsg::Instantiate {
-            code_id: msg.collection_params.code_id,
-            msg: to_binary(&Sg721InstantiateMsg {
-                name: msg.collection_params.name.clone(),
-                symbol: msg.collection_params.symbol,
-                minter: env.contract.address.to_string(),
-                collection_info,
-            })?,
-            funds: info.funds,
-            admin: Some(config.extension.admin.to_string()),
-            label: format!("SG721-{}", msg.collection_params.name.trim()),
-        }
-        .into(),
-        id: INSTANTIATE_SG721_REPLY_ID,
-        gas_limit: None,
-        reply_on: ReplyOn::Success,
+    let collection_msg = Sg721InstantiateMsg {
+        name,
+        symbol,
+        minter: env.contract.address.to_string(),
+        collection_info,
     };
+    COLLECTION_INSTANTIATE_MSG.save(deps.storage, &collection_msg)?;
+    let submsg = instantiate_collection_submsg(
+        msg.collection_params.code_id,
+        &collection_msg,
+        info.funds,
+        &config.extension.admin,
+    )?;
 
     BASE_TOKEN_ID.save(deps.storage, &0)?;
     MINTED_NUM_TOKENS.save(deps.storage, &0)?;
+    TOTAL_MINTED.save(deps.storage, &0)?;
+    PAID_MINTED.save(deps.storage, &0)?;
+    WITHDRAWN.save(deps.storage, &Uint128::zero())?;
+    TOTAL_DEPOSITS.save(deps.storage, &Uint128::zero())?;
+    TOTAL_CLAIMED.save(deps.storage, &0)?;
+    UNIQUE_MINTERS.save(deps.storage, &0)?;
+    WHITELIST_MINTED.save(deps.storage, &0)?;
+    EARLY_PUBLIC.save(deps.storage, &false)?;
+    REVEALED.save(deps.storage, &false)?;
     MINTING_PAUSED.save(deps.storage, &false)?;
 
     Ok(Response::new()
@@ -187,6 +585,29 @@ pub fn instantiate(
         .add_submessage(submsg))
 }
 
+// A failed instantiation is reported in the reply instead of failing the minter,
+// so the admin can retry it with `RetryCollectionInstantiate`
+fn instantiate_collection_submsg(
+    code_id: u64,
+    collection_msg: &Sg721InstantiateMsg,
+    funds: Vec<Coin>,
+    admin: &Addr,
+) -> StdResult<SubMsg> {
+    Ok(SubMsg {
+        msg: WasmMsg::Instantiate {
+            code_id,
+            msg: to_binary(collection_msg)?,
+            funds,
+            admin: Some(admin.to_string()),
+            label: format!("SG721-{}", collection_msg.name),
+        }
+        .into(),
+        id: INSTANTIATE_SG721_REPLY_ID,
+        gas_limit: None,
+        reply_on: ReplyOn::Always,
+    })
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn execute(
     deps: DepsMut,
@@ -195,7 +616,10 @@ pub fn execute(
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
     match msg {
-        ExecuteMsg::Mint {} => execute_mint_sender(deps, env, info),
+        ExecuteMsg::Mint {
+            referral,
+            recipient,
+        } => execute_mint_sender(deps, env, info, referral, recipient),
         ExecuteMsg::Purge {} => execute_purge(deps, env, info),
         ExecuteMsg::UpdateMintPrice { price } => execute_update_mint_price(deps, env, info, price),
         ExecuteMsg::UpdateStartTime(time) => execute_update_start_time(deps, env, info, time),
@@ -206,6 +630,8 @@ pub fn execute(
             execute_update_per_address_limit(deps, env, info, per_address_limit)
         }
         ExecuteMsg::MintTo { recipient } => execute_mint_to(deps, env, info, recipient),
+        ExecuteMsg::LoadAirdrop { recipients } => execute_load_airdrop(deps, info, recipients),
+        ExecuteMsg::ContinueAirdrop { count } => execute_continue_airdrop(deps, env, info, count),
         ExecuteMsg::MintFor {
             token_id,
             recipient,
@@ -218,6 +644,39 @@ pub fn execute(
             execute_set_token_uri(deps, env, info, uri, num_tokens)
         }
         ExecuteMsg::SetMintingPause { pause } => execute_set_minting_pause(deps, info, pause),
+        ExecuteMsg::ReopenMint {
+            additional_tokens,
+            new_base_token_uri,
+        } => execute_reopen_mint(deps, info, additional_tokens, new_base_token_uri),
+        ExecuteMsg::Withdraw {} => execute_withdraw(deps, env, info),
+        ExecuteMsg::BatchMint { num_mints } => execute_batch_mint(deps, env, info, num_mints),
+        ExecuteMsg::Deposit {} => execute_deposit(deps, env, info),
+        ExecuteMsg::ClaimMint {} => execute_claim_mint(deps, env, info),
+        ExecuteMsg::RefundDeposit {} => execute_refund_deposit(deps, info),
+        ExecuteMsg::CancelLaunch {} => execute_cancel_launch(deps, info),
+        ExecuteMsg::Reroll { token_id } => execute_reroll(deps, env, info, token_id),
+        ExecuteMsg::ReserveTokens { token_ids } => execute_reserve_tokens(deps, info, token_ids),
+        ExecuteMsg::ReleaseTokens { token_ids } => execute_release_tokens(deps, info, token_ids),
+        ExecuteMsg::Reveal {} => execute_reveal(deps, env, info),
+        ExecuteMsg::ClaimRefund { token_id } => execute_claim_refund(deps, env, info, token_id),
+        ExecuteMsg::SetTokenRoyalty {
+            token_id,
+            share,
+            recipient,
+        } => execute_set_token_royalty(deps, info, token_id, share, recipient),
+        ExecuteMsg::FreezeTokenUri { token_id } => execute_freeze_token_uri(deps, info, token_id),
+        ExecuteMsg::RequestFreeze {} => execute_request_freeze(deps, env, info),
+        ExecuteMsg::ConfirmFreeze {} => execute_confirm_freeze(deps, env, info),
+        ExecuteMsg::CancelFreeze {} => execute_cancel_freeze(deps, info),
+        ExecuteMsg::MerkleMint { allocation, proof } => {
+            execute_merkle_mint(deps, env, info, allocation, proof)
+        }
+        ExecuteMsg::RetryCollectionInstantiate { sg721_code_id } => {
+            execute_retry_collection_instantiate(deps, info, sg721_code_id)
+        }
+        ExecuteMsg::SetBatchMintEnabled { enabled } => {
+            execute_set_batch_mint_enabled(deps, info, enabled)
+        }
     }
 }
 
@@ -241,6 +700,12 @@ pub fn execute_purge(
     for key in keys {
         MINTER_ADDRS.remove(deps.storage, &key?);
     }
+    let keys = PUBLIC_MINTER_ADDRS
+        .keys(deps.storage, None, None, Order::Ascending)
+        .collect::<Vec<_>>();
+    for key in keys {
+        PUBLIC_MINTER_ADDRS.remove(deps.storage, &key?);
+    }
 
     Ok(Response::new()
         .add_attribute("action", "purge")
@@ -285,6 +750,12 @@ pub fn execute_set_whitelist(
     if res.is_active {
         return Err(ContractError::WhitelistAlreadyStarted {});
     }
+    check_whitelist_price(&res.mint_price, &config.mint_price)?;
+    check_claim_allocation(
+        config.extension.claim_allocation,
+        MINTABLE_NUM_TOKENS.load(deps.storage)?,
+        &res,
+    )?;
     CONFIG.save(deps.storage, &config)?;
 
     Ok(Response::default()
@@ -292,265 +763,713 @@ pub fn execute_set_whitelist(
         .add_attribute("whitelist", whitelist.to_string()))
 }
 
+// Every member must be able to claim their full allocation
+fn check_claim_allocation(
+    claim_allocation: Option<u32>,
+    mintable_num_tokens: u32,
+    wl_config: &WhitelistConfigResponse,
+) -> Result<(), ContractError> {
+    if let Some(allocation) = claim_allocation {
+        let reserve_fits = allocation
+            .checked_mul(wl_config.num_members)
+            .is_some_and(|reserve| reserve <= mintable_num_tokens);
+        if allocation > wl_config.per_address_limit || !reserve_fits {
+            return Err(ContractError::InvalidClaimAllocation { allocation });
+        }
+    }
+    Ok(())
+}
+
+fn check_max_token_limit(
+    num_tokens: u32,
+    factory_params: &VendingMinterParams,
+) -> Result<(), ContractError> {
+    let max = factory_params.extension.max_token_limit;
+    if num_tokens > max {
+        return Err(ContractError::MaxTokenLimitExceeded { max });
+    }
+    Ok(())
+}
+
+// Catch prices entered with too many zeros. The cap only applies in its own denom.
+fn check_max_mint_price(price: &Coin, max: &Coin) -> Result<(), ContractError> {
+    if price.denom == max.denom && price.amount > max.amount {
+        return Err(ContractError::MintPriceTooHigh {
+            max: max.amount.u128(),
+            got: price.amount.u128(),
+        });
+    }
+    Ok(())
+}
+
+// Whitelist members should never pay more than the public
+fn check_whitelist_price(whitelist_price: &Coin, public_price: &Coin) -> Result<(), ContractError> {
+    if whitelist_price.denom != public_price.denom {
+        return Err(ContractError::InvalidDenom {
+            expected: public_price.denom.clone(),
+            got: whitelist_price.denom.clone(),
+        });
+    }
+    if whitelist_price.amount > public_price.amount {
+        return Err(ContractError::WhitelistPriceAbovePublicPrice {
+            whitelist_price: whitelist_price.clone(),
+            public_price: public_price.clone(),
+        });
+    }
+    Ok(())
+}
+
 pub fn execute_mint_sender(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
+    referral: Option<String>,
+    recipient: Option<String>,
 ) -> Result<Response, ContractError> {
-    let config = CONFIG.load(deps.storage)?;
     let action = "mint_sender";
+    let referral = maybe_addr(deps.api, referral)?;
+    if let Some(referrer) = &referral {
+        check_referral(deps.as_ref(), &env, &info.sender, referrer)?;
+    }
+    let recipient = maybe_addr(deps.api, recipient)?;
+    check_sender_can_mint(deps.as_ref(), &env, &info.sender, 1)?;
+    _execute_mint(
+        deps,
+        env,
+        info,
+        action,
+        false,
+        recipient,
+        vec![None],
+        referral,
+    )
+}
 
-    // If there is no active whitelist right now, check public mint
-    // Check if after start_time
-    if is_public_mint(deps.as_ref(), &info)? && (env.block.time < config.extension.start_time) {
+// Buyers can't refer themselves to get the referral share back as a discount.
+// Referrals to the minter or its admin are rejected as well when configured.
+fn check_referral(
+    deps: Deps,
+    env: &Env,
+    sender: &Addr,
+    referrer: &Addr,
+) -> Result<(), ContractError> {
+    if referrer == sender {
+        return Err(ContractError::InvalidReferral {
+            referral: referrer.to_string(),
+        });
+    }
+    let config = CONFIG.load(deps.storage)?;
+    if config.extension.block_privileged_referrals
+        && (*referrer == env.contract.address || *referrer == config.extension.admin)
+    {
+        return Err(ContractError::InvalidReferral {
+            referral: referrer.to_string(),
+        });
+    }
+    Ok(())
+}
+
+// Members of a Merkle allowlist mint up to the allocation encoded in their leaf.
+// The allocation replaces the per-address limit, consumed mints are tracked in `MINTER_ADDRS`.
+pub fn execute_merkle_mint(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    allocation: u32,
+    proof: Vec<HexBinary>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let action = "merkle_mint";
+
+    let root = config
+        .extension
+        .allocation_merkle_root
+        .ok_or(ContractError::MerkleDisabled {})?;
+    if env.block.time < config.extension.start_time {
         return Err(ContractError::BeforeMintStartTime {});
     }
 
-    // Check if already minted max per address limit
-    let mint_count = mint_count(deps.as_ref(), &info)?;
-    if mint_count >= config.extension.per_address_limit {
+    let leaf = format!("{}:{}", info.sender, allocation);
+    if !verify_merkle_proof(leaf.as_bytes(), &proof, &root) {
+        return Err(ContractError::InvalidMerkleProof {});
+    }
+    if mint_count(deps.as_ref(), &info.sender)? >= allocation {
         return Err(ContractError::MaxPerAddressLimitExceeded {});
     }
 
-    _execute_mint(deps, env, info, action, false, None, None)
+    _execute_mint(deps, env, info, action, false, None, vec![None], None)
 }
 
-// Check if a whitelist exists and not ended
-// Sender has to be whitelisted to mint
-fn is_public_mint(deps: Deps, info: &MessageInfo) -> Result<bool, ContractError> {
-    let config = CONFIG.load(deps.storage)?;
-
-    // If there is no whitelist, there's only a public mint
-    if config.extension.whitelist.is_none() {
-        return Ok(true);
-    }
+// Hash the leaf and fold in each proof element, hashing sibling pairs in sorted order
+fn verify_merkle_proof(leaf: &[u8], proof: &[HexBinary], root: &HexBinary) -> bool {
+    let hash = proof
+        .iter()
+        .fold(Sha256::digest(leaf).to_vec(), |hash, sibling| {
+            let (first, second) = if hash.as_slice() <= sibling.as_slice() {
+                (hash.as_slice(), sibling.as_slice())
+            } else {
+                (sibling.as_slice(), hash.as_slice())
+            };
+            Sha256::new()
+                .chain_update(first)
+                .chain_update(second)
+                .finalize()
+                .to_vec()
+        });
+    hash.as_slice() == root.as_slice()
+}
 
-    let whitelist = config.extension.whitelist.unwrap();
+// Seed of a generative token, from the block data and the token id.
+// Block data is known to validators, so this is not safe for anything of value.
+fn token_seed(env: &Env, token_id: u32) -> HexBinary {
+    let tx_index = env.transaction.as_ref().map_or(0, |tx| tx.index);
+    Sha256::new()
+        .chain_update(env.contract.address.as_bytes())
+        .chain_update(env.block.height.to_be_bytes())
+        .chain_update(env.block.time.nanos().to_be_bytes())
+        .chain_update(tx_index.to_be_bytes())
+        .chain_update(token_id.to_be_bytes())
+        .finalize()
+        .to_vec()
+        .into()
+}
 
-    let wl_config: WhitelistConfigResponse = deps
-        .querier
-        .query_wasm_smart(whitelist.clone(), &WhitelistQueryMsg::Config {})?;
+pub fn execute_batch_mint(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    num_mints: u32,
+) -> Result<Response, ContractError> {
+    check_batch_mint(deps.as_ref(), &env, &info.sender, num_mints)?;
+    _execute_mint(
+        deps,
+        env,
+        info,
+        "batch_mint",
+        false,
+        None,
+        vec![None; num_mints as usize],
+        None,
+    )
+}
 
-    if !wl_config.is_active {
-        return Ok(true);
+// Check `sender` can batch mint `num_mints` tokens right now
+fn check_batch_mint(
+    deps: Deps,
+    env: &Env,
+    sender: &Addr,
+    num_mints: u32,
+) -> Result<(), ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if !config.extension.batch_mint_enabled {
+        return Err(ContractError::BatchMintDisabled {});
     }
-
-    let res: HasMemberResponse = deps.querier.query_wasm_smart(
-        whitelist,
-        &WhitelistQueryMsg::HasMember {
-            member: info.sender.to_string(),
-        },
-    )?;
-    if !res.has_member {
-        return Err(ContractError::NotWhitelisted {
-            addr: info.sender.to_string(),
+    if num_mints == 0 {
+        return Err(ContractError::InvalidNumMints {});
+    }
+    if num_mints > MAX_MINTS_PER_TX {
+        return Err(ContractError::TooManyMintsPerTx {
+            max: MAX_MINTS_PER_TX,
         });
     }
-
-    // Check wl per address limit
-    let mint_count = mint_count(deps, info)?;
-    if mint_count >= wl_config.per_address_limit {
-        return Err(ContractError::MaxPerAddressLimitExceeded {});
+    let remaining = MINTABLE_NUM_TOKENS.load(deps.storage)?;
+    if num_mints > remaining {
+        return Err(ContractError::NotEnoughTokensRemaining {
+            requested: num_mints,
+            remaining,
+        });
     }
 
-    Ok(false)
+    check_sender_can_mint(deps, env, sender, num_mints)
 }
 
-pub fn execute_mint_to(
+pub fn execute_deposit(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
-    recipient: String,
 ) -> Result<Response, ContractError> {
-    let recipient = deps.api.addr_validate(&recipient)?;
     let config = CONFIG.load(deps.storage)?;
-    let action = "mint_to";
-
-    // Check only admin
-    if info.sender != config.extension.admin {
-        return Err(ContractError::Unauthorized(
-            "Sender is not an admin".to_owned(),
-        ));
+    if !config.extension.presale_deposits {
+        return Err(ContractError::PresaleDepositsDisabled {});
+    }
+    check_launch_not_cancelled(deps.storage)?;
+    if env.block.time >= config.extension.start_time {
+        return Err(ContractError::AlreadyStarted {});
     }
 
-    _execute_mint(deps, env, info, action, true, Some(recipient), None)
+    let amount = must_pay(&info, &config.mint_price.denom)?;
+    let deposit = DEPOSITS.update(deps.storage, &info.sender, |deposit| -> StdResult<_> {
+        Ok(deposit.unwrap_or_default() + amount)
+    })?;
+    TOTAL_DEPOSITS.update(deps.storage, |total| -> StdResult<_> { Ok(total + amount) })?;
+
+    Ok(Response::new()
+        .add_attribute("action", "deposit")
+        .add_attribute("sender", info.sender)
+        .add_attribute("amount", amount)
+        .add_attribute("deposit", deposit))
 }
 
-pub fn execute_mint_for(
-    deps: DepsMut,
+// Mints one token paid for by the sender's deposit, and refunds what is left of it
+pub fn execute_claim_mint(
+    mut deps: DepsMut,
     env: Env,
     info: MessageInfo,
-    token_id: u32,
-    recipient: String,
 ) -> Result<Response, ContractError> {
-    let recipient = deps.api.addr_validate(&recipient)?;
+    nonpayable(&info)?;
+    let deposit = DEPOSITS
+        .may_load(deps.storage, &info.sender)?
+        .ok_or(ContractError::NoDeposit {})?;
+    let price = mint_price(deps.as_ref(), &env, false)?;
+    if deposit < price.amount {
+        return Err(ContractError::NotEnoughFunds {});
+    }
+    check_sender_can_mint(deps.as_ref(), &env, &info.sender, 1)?;
+
+    // The deposit is already held by the minter, spend it as if it was sent with the mint
+    DEPOSITS.remove(deps.storage, &info.sender);
+    TOTAL_DEPOSITS.update(deps.storage, |total| -> StdResult<_> {
+        Ok(total - price.amount)
+    })?;
+    let mint_info = MessageInfo {
+        sender: info.sender.clone(),
+        funds: vec![price.clone()],
+    };
+    let mut res = _execute_mint(
+        deps.branch(),
+        env,
+        mint_info,
+        "claim_mint",
+        false,
+        None,
+        vec![None],
+        None,
+    )?;
+
+    let excess = deposit - price.amount;
+    if !excess.is_zero() {
+        TOTAL_DEPOSITS.update(deps.storage, |total| -> StdResult<_> { Ok(total - excess) })?;
+        res = res.add_message(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: vec![coin(excess.u128(), &price.denom)],
+        });
+    }
+    Ok(res.add_attribute("refunded", excess))
+}
+
+// A deposit belongs to the depositor until it is claimed, so it can be refunded at any time.
+// After the start this is the way out when a claim would fail: sold out, not eligible, or a raised price.
+pub fn execute_refund_deposit(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    nonpayable(&info)?;
     let config = CONFIG.load(deps.storage)?;
-    let action = "mint_for";
+    let deposit = DEPOSITS
+        .may_load(deps.storage, &info.sender)?
+        .ok_or(ContractError::NoDeposit {})?;
+    DEPOSITS.remove(deps.storage, &info.sender);
+    TOTAL_DEPOSITS.update(deps.storage, |total| -> StdResult<_> {
+        Ok(total - deposit)
+    })?;
+
+    Ok(Response::new()
+        .add_attribute("action", "refund_deposit")
+        .add_attribute("sender", info.sender.to_string())
+        .add_attribute("amount", deposit)
+        .add_message(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: vec![coin(deposit.u128(), &config.mint_price.denom)],
+        }))
+}
 
+// Closes the minter for good before the first mint.
+// Depositors pull their deposits back with `RefundDeposit`.
+pub fn execute_cancel_launch(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    nonpayable(&info)?;
+    let config = CONFIG.load(deps.storage)?;
     // Check only admin
     if info.sender != config.extension.admin {
         return Err(ContractError::Unauthorized(
             "Sender is not an admin".to_owned(),
         ));
     }
+    check_launch_not_cancelled(deps.storage)?;
+    // The creator reserve is minted with the collection
+    if TOTAL_MINTED.load(deps.storage)? > 0 {
+        return Err(ContractError::CancelAfterMint {});
+    }
+    LAUNCH_CANCELLED.save(deps.storage, &true)?;
 
-    _execute_mint(
-        deps,
-        env,
-        info,
-        action,
-        true,
-        Some(recipient),
-        Some(token_id),
-    )
+    Ok(Response::new()
+        .add_event(Event::new("launch_cancelled").add_attribute("sender", &info.sender))
+        .add_attribute("action", "cancel_launch")
+        .add_attribute("sender", info.sender))
 }
 
-// Generalize checks and mint message creation
-// mint -> _execute_mint(recipient: None, token_id: None)
-// mint_to(recipient: "friend") -> _execute_mint(Some(recipient), token_id: None)
-// mint_for(recipient: "friend2", token_id: 420) -> _execute_mint(recipient, token_id)
-fn _execute_mint(
-    deps: DepsMut,
-    _env: Env,
-    info: MessageInfo,
-    action: &str,
-    is_admin: bool,
-    recipient: Option<Addr>,
-    token_id: Option<u32>,
-) -> Result<Response, ContractError> {
-    // Check mintable
-    let minting_paused = MINTING_PAUSED.load(deps.storage)?;
-    if minting_paused == true {
+// Check the sender is allowed to mint `num_mints` more tokens right now
+fn check_sender_can_mint(
+    deps: Deps,
+    env: &Env,
+    sender: &Addr,
+    num_mints: u32,
+) -> Result<(), ContractError> {
+    check_eligibility(deps, env.block.time, env.block.height, sender, num_mints)
+}
+
+// Check minting is open to anyone at `height`, before looking at the sender
+fn check_mint_open(deps: Deps, height: u64) -> Result<(), ContractError> {
+    check_not_fully_frozen(deps.storage)?;
+    check_launch_not_cancelled(deps.storage)?;
+    if MINTING_PAUSED.load(deps.storage)? {
         return Err(ContractError::MintingPaused {});
     }
-
-    let mintable_num_tokens = MINTABLE_NUM_TOKENS.load(deps.storage)?;
-    if mintable_num_tokens == 0 {
-        return Err(ContractError::SoldOut {});
+    let config = CONFIG.load(deps.storage)?;
+    if let Some(gate) = config.extension.require_height_gate {
+        if height < gate {
+            return Err(ContractError::BeforeMintStartHeight { height: gate });
+        }
     }
+    Ok(())
+}
 
+// Check `sender` could mint `num_mints` tokens at `time` and `height`, with the current mint counts.
+// The mint entry points and the eligibility queries all go through here so they can't disagree.
+fn check_eligibility(
+    deps: Deps,
+    time: Timestamp,
+    height: u64,
+    sender: &Addr,
+    num_mints: u32,
+) -> Result<(), ContractError> {
+    check_mint_open(deps, height)?;
     let config = CONFIG.load(deps.storage)?;
-    let sg721_address = SG721_ADDRESS.load(deps.storage)?;
+    let mint_count = mint_count(deps, sender)?;
 
-    let recipient_addr = match recipient {
-        Some(some_recipient) => some_recipient,
-        None => info.sender.clone(),
-    };
+    if let Some(daily_limit) = config.extension.daily_mint_limit {
+        let day = time.seconds() / SECONDS_PER_DAY;
+        let minted_today = match DAILY_MINTS.may_load(deps.storage, sender)? {
+            Some((mint_day, count)) if mint_day == day => count,
+            _ => 0,
+        };
+        if add_mints(minted_today, num_mints)? > daily_limit {
+            return Err(ContractError::DailyLimitExceeded { limit: daily_limit });
+        }
+    }
 
-    let mint_price: Coin = mint_price(deps.as_ref(), is_admin)?;
-    // Exact payment only accepted
-    let payment = may_pay(&info, &config.mint_price.denom)?;
-    if payment != mint_price.amount {
-        return Err(ContractError::IncorrectPaymentAmount(
-            coin(payment.u128(), &config.mint_price.denom),
-            mint_price,
-        ));
+    // Phases replace the whitelist / public mint checks
+    let phases = PHASES.load(deps.storage)?;
+    if !phases.is_empty() {
+        let phase = match active_phase(&phases, time) {
+            Some(phase) => phase,
+            None => return Err(ContractError::BeforeMintStartTime {}),
+        };
+        if let Some(allowlist) = &phase.allowlist {
+            let res: HasMemberResponse = deps.querier.query_wasm_smart(
+                allowlist,
+                &WhitelistQueryMsg::HasMember {
+                    member: sender.to_string(),
+                },
+            )?;
+            if !res.has_member {
+                return Err(ContractError::NotWhitelisted {
+                    addr: sender.to_string(),
+                });
+            }
+        }
+        if add_mints(mint_count, num_mints)? > phase.per_address_limit {
+            return Err(ContractError::MaxPerAddressLimitExceeded {});
+        }
+        return Ok(());
     }
 
-    let mut res = Response::new();
+    // If there is no active whitelist right now, check public mint
+    // Check if after start_time
+    let is_public = is_public_mint(deps, sender, num_mints, time)?;
+    if is_public && time < config.extension.start_time && !EARLY_PUBLIC.load(deps.storage)? {
+        return Err(ContractError::BeforeMintStartTime {});
+    }
 
-    let factory: ParamsResponse = deps
+    // During the claim window members mint up to their allocation,
+    // and public mints can't eat into what members haven't claimed yet
+    if let Some(window) = active_claim_window(deps, &config, time)? {
+        if is_public {
+            let mintable_num_tokens = MINTABLE_NUM_TOKENS.load(deps.storage)?;
+            let available = mintable_num_tokens.saturating_sub(unclaimed_reserve(deps, &window)?);
+            if num_mints > available {
+                return Err(ContractError::ReservedForClaims { available });
+            }
+        } else {
+            let claimed = CLAIMED_ALLOCATIONS
+                .may_load(deps.storage, sender)?
+                .unwrap_or_default();
+            if add_mints(claimed, num_mints)? > window.allocation {
+                return Err(ContractError::ClaimAllocationExceeded {
+                    allocation: window.allocation,
+                });
+            }
+        }
+    }
+
+    // Check whitelist sales stay under the cap
+    if let (false, Some(cap)) = (is_public, config.extension.whitelist_total_cap) {
+        if add_mints(WHITELIST_MINTED.load(deps.storage)?, num_mints)? > cap {
+            return Err(ContractError::WhitelistCapReached { cap });
+        }
+    }
+
+    // Check if already minted max per address limit
+    let mint_count = limit_mint_count(deps, sender, is_public)?;
+    if add_mints(mint_count, num_mints)? > per_address_limit(&config, time) {
+        return Err(ContractError::MaxPerAddressLimitExceeded {});
+    }
+
+    check_tokens_left(deps.storage, num_mints)
+}
+
+fn check_tokens_left(storage: &dyn Storage, num_mints: u32) -> Result<(), ContractError> {
+    let mintable_num_tokens = MINTABLE_NUM_TOKENS.load(storage)?;
+    if mintable_num_tokens < num_mints {
+        // Ids left behind a sold out counter mean storage is out of sync
+        if mintable_num_tokens == 0
+            && MINTABLE_TOKEN_IDS
+                .keys(storage, None, None, Order::Ascending)
+                .next()
+                .is_some()
+        {
+            return Err(ContractError::InconsistentState {});
+        }
+        return Err(ContractError::SoldOut {});
+    }
+    Ok(())
+}
+
+// The active phase is the last one that has started
+fn active_phase(phases: &[Phase], now: Timestamp) -> Option<&Phase> {
+    phases.iter().rev().find(|phase| phase.start_time <= now)
+}
+
+// Check if a whitelist exists and is active at `now`
+// Sender has to be whitelisted to mint
+fn is_public_mint(
+    deps: Deps,
+    sender: &Addr,
+    num_mints: u32,
+    now: Timestamp,
+) -> Result<bool, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    // If there is no whitelist, there's only a public mint
+    if config.extension.whitelist.is_none() || EARLY_PUBLIC.load(deps.storage)? {
+        return Ok(true);
+    }
+
+    let whitelist = config.extension.whitelist.unwrap();
+
+    let wl_config: WhitelistConfigResponse = deps
         .querier
-        .query_wasm_smart(config.factory, &Sg2QueryMsg::Params {})?;
-    let factory_params = factory.params;
+        .query_wasm_smart(whitelist.clone(), &WhitelistQueryMsg::Config {})?;
 
-    // Create network fee msgs
-    let mint_fee = if is_admin {
-        factory_params
-            .extension
-            .airdrop_mint_fee_bps
-            .bps_to_decimal()
-    } else {
-        factory_params.mint_fee_bps.bps_to_decimal()
-    };
-    let network_fee = mint_price.amount * mint_fee;
-    checked_fair_burn(&info, network_fee.u128(), None, &mut res)?;
+    if !whitelist_active_at(&wl_config, now) {
+        return Ok(true);
+    }
 
-    let minted_num_tokens = MINTED_NUM_TOKENS.load(deps.storage)?;
-    let mintable_token_id = match token_id {
-        Some(token_id) => {
-            if token_id == 0 || token_id > config.extension.num_tokens - minted_num_tokens {
-                return Err(ContractError::InvalidTokenId {});
-            }
-            // If token_id not on mintable map, throw err
-            if !MINTABLE_TOKEN_IDS.has(deps.storage, token_id) {
-                return Err(ContractError::TokenIdAlreadySold { token_id });
-            }
-            token_id
-        }
-        None => {
-            let mintable_tokens_result: StdResult<Vec<u32>> = MINTABLE_TOKEN_IDS
-                .keys(deps.storage, None, None, Order::Ascending)
-                .take(1)
-                .collect();
-            let mintable_tokens = mintable_tokens_result?;
-            if mintable_tokens.is_empty() {
-                return Err(ContractError::SoldOut {});
-            }
-            mintable_tokens[0]
+    let res: HasMemberResponse = deps.querier.query_wasm_smart(
+        whitelist,
+        &WhitelistQueryMsg::HasMember {
+            member: sender.to_string(),
+        },
+    )?;
+    if !res.has_member {
+        // Point buyers at the allowlist instead of a public sale that will never open
+        if wl_config.end_time == NEVER_EXPIRES {
+            return Err(ContractError::AllowlistOnly {
+                addr: sender.to_string(),
+            });
         }
-    };
+        return Err(ContractError::NotWhitelisted {
+            addr: sender.to_string(),
+        });
+    }
 
-    let base_token_id = BASE_TOKEN_ID.load(deps.storage)?;
-    // Create mint msgs
-    let mint_msg = Sg721ExecuteMsg::<Extension, Empty>::Mint(MintMsg::<Extension> {
-        token_id: (mintable_token_id + base_token_id).to_string(),
-        owner: recipient_addr.to_string(),
-        token_uri: Some(format!(
-            "{}/{}",
-            config.extension.base_token_uri, mintable_token_id
-        )),
-        extension: None,
-    });
-    let msg = CosmosMsg::Wasm(WasmMsg::Execute {
-        contract_addr: sg721_address.to_string(),
-        msg: to_binary(&mint_msg)?,
-        funds: vec![],
-    });
-    res = res.add_message(msg);
+    // Check wl per address limit
+    let mint_count = mint_count(deps, sender)?;
+    if add_mints(mint_count, num_mints)? > wl_config.per_address_limit {
+        return Err(ContractError::MaxPerAddressLimitExceeded {});
+    }
+
+    Ok(false)
+}
 
-    // Remove mintable token id from map
-    MINTABLE_TOKEN_IDS.remove(deps.storage, mintable_token_id);
+pub fn execute_mint_to(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    recipient: String,
+) -> Result<Response, ContractError> {
+    let recipient = deps.api.addr_validate(&recipient)?;
+    let config = CONFIG.load(deps.storage)?;
+    let action = "mint_to";
 
-    let mintable_num_tokens = MINTABLE_NUM_TOKENS.load(deps.storage)?;
-    // Decrement mintable num tokens
-    MINTABLE_NUM_TOKENS.save(deps.storage, &(mintable_num_tokens - 1))?;
-    // Save the new mint count for the sender's address
-    let new_mint_count = mint_count(deps.as_ref(), &info)? + 1;
-    MINTER_ADDRS.save(deps.storage, &info.sender, &new_mint_count)?;
+    // Check only admin
+    if info.sender != config.extension.admin {
+        return Err(ContractError::Unauthorized(
+            "Sender is not an admin".to_owned(),
+        ));
+    }
 
-    let seller_amount = if !is_admin {
-        let amount = mint_price.amount - network_fee;
-        let payment_address = config.extension.payment_address;
-        let seller = config.extension.admin;
-        let msg = BankMsg::Send {
-            to_address: payment_address.unwrap_or(seller).to_string(),
-            amount: vec![coin(amount.u128(), mint_price.denom)],
-        };
-        res = res.add_message(msg);
-        amount
-    } else {
-        Uint128::zero()
+    check_mint_to_allowlist(&config, &recipient)?;
+
+    _execute_mint(
+        deps,
+        env,
+        info,
+        action,
+        true,
+        Some(recipient),
+        vec![None],
+        None,
+    )
+}
+
+pub fn execute_load_airdrop(
+    deps: DepsMut,
+    info: MessageInfo,
+    recipients: Vec<String>,
+) -> Result<Response, ContractError> {
+    nonpayable(&info)?;
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.extension.admin {
+        return Err(ContractError::Unauthorized(
+            "Sender is not an admin".to_owned(),
+        ));
+    }
+
+    if recipients.len() > MAX_AIRDROP_RECIPIENTS as usize {
+        return Err(ContractError::TooManyAirdropRecipients {
+            max: MAX_AIRDROP_RECIPIENTS,
+        });
+    }
+
+    // A new list can only replace a finished airdrop
+    let len = AIRDROP_LEN.may_load(deps.storage)?.unwrap_or_default();
+    let cursor = AIRDROP_CURSOR.may_load(deps.storage)?.unwrap_or_default();
+    if cursor < len {
+        return Err(ContractError::AirdropInProgress {
+            remaining: len - cursor,
+        });
+    }
+
+    for (index, recipient) in recipients.iter().enumerate() {
+        let recipient = deps.api.addr_validate(recipient)?;
+        check_mint_to_allowlist(&config, &recipient)?;
+        AIRDROP_RECIPIENTS.save(deps.storage, index as u32, &recipient)?;
+    }
+    AIRDROP_LEN.save(deps.storage, &(recipients.len() as u32))?;
+    AIRDROP_CURSOR.save(deps.storage, &0)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "load_airdrop")
+        .add_attribute("sender", info.sender)
+        .add_attribute("num_recipients", recipients.len().to_string()))
+}
+
+// Each recipient is minted through `_execute_mint` as an admin mint,
+// with the airdrop price of that single mint as its funds
+pub fn execute_continue_airdrop(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    count: u32,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.extension.admin {
+        return Err(ContractError::Unauthorized(
+            "Sender is not an admin".to_owned(),
+        ));
+    }
+    if count == 0 {
+        return Err(ContractError::InvalidNumMints {});
+    }
+    if count > MAX_MINTS_PER_TX {
+        return Err(ContractError::TooManyMintsPerTx {
+            max: MAX_MINTS_PER_TX,
+        });
+    }
+
+    let len = AIRDROP_LEN.may_load(deps.storage)?.unwrap_or_default();
+    let cursor = AIRDROP_CURSOR.may_load(deps.storage)?.unwrap_or_default();
+    let remaining = len - cursor;
+    if remaining == 0 {
+        return Err(ContractError::NoAirdropInProgress {});
+    }
+    let count = count.min(remaining);
+
+    let airdrop_price = mint_price(deps.as_ref(), &env, true)?;
+    check_payment(&info, &airdrop_price, count)?;
+    let mint_info = MessageInfo {
+        sender: info.sender.clone(),
+        funds: if airdrop_price.amount.is_zero() {
+            vec![]
+        } else {
+            vec![airdrop_price]
+        },
     };
 
+    let mut res = Response::new();
+    let mut token_id_attrs = vec![];
+    for index in cursor..cursor + count {
+        let recipient = AIRDROP_RECIPIENTS.load(deps.storage, index)?;
+        AIRDROP_RECIPIENTS.remove(deps.storage, index);
+        let mint_res = _execute_mint(
+            deps.branch(),
+            env.clone(),
+            mint_info.clone(),
+            "airdrop",
+            true,
+            Some(recipient),
+            vec![None],
+            None,
+        )?;
+        res = res
+            .add_submessages(mint_res.messages)
+            .add_events(mint_res.events);
+        token_id_attrs.extend(
+            mint_res
+                .attributes
+                .into_iter()
+                .filter(|attr| attr.key == "token_id"),
+        );
+    }
+    AIRDROP_CURSOR.save(deps.storage, &(cursor + count))?;
+
     Ok(res
-        .add_attribute("action", action)
+        .add_attribute("action", "continue_airdrop")
         .add_attribute("sender", info.sender)
-        .add_attribute("recipient", recipient_addr)
-        .add_attribute("token_id", (mintable_token_id + base_token_id).to_string())
-        .add_attribute("network_fee", network_fee)
-        .add_attribute("mint_price", mint_price.amount)
-        .add_attribute("seller_amount", seller_amount))
+        .add_attribute("num_mints", count.to_string())
+        .add_attribute("remaining", (remaining - count).to_string())
+        .add_attributes(token_id_attrs))
 }
 
-pub fn execute_set_token_uri(
+// Admin mints can be restricted to a pre-approved list of recipients
+fn check_mint_to_allowlist(config: &Config, recipient: &Addr) -> Result<(), ContractError> {
+    if let Some(allowlist) = &config.extension.mint_to_allowlist {
+        if !allowlist.contains(recipient) {
+            return Err(ContractError::RecipientNotAllowed {
+                recipient: recipient.to_string(),
+            });
+        }
+    }
+    Ok(())
+}
+
+pub fn execute_mint_for(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
-    uri: String,
-    num_tokens: u32,
+    token_id: u32,
+    recipient: String,
 ) -> Result<Response, ContractError> {
-    let mut config = CONFIG.load(deps.storage)?;
+    let recipient = deps.api.addr_validate(&recipient)?;
+    let config = CONFIG.load(deps.storage)?;
+    let action = "mint_for";
 
     // Check only admin
     if info.sender != config.extension.admin {
@@ -559,78 +1478,1278 @@ pub fn execute_set_token_uri(
         ));
     }
 
-    // Calcuate the creation fee for num_tokens and fair burn
-    let mut res = Response::new();
-    let factory: ParamsResponse = deps
-        .querier
-        .query_wasm_smart(config.factory.clone(), &Sg2QueryMsg::Params {})?;
-    let factory_params = factory.params;
+    check_mint_to_allowlist(&config, &recipient)?;
 
-    let creation_fee = factory_params.extension.creation_fee_per_token * (num_tokens as u128);
-    checked_fair_burn(&info, creation_fee, None, &mut res)?;
+    // Check token id is within the id range of the current round
+    let minted_num_tokens = MINTED_NUM_TOKENS.load(deps.storage)?;
+    if token_id == 0 || token_id > config.extension.num_tokens - minted_num_tokens {
+        return Err(ContractError::InvalidTokenId {});
+    }
 
-    if num_tokens == 0 {
-        return Err(ContractError::InvalidNumTokens { })
+    _execute_mint(
+        deps,
+        env,
+        info,
+        action,
+        true,
+        Some(recipient),
+        vec![Some(token_id)],
+        None,
+    )
+}
+
+// Generalize checks and mint message creation
+// Each entry in `token_ids` mints one token, `None` takes the next mintable id
+// mint -> _execute_mint(recipient: None, token_ids: [None])
+// batch_mint(2) -> _execute_mint(recipient: None, token_ids: [None, None])
+// mint_to(recipient: "friend") -> _execute_mint(Some(recipient), token_ids: [None])
+// mint_for(recipient: "friend2", token_id: 420) -> _execute_mint(recipient, token_ids: [Some(420)])
+#[allow(clippy::too_many_arguments)]
+fn _execute_mint(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    action: &str,
+    is_admin: bool,
+    recipient: Option<Addr>,
+    token_ids: Vec<Option<u32>>,
+    referral: Option<Addr>,
+) -> Result<Response, ContractError> {
+    // Check mintable
+    check_not_fully_frozen(deps.storage)?;
+    check_launch_not_cancelled(deps.storage)?;
+    let minting_paused = MINTING_PAUSED.load(deps.storage)?;
+    if minting_paused {
+        return Err(ContractError::MintingPaused {});
     }
 
-    let mut base_token_uri = uri.trim().to_string();
-    // Check that base_token_uri is a valid IPFS uri
-    let parsed_token_uri = Url::parse(&base_token_uri)?;
-    if parsed_token_uri.scheme() != "ipfs" {
-        return Err(ContractError::InvalidBaseTokenURI {});
+    let num_mints = token_ids.len() as u32;
+    check_tokens_left(deps.storage, num_mints)?;
+
+    let config = CONFIG.load(deps.storage)?;
+    let sg721_address = SG721_ADDRESS.load(deps.storage)?;
+
+    // Public mints wait for the height gate as well as the start time
+    if let (false, Some(height)) = (is_admin, config.extension.require_height_gate) {
+        if env.block.height < height {
+            return Err(ContractError::BeforeMintStartHeight { height });
+        }
     }
-    base_token_uri = parsed_token_uri.to_string();
 
-    let base_token_id = BASE_TOKEN_ID.load(deps.storage)?;
-    let minted_num_tokens = MINTED_NUM_TOKENS.load(deps.storage)?;
-    BASE_TOKEN_ID.save(
-        deps.storage,
-        &(base_token_id + config.extension.num_tokens - minted_num_tokens),
-    )?;
+    // The whole batch counts against the sender's daily limit up front
+    if let (false, Some(daily_limit)) = (is_admin, config.extension.daily_mint_limit) {
+        let day = env.block.time.seconds() / SECONDS_PER_DAY;
+        let minted_today = match DAILY_MINTS.may_load(deps.storage, &info.sender)? {
+            Some((mint_day, count)) if mint_day == day => count,
+            _ => 0,
+        };
+        let minted_today = add_mints(minted_today, num_mints)?;
+        if minted_today > daily_limit {
+            return Err(ContractError::DailyLimitExceeded { limit: daily_limit });
+        }
+        DAILY_MINTS.save(deps.storage, &info.sender, &(day, minted_today))?;
+    }
 
-    // Remove the old mintable tokens ids map
-    let keys = MINTABLE_TOKEN_IDS
-        .keys(deps.storage, None, None, Order::Ascending)
-        .collect::<Vec<_>>();
-    for key in keys {
-        MINTABLE_TOKEN_IDS.remove(deps.storage, key?);
+    // Pace public mints across all addresses
+    if let (false, Some(interval)) = (is_admin, config.extension.global_mint_interval_secs) {
+        if let Some(last_mint) = LAST_GLOBAL_MINT.may_load(deps.storage)? {
+            let next_mint_time = last_mint.plus_seconds(interval);
+            if env.block.time < next_mint_time {
+                return Err(ContractError::GlobalMintIntervalNotElapsed { next_mint_time });
+            }
+        }
+        LAST_GLOBAL_MINT.save(deps.storage, &env.block.time)?;
     }
 
-    // Save mintable token ids map
-    for token_id in 1..=num_tokens {
-        MINTABLE_TOKEN_IDS.save(deps.storage, token_id, &true)?;
+    // Keep tokens out of contracts that may not handle them
+    if let (false, Some(recipient)) = (config.extension.allow_contract_recipients, &recipient) {
+        if deps
+            .querier
+            .query_wasm_contract_info(recipient.as_str())
+            .is_ok()
+        {
+            return Err(ContractError::ContractRecipientNotAllowed {
+                recipient: recipient.to_string(),
+            });
+        }
     }
 
-    let mintable_num_tokens = MINTABLE_NUM_TOKENS.load(deps.storage)?;
-    let minted_num_tokens = config.extension.num_tokens - mintable_num_tokens;
+    let recipient_addr = match recipient {
+        Some(some_recipient) => some_recipient,
+        None => info.sender.clone(),
+    };
+
+    let mint_price: Coin = payment_price(deps.as_ref(), &env, &info, is_admin)?;
+    let total_price = check_payment(&info, &mint_price, num_mints)?;
+
+    let mut res = Response::new();
+
+    let factory: ParamsResponse = deps
+        .querier
+        .query_wasm_smart(&config.factory, &Sg2QueryMsg::Params {})?;
+    let factory_params = factory.params;
+
+    // Create network fee msgs
+    let mint_fee = if is_admin {
+        factory_params
+            .extension
+            .airdrop_mint_fee_bps
+            .bps_to_decimal()
+    } else {
+        factory_params.mint_fee_bps.bps_to_decimal()
+    };
+    let network_fee = total_price * mint_fee;
+    if mint_price.denom == NATIVE_DENOM {
+        checked_fair_burn(&info, network_fee.u128(), None, &mut res)?;
+    } else if !network_fee.is_zero() {
+        // IBC and token factory denoms aren't burned, the whole fee funds the fairburn pool.
+        // `check_payment` already made sure the fee was paid in this denom.
+        res = res.add_message(create_fund_fairburn_pool_msg(vec![coin(
+            network_fee.u128(),
+            &mint_price.denom,
+        )]));
+    }
+    NETWORK_FEES.update(deps.storage, &mint_price.denom, |fees| -> StdResult<_> {
+        Ok(fees.unwrap_or_default() + network_fee)
+    })?;
+
+    let base_token_id = BASE_TOKEN_ID.load(deps.storage)?;
+    let mut minted_token_ids = vec![];
+    for token_id in token_ids {
+        let mintable_token_id = match token_id {
+            // Availability is checked when the id is consumed below
+            Some(token_id) => token_id,
+            None => {
+                let mintable_tokens_result: StdResult<Vec<u32>> = MINTABLE_TOKEN_IDS
+                    .keys(deps.storage, None, None, Order::Ascending)
+                    .take(1)
+                    .collect();
+                let mintable_tokens = mintable_tokens_result?;
+                // The counter was checked above, so there must be an id left
+                if mintable_tokens.is_empty() {
+                    return Err(ContractError::InconsistentState {});
+                }
+                mintable_tokens[0]
+            }
+        };
+
+        // Create mint msgs
+        let mint_msg = Sg721ExecuteMsg::<Extension, Empty>::Mint(MintMsg::<Extension> {
+            token_id: (mintable_token_id + base_token_id).to_string(),
+            owner: recipient_addr.to_string(),
+            token_uri: Some(mint_token_uri(
+                deps.storage,
+                &config,
+                mintable_token_id,
+                base_token_id,
+            )?),
+            extension: None,
+        });
+        let msg = CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: sg721_address.to_string(),
+            msg: to_binary(&mint_msg)?,
+            funds: vec![],
+        });
+        res = res.add_message(msg);
+
+        consume_mintable_token_id(deps.storage, mintable_token_id)?;
+        if config.extension.record_history {
+            MINT_EVENTS.save(
+                deps.storage,
+                mintable_token_id + base_token_id,
+                &(info.sender.clone(), env.block.time),
+            )?;
+        }
+        if config.extension.generative {
+            let token_id = mintable_token_id + base_token_id;
+            TOKEN_SEEDS.save(deps.storage, token_id, &token_seed(&env, token_id))?;
+        }
+        if config.extension.record_receipts {
+            let token_id = mintable_token_id + base_token_id;
+            MINT_RECEIPTS.save(
+                deps.storage,
+                token_id,
+                &MintReceipt {
+                    height: env.block.height,
+                    price: mint_price.clone(),
+                },
+            )?;
+            res = res.add_event(
+                Event::new("mint_receipt")
+                    .add_attribute("token_id", token_id.to_string())
+                    .add_attribute("height", env.block.height.to_string())
+                    .add_attribute("price", mint_price.to_string()),
+            );
+        }
+        minted_token_ids.push(mintable_token_id + base_token_id);
+    }
+
+    // Save the new mint count for the sender's address
+    let prev_mint_count = mint_count(deps.as_ref(), &info.sender)?;
+    MINTER_ADDRS.save(
+        deps.storage,
+        &info.sender,
+        &add_mints(prev_mint_count, num_mints)?,
+    )?;
+    if prev_mint_count == 0 {
+        UNIQUE_MINTERS.update(deps.storage, |count| -> StdResult<_> { Ok(count + 1) })?;
+    }
+
+    // Mints during an active whitelist count towards the whitelist cap
+    let phases = PHASES.load(deps.storage)?;
+    if !is_admin && phases.is_empty() && whitelist_is_active(deps.as_ref())? {
+        if active_claim_window(deps.as_ref(), &config, env.block.time)?.is_some() {
+            CLAIMED_ALLOCATIONS.update(deps.storage, &info.sender, |claimed| {
+                add_mints(claimed.unwrap_or_default(), num_mints)
+            })?;
+            TOTAL_CLAIMED.update(deps.storage, |total| add_mints(total, num_mints))?;
+        }
+        let whitelist_minted = WHITELIST_MINTED
+            .update(deps.storage, |count| -> Result<_, ContractError> {
+                add_mints(count, num_mints)
+            })?;
+        // A sold out whitelist can open the public mint early
+        if let (true, Some(cap)) = (
+            config.extension.public_on_whitelist_sellout,
+            config.extension.whitelist_total_cap,
+        ) {
+            if whitelist_minted >= cap {
+                EARLY_PUBLIC.save(deps.storage, &true)?;
+                res = res.add_event(
+                    Event::new("early_public_opened")
+                        .add_attribute("whitelist_minted", whitelist_minted.to_string()),
+                );
+            }
+        }
+    } else if !is_admin && phases.is_empty() {
+        if config.extension.reset_limit_on_public {
+            PUBLIC_MINTER_ADDRS.update(deps.storage, &info.sender, |count| {
+                add_mints(count.unwrap_or_default(), num_mints)
+            })?;
+        }
+        // The end of the whitelist is implicit in block time, signal it once on the first public mint
+        if config.extension.whitelist.is_some()
+            && !PUBLIC_OPENED.may_load(deps.storage)?.unwrap_or_default()
+        {
+            PUBLIC_OPENED.save(deps.storage, &true)?;
+            res = res.add_event(
+                Event::new("public_sale_opened").add_attribute("time", env.block.time.to_string()),
+            );
+        }
+    }
+
+    let total_minted = add_mints(TOTAL_MINTED.load(deps.storage)?, num_mints)?;
+    TOTAL_MINTED.save(deps.storage, &total_minted)?;
+    // Admin mints are free, so only paid mints count toward the soft cap
+    let prev_paid_minted = PAID_MINTED.load(deps.storage)?;
+    let paid_minted = if is_admin {
+        prev_paid_minted
+    } else {
+        add_mints(prev_paid_minted, num_mints)?
+    };
+    PAID_MINTED.save(deps.storage, &paid_minted)?;
+
+    // Pay the referrer their share of the mint price
+    let referral_amount = match (&referral, config.extension.referral_bps) {
+        (Some(referrer), Some(referral_bps)) if !is_admin => {
+            let amount = total_price * referral_bps.bps_to_decimal();
+            if !amount.is_zero() {
+                REFERRAL_REWARDS.update(
+                    deps.storage,
+                    (referrer, &mint_price.denom),
+                    |rewards| -> StdResult<_> { Ok(rewards.unwrap_or_default() + amount) },
+                )?;
+                res = res.add_message(BankMsg::Send {
+                    to_address: referrer.to_string(),
+                    amount: vec![coin(amount.u128(), &mint_price.denom)],
+                });
+            }
+            amount
+        }
+        _ => Uint128::zero(),
+    };
+
+    let seller_amount = if !is_admin {
+        let amount = total_price - network_fee - referral_amount;
+        // Proceeds are refundable until the collection is revealed
+        if config.extension.reveal_deadline.is_some() {
+            let refund = amount.multiply_ratio(1u128, num_mints);
+            for token_id in &minted_token_ids {
+                MINT_REFUNDS.save(deps.storage, *token_id, &refund)?;
+            }
+        }
+        // With a soft cap, vesting or a pending reveal, proceeds stay in the minter until withdrawn
+        if config.extension.soft_cap.is_none()
+            && config.extension.vesting.is_none()
+            && config.extension.reveal_deadline.is_none()
+        {
+            // With an auto withdraw threshold, proceeds are held and swept in one payout
+            let payout = match config.extension.auto_withdraw_threshold {
+                Some(threshold) => {
+                    let balance = deps
+                        .querier
+                        .query_balance(&env.contract.address, &mint_price.denom)?;
+                    let held = balance.amount
+                        - network_fee
+                        - referral_amount
+                        - TOTAL_DEPOSITS.load(deps.storage)?;
+                    if held >= threshold {
+                        res = res
+                            .add_event(Event::new("auto_withdraw").add_attribute("amount", held));
+                        held
+                    } else {
+                        Uint128::zero()
+                    }
+                }
+                None => amount,
+            };
+            // Surface a payout failure as a clear error instead of a raw bank error
+            for msg in payout_msgs(deps.storage, &config, payout, &mint_price.denom)? {
+                res = res.add_submessage(SubMsg::reply_on_error(msg, PAYOUT_REPLY_ID));
+            }
+        }
+        amount
+    } else {
+        Uint128::zero()
+    };
+
+    if let Some(soft_cap) = config.extension.soft_cap {
+        if prev_paid_minted < soft_cap && paid_minted >= soft_cap {
+            res = res.add_event(
+                Event::new("soft_cap_reached")
+                    .add_attribute("soft_cap", soft_cap.to_string())
+                    .add_attribute("paid_minted", paid_minted.to_string()),
+            );
+        }
+    }
+
+    res = res
+        .add_attribute("action", action)
+        .add_attribute("sender", info.sender)
+        .add_attribute("recipient", recipient_addr);
+    for token_id in &minted_token_ids {
+        res = res.add_attribute("token_id", token_id.to_string());
+    }
+    // Batch mints also list every assigned id in one attribute and a typed event
+    if action == "batch_mint" {
+        let token_ids = minted_token_ids
+            .iter()
+            .map(|token_id| token_id.to_string())
+            .collect::<Vec<_>>()
+            .join(",");
+        res = res
+            .add_event(Event::new("batch_mint").add_attribute("token_ids", &token_ids))
+            .add_attribute("token_ids", token_ids);
+    }
+    res = res
+        .add_attribute("network_fee", network_fee)
+        .add_attribute("mint_price", mint_price.amount)
+        .add_attribute("seller_amount", seller_amount);
+    if let Some(referrer) = referral {
+        res = res
+            .add_attribute("referral", referrer)
+            .add_attribute("referral_amount", referral_amount);
+    }
+    Ok(res)
+}
+
+// Exact payment of `num_mints` at the effective price only accepted.
+// Returns the total price that was paid.
+fn check_payment(
+    info: &MessageInfo,
+    price: &Coin,
+    num_mints: u32,
+) -> Result<Uint128, ContractError> {
+    let total_price = price.amount * Uint128::from(num_mints);
+    let payment = may_pay(info, &price.denom)?;
+    if payment != total_price {
+        return Err(ContractError::IncorrectPaymentAmount(
+            coin(payment.u128(), &price.denom),
+            coin(total_price.u128(), &price.denom),
+        ));
+    }
+    Ok(total_price)
+}
+
+// Sanitize a base token uri and check it is a valid IPFS uri
+fn parse_base_token_uri(uri: &str) -> Result<String, ContractError> {
+    let parsed_token_uri = Url::parse(uri.trim())?;
+    if parsed_token_uri.scheme() != "ipfs" {
+        return Err(ContractError::InvalidBaseTokenURI {});
+    }
+    // token uris are built as `{base_token_uri}/{id}`
+    Ok(parsed_token_uri
+        .to_string()
+        .trim_end_matches('/')
+        .to_string())
+}
+
+fn token_uri(config: &Config, token_id: u32) -> String {
+    format!(
+        "{}/{}{}",
+        config.extension.base_token_uri,
+        token_id,
+        config
+            .extension
+            .token_uri_suffix
+            .as_deref()
+            .unwrap_or_default()
+    )
+}
+
+// Uri of a mintable id at mint time, the frozen one if the admin froze it
+fn mint_token_uri(
+    storage: &dyn Storage,
+    config: &Config,
+    mintable_token_id: u32,
+    base_token_id: u32,
+) -> StdResult<String> {
+    Ok(FROZEN_TOKEN_URIS
+        .may_load(storage, mintable_token_id + base_token_id)?
+        .unwrap_or_else(|| token_uri(config, mintable_token_id)))
+}
+
+// Remove a token id from the mintable map and decrement the mintable count.
+// Every path that takes an id out of the pool goes through here so both stay in sync.
+fn consume_mintable_token_id(
+    storage: &mut dyn Storage,
+    token_id: u32,
+) -> Result<(), ContractError> {
+    if !MINTABLE_TOKEN_IDS.has(storage, token_id) {
+        return Err(ContractError::TokenIdAlreadySold { token_id });
+    }
+    MINTABLE_TOKEN_IDS.remove(storage, token_id);
+
+    let mintable_num_tokens = MINTABLE_NUM_TOKENS
+        .load(storage)?
+        .checked_sub(1)
+        .ok_or(ContractError::InconsistentState {})?;
+    MINTABLE_NUM_TOKENS.save(storage, &mintable_num_tokens)?;
+    Ok(())
+}
+
+// Burn a token held by the sender and mint a replacement from the pool.
+// The replacement is always the next id in mint order and is drawn before the burned id
+// returns to the pool, so holders can't steer rerolls towards specific (rare) ids.
+pub fn execute_reroll(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    token_id: u32,
+) -> Result<Response, ContractError> {
+    check_not_fully_frozen(deps.storage)?;
+    check_launch_not_cancelled(deps.storage)?;
+    let minting_paused = MINTING_PAUSED.load(deps.storage)?;
+    if minting_paused {
+        return Err(ContractError::MintingPaused {});
+    }
+
+    let config = CONFIG.load(deps.storage)?;
+    let reroll_fee = config
+        .extension
+        .reroll_fee
+        .clone()
+        .ok_or(ContractError::RerollDisabled {})?;
+    if env.block.time < config.extension.start_time {
+        return Err(ContractError::BeforeMintStartTime {});
+    }
+
+    check_payment(&info, &reroll_fee, 1)?;
+
+    // Only tokens of the current round can go back into the pool
+    let base_token_id = BASE_TOKEN_ID.load(deps.storage)?;
+    let minted_num_tokens = MINTED_NUM_TOKENS.load(deps.storage)?;
+    let mintable_token_id = token_id
+        .checked_sub(base_token_id)
+        .filter(|id| *id > 0 && *id <= config.extension.num_tokens - minted_num_tokens)
+        .ok_or(ContractError::InvalidTokenId {})?;
+
+    let sg721_address = SG721_ADDRESS.load(deps.storage)?;
+    let owner: OwnerOfResponse = deps.querier.query_wasm_smart(
+        &sg721_address,
+        &Cw721QueryMsg::OwnerOf {
+            token_id: token_id.to_string(),
+            include_expired: None,
+        },
+    )?;
+    if owner.owner != info.sender {
+        return Err(ContractError::Unauthorized(
+            "Sender is not the token owner".to_owned(),
+        ));
+    }
+
+    let new_mintable_token_id = MINTABLE_TOKEN_IDS
+        .keys(deps.storage, None, None, Order::Ascending)
+        .next()
+        .transpose()?
+        .ok_or(ContractError::SoldOut {})?;
+    consume_mintable_token_id(deps.storage, new_mintable_token_id)?;
+    MINTABLE_TOKEN_IDS.save(deps.storage, mintable_token_id, &true)?;
+    MINTABLE_NUM_TOKENS.update(deps.storage, |count| -> StdResult<_> { Ok(count + 1) })?;
+
+    // The replacement takes over the receipt and refund of the burned token, and gets its own seed
+    let new_token_id = new_mintable_token_id + base_token_id;
+    TOKEN_SEEDS.remove(deps.storage, token_id);
+    if config.extension.generative {
+        TOKEN_SEEDS.save(deps.storage, new_token_id, &token_seed(&env, new_token_id))?;
+    }
+    if let Some(receipt) = MINT_RECEIPTS.may_load(deps.storage, token_id)? {
+        MINT_RECEIPTS.remove(deps.storage, token_id);
+        MINT_RECEIPTS.save(deps.storage, new_token_id, &receipt)?;
+    }
+    if let Some(refund) = MINT_REFUNDS.may_load(deps.storage, token_id)? {
+        MINT_REFUNDS.remove(deps.storage, token_id);
+        MINT_REFUNDS.save(deps.storage, new_token_id, &refund)?;
+    }
+
+    let burn_msg = Sg721ExecuteMsg::<Extension, Empty>::Burn {
+        token_id: token_id.to_string(),
+    };
+    let mint_msg = Sg721ExecuteMsg::<Extension, Empty>::Mint(MintMsg::<Extension> {
+        token_id: new_token_id.to_string(),
+        owner: info.sender.to_string(),
+        token_uri: Some(mint_token_uri(
+            deps.storage,
+            &config,
+            new_mintable_token_id,
+            base_token_id,
+        )?),
+        extension: None,
+    });
+    let mut res = Response::new()
+        .add_message(WasmMsg::Execute {
+            contract_addr: sg721_address.to_string(),
+            msg: to_binary(&burn_msg)?,
+            funds: vec![],
+        })
+        .add_message(WasmMsg::Execute {
+            contract_addr: sg721_address.to_string(),
+            msg: to_binary(&mint_msg)?,
+            funds: vec![],
+        });
+
+    // Like mint proceeds, the fee is held by the minter under a soft cap or vesting
+    if !reroll_fee.amount.is_zero()
+        && config.extension.soft_cap.is_none()
+        && config.extension.vesting.is_none()
+    {
+        res = res.add_messages(payout_msgs(
+            deps.storage,
+            &config,
+            reroll_fee.amount,
+            &reroll_fee.denom,
+        )?);
+    }
+
+    Ok(res
+        .add_attribute("action", "reroll")
+        .add_attribute("sender", info.sender)
+        .add_attribute("burned_token_id", token_id.to_string())
+        .add_attribute("token_id", new_token_id.to_string())
+        .add_attribute("reroll_fee", reroll_fee.to_string()))
+}
+
+pub fn execute_set_token_uri(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    uri: String,
+    num_tokens: u32,
+) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+
+    // Check only admin
+    if info.sender != config.extension.admin {
+        return Err(ContractError::Unauthorized(
+            "Sender is not an admin".to_owned(),
+        ));
+    }
+    check_collection_info_not_frozen(deps.as_ref())?;
+
+    if num_tokens == 0 {
+        return Err(ContractError::InvalidNumTokens {});
+    }
+    let factory: ParamsResponse = deps
+        .querier
+        .query_wasm_smart(config.factory.clone(), &Sg2QueryMsg::Params {})?;
+    let factory_params = factory.params;
+
+    // Reserved ids of the old round are dropped like its other unminted ids
+    let reserved_token_ids = RESERVED_TOKEN_IDS
+        .keys(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    let minted_num_tokens = config.extension.num_tokens
+        - MINTABLE_NUM_TOKENS.load(deps.storage)?
+        - reserved_token_ids.len() as u32;
+
+    // The collection size after the new round, capped like `ReopenMint`
+    let new_num_tokens =
+        minted_num_tokens
+            .checked_add(num_tokens)
+            .ok_or(ContractError::MaxTokenLimitExceeded {
+                max: factory_params.extension.max_token_limit,
+            })?;
+    check_max_token_limit(new_num_tokens, &factory_params)?;
+
+    let base_token_uri = parse_base_token_uri(&uri)?;
+
+    // Calcuate the creation fee for num_tokens and fair burn
+    let mut res = Response::new();
+    let creation_fee = factory_params.extension.creation_fee_per_token * (num_tokens as u128);
+    checked_fair_burn(&info, creation_fee, None, &mut res)?;
+
+    let base_token_id = BASE_TOKEN_ID.load(deps.storage)?;
+    let prev_minted_num_tokens = MINTED_NUM_TOKENS.load(deps.storage)?;
+    BASE_TOKEN_ID.save(
+        deps.storage,
+        &(base_token_id + config.extension.num_tokens - prev_minted_num_tokens),
+    )?;
+
+    // Remove the old mintable and reserved tokens ids maps
+    let keys = MINTABLE_TOKEN_IDS
+        .keys(deps.storage, None, None, Order::Ascending)
+        .collect::<Vec<_>>();
+    for key in keys {
+        MINTABLE_TOKEN_IDS.remove(deps.storage, key?);
+    }
+    for token_id in reserved_token_ids {
+        RESERVED_TOKEN_IDS.remove(deps.storage, token_id);
+    }
+
+    // Save mintable token ids map
+    for token_id in 1..=num_tokens {
+        MINTABLE_TOKEN_IDS.save(deps.storage, token_id, &true)?;
+    }
+
+    config.extension.base_token_uri = base_token_uri;
+    config.extension.num_tokens = new_num_tokens;
+    CONFIG.save(deps.storage, &config)?;
+
+    MINTABLE_NUM_TOKENS.save(deps.storage, &num_tokens)?;
+    MINTED_NUM_TOKENS.save(deps.storage, &minted_num_tokens)?;
+
+    Ok(res
+        .add_attribute("action", "set new uri")
+        .add_attribute("num_token", num_tokens.to_string())
+        .add_attribute("creation_fee", creation_fee.to_string()))
+}
+
+// A frozen collection info promises holders the collection won't change,
+// so the base token uri is frozen with it
+fn check_collection_info_not_frozen(deps: Deps) -> Result<(), ContractError> {
+    let sg721_address = SG721_ADDRESS.load(deps.storage)?;
+    let res: FrozenCollectionInfoResponse = deps
+        .querier
+        .query_wasm_smart(sg721_address, &Sg721QueryMsg::FrozenCollectionInfo {})?;
+    if res.frozen {
+        return Err(ContractError::CollectionInfoFrozen {});
+    }
+    Ok(())
+}
+
+// Extend the current round with new ids after the current max, e.g. for a re-drop.
+// Unlike `SetTokenUri`, already minted ids of the round keep their uri.
+pub fn execute_reopen_mint(
+    deps: DepsMut,
+    info: MessageInfo,
+    additional_tokens: u32,
+    new_base_token_uri: Option<String>,
+) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+
+    // Check only admin
+    if info.sender != config.extension.admin {
+        return Err(ContractError::Unauthorized(
+            "Sender is not an admin".to_owned(),
+        ));
+    }
+
+    if additional_tokens == 0 {
+        return Err(ContractError::InvalidNumTokens {});
+    }
+    if new_base_token_uri.is_some() {
+        check_collection_info_not_frozen(deps.as_ref())?;
+    }
+
+    // Calcuate the creation fee for the new tokens and fair burn
+    let mut res = Response::new();
+    let factory: ParamsResponse = deps
+        .querier
+        .query_wasm_smart(config.factory.clone(), &Sg2QueryMsg::Params {})?;
+    let num_tokens = config
+        .extension
+        .num_tokens
+        .checked_add(additional_tokens)
+        .ok_or(ContractError::MaxTokenLimitExceeded {
+            max: factory.params.extension.max_token_limit,
+        })?;
+    check_max_token_limit(num_tokens, &factory.params)?;
+    let creation_fee =
+        factory.params.extension.creation_fee_per_token * (additional_tokens as u128);
+    checked_fair_burn(&info, creation_fee, None, &mut res)?;
+
+    // Ids of the current round are 1..=round_size, the new ids follow them
+    let minted_num_tokens = MINTED_NUM_TOKENS.load(deps.storage)?;
+    let round_size = config.extension.num_tokens - minted_num_tokens;
+    for token_id in round_size + 1..=round_size + additional_tokens {
+        MINTABLE_TOKEN_IDS.save(deps.storage, token_id, &true)?;
+    }
+    MINTABLE_NUM_TOKENS.update(deps.storage, |count| -> StdResult<_> {
+        Ok(count + additional_tokens)
+    })?;
+
+    if let Some(uri) = new_base_token_uri {
+        config.extension.base_token_uri = parse_base_token_uri(&uri)?;
+    }
+    config.extension.num_tokens = num_tokens;
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(res
+        .add_attribute("action", "reopen_mint")
+        .add_attribute("sender", info.sender)
+        .add_attribute("additional_tokens", additional_tokens.to_string())
+        .add_attribute("num_tokens", num_tokens.to_string())
+        .add_attribute("creation_fee", creation_fee.to_string()))
+}
+
+pub fn execute_set_minting_pause(
+    deps: DepsMut,
+    info: MessageInfo,
+    pause: bool,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    // Check only admin
+    if info.sender != config.extension.admin {
+        return Err(ContractError::Unauthorized(
+            "Sender is not an admin".to_owned(),
+        ));
+    }
+    MINTING_PAUSED.save(deps.storage, &pause)?;
+    Ok(Response::new().add_attribute("minting paused", pause.to_string()))
+}
+
+pub fn execute_reserve_tokens(
+    deps: DepsMut,
+    info: MessageInfo,
+    token_ids: Vec<u32>,
+) -> Result<Response, ContractError> {
+    nonpayable(&info)?;
+    let config = CONFIG.load(deps.storage)?;
+    // Check only admin
+    if info.sender != config.extension.admin {
+        return Err(ContractError::Unauthorized(
+            "Sender is not an admin".to_owned(),
+        ));
+    }
+
+    for token_id in &token_ids {
+        consume_mintable_token_id(deps.storage, *token_id)?;
+        RESERVED_TOKEN_IDS.save(deps.storage, *token_id, &true)?;
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "reserve_tokens")
+        .add_attribute("sender", info.sender)
+        .add_attribute("num_tokens", token_ids.len().to_string()))
+}
+
+pub fn execute_request_freeze(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    nonpayable(&info)?;
+    let config = CONFIG.load(deps.storage)?;
+    // Check only admin
+    if info.sender != config.extension.admin {
+        return Err(ContractError::Unauthorized(
+            "Sender is not an admin".to_owned(),
+        ));
+    }
+    if FREEZE_REQUESTED_AT.may_load(deps.storage)?.is_some() {
+        return Err(ContractError::FreezeAlreadyRequested {});
+    }
+    FREEZE_REQUESTED_AT.save(deps.storage, &env.block.time)?;
+
+    let confirm_time = env
+        .block
+        .time
+        .plus_seconds(config.extension.freeze_delay_secs);
+    Ok(Response::new()
+        .add_attribute("action", "request_freeze")
+        .add_attribute("sender", info.sender)
+        .add_attribute("confirm_time", confirm_time.to_string()))
+}
+
+// Freezing is irreversible, so it only goes through after the freeze delay
+pub fn execute_confirm_freeze(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    nonpayable(&info)?;
+    let config = CONFIG.load(deps.storage)?;
+    // Check only admin
+    if info.sender != config.extension.admin {
+        return Err(ContractError::Unauthorized(
+            "Sender is not an admin".to_owned(),
+        ));
+    }
+    let requested_at = FREEZE_REQUESTED_AT
+        .may_load(deps.storage)?
+        .ok_or(ContractError::NoFreezeRequested {})?;
+    let confirm_time = requested_at.plus_seconds(config.extension.freeze_delay_secs);
+    if env.block.time < confirm_time {
+        return Err(ContractError::FreezeDelayNotElapsed { confirm_time });
+    }
+    FREEZE_REQUESTED_AT.remove(deps.storage);
+
+    let sg721_address = SG721_ADDRESS.load(deps.storage)?;
+    let msg = WasmMsg::Execute {
+        contract_addr: sg721_address.to_string(),
+        msg: to_binary(&Sg721ExecuteMsg::<Empty, Empty>::FreezeCollectionInfo {})?,
+        funds: vec![],
+    };
+    Ok(Response::new()
+        .add_attribute("action", "confirm_freeze")
+        .add_attribute("sender", info.sender)
+        .add_message(msg))
+}
+
+pub fn execute_cancel_freeze(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    nonpayable(&info)?;
+    let config = CONFIG.load(deps.storage)?;
+    // Check only admin
+    if info.sender != config.extension.admin {
+        return Err(ContractError::Unauthorized(
+            "Sender is not an admin".to_owned(),
+        ));
+    }
+    if FREEZE_REQUESTED_AT.may_load(deps.storage)?.is_none() {
+        return Err(ContractError::NoFreezeRequested {});
+    }
+    FREEZE_REQUESTED_AT.remove(deps.storage);
+
+    Ok(Response::new()
+        .add_attribute("action", "cancel_freeze")
+        .add_attribute("sender", info.sender))
+}
+
+pub fn execute_freeze_token_uri(
+    deps: DepsMut,
+    info: MessageInfo,
+    token_id: u32,
+) -> Result<Response, ContractError> {
+    nonpayable(&info)?;
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.extension.admin {
+        return Err(ContractError::Unauthorized(
+            "Sender is not an admin".to_owned(),
+        ));
+    }
+    // Minted tokens already have their uri set on the collection
+    if !MINTABLE_TOKEN_IDS.has(deps.storage, token_id) {
+        return Err(ContractError::InvalidTokenId {});
+    }
+
+    let base_token_id = BASE_TOKEN_ID.load(deps.storage)?;
+    let uri = mint_token_uri(deps.storage, &config, token_id, base_token_id)?;
+    FROZEN_TOKEN_URIS.save(deps.storage, token_id + base_token_id, &uri)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "freeze_token_uri")
+        .add_attribute("sender", info.sender)
+        .add_attribute("token_id", (token_id + base_token_id).to_string())
+        .add_attribute("token_uri", uri))
+}
+
+pub fn execute_set_token_royalty(
+    deps: DepsMut,
+    info: MessageInfo,
+    token_id: u32,
+    share: Decimal,
+    recipient: String,
+) -> Result<Response, ContractError> {
+    nonpayable(&info)?;
+    let config = CONFIG.load(deps.storage)?;
+    // Check only admin
+    if info.sender != config.extension.admin {
+        return Err(ContractError::Unauthorized(
+            "Sender is not an admin".to_owned(),
+        ));
+    }
+    if share > Decimal::one() {
+        return Err(ContractError::InvalidRoyaltyShare {});
+    }
+    // Any token of the current round, minted or not
+    let base_token_id = BASE_TOKEN_ID.load(deps.storage)?;
+    if token_id == 0 || token_id > base_token_id + config.extension.num_tokens {
+        return Err(ContractError::InvalidTokenId {});
+    }
+
+    let factory: ParamsResponse = deps
+        .querier
+        .query_wasm_smart(config.factory, &Sg2QueryMsg::Params {})?;
+    let max = Decimal::from_ratio(factory.params.extension.max_royalty_bps, 10_000u128);
+    if share > max {
+        return Err(ContractError::RoyaltyTooHigh { max, got: share });
+    }
+
+    let recipient = deps.api.addr_validate(&recipient)?;
+    // The collection holds the royalty so marketplaces read it from there
+    let royalty_msg = Sg721ExecuteMsg::<Extension, ExtensionMsg>::Extension {
+        msg: ExtensionMsg::SetTokenRoyalty {
+            token_id: token_id.to_string(),
+            royalty_info: RoyaltyInfoResponse {
+                payment_address: recipient.to_string(),
+                share,
+            },
+        },
+    };
+
+    Ok(Response::new()
+        .add_attribute("action", "set_token_royalty")
+        .add_attribute("sender", info.sender)
+        .add_attribute("token_id", token_id.to_string())
+        .add_attribute("share", share.to_string())
+        .add_attribute("recipient", recipient)
+        .add_message(WasmMsg::Execute {
+            contract_addr: SG721_ADDRESS.load(deps.storage)?.to_string(),
+            msg: to_binary(&royalty_msg)?,
+            funds: vec![],
+        }))
+}
+
+pub fn execute_release_tokens(
+    deps: DepsMut,
+    info: MessageInfo,
+    token_ids: Vec<u32>,
+) -> Result<Response, ContractError> {
+    nonpayable(&info)?;
+    let config = CONFIG.load(deps.storage)?;
+    // Check only admin
+    if info.sender != config.extension.admin {
+        return Err(ContractError::Unauthorized(
+            "Sender is not an admin".to_owned(),
+        ));
+    }
+
+    for token_id in &token_ids {
+        if !RESERVED_TOKEN_IDS.has(deps.storage, *token_id) {
+            return Err(ContractError::TokenIdNotReserved {
+                token_id: *token_id,
+            });
+        }
+        RESERVED_TOKEN_IDS.remove(deps.storage, *token_id);
+        MINTABLE_TOKEN_IDS.save(deps.storage, *token_id, &true)?;
+        MINTABLE_NUM_TOKENS.update(deps.storage, |count| -> StdResult<_> { Ok(count + 1) })?;
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "release_tokens")
+        .add_attribute("sender", info.sender)
+        .add_attribute("num_tokens", token_ids.len().to_string()))
+}
+
+pub fn execute_retry_collection_instantiate(
+    deps: DepsMut,
+    info: MessageInfo,
+    sg721_code_id: u64,
+) -> Result<Response, ContractError> {
+    // The collection doesn't accept funds
+    nonpayable(&info)?;
+    let mut config = CONFIG.load(deps.storage)?;
+    // Check only admin
+    if info.sender != config.extension.admin {
+        return Err(ContractError::Unauthorized(
+            "Sender is not an admin".to_owned(),
+        ));
+    }
+    // Only minters left without a collection by a failed instantiation are pending
+    let collection_msg = match (
+        SG721_ADDRESS.may_load(deps.storage)?,
+        COLLECTION_INSTANTIATE_MSG.may_load(deps.storage)?,
+    ) {
+        (None, Some(collection_msg)) => collection_msg,
+        _ => return Err(ContractError::CollectionAlreadyInstantiated {}),
+    };
+
+    config.collection_code_id = sg721_code_id;
+    CONFIG.save(deps.storage, &config)?;
+
+    let submsg = instantiate_collection_submsg(
+        sg721_code_id,
+        &collection_msg,
+        vec![],
+        &config.extension.admin,
+    )?;
+
+    Ok(Response::new()
+        .add_attribute("action", "retry_collection_instantiate")
+        .add_attribute("sender", info.sender)
+        .add_attribute("sg721_code_id", sg721_code_id.to_string())
+        .add_submessage(submsg))
+}
+
+pub fn execute_set_batch_mint_enabled(
+    deps: DepsMut,
+    info: MessageInfo,
+    enabled: bool,
+) -> Result<Response, ContractError> {
+    nonpayable(&info)?;
+    let mut config = CONFIG.load(deps.storage)?;
+    if info.sender != config.extension.admin {
+        return Err(ContractError::Unauthorized(
+            "Sender is not an admin".to_owned(),
+        ));
+    }
+    config.extension.batch_mint_enabled = enabled;
+    CONFIG.save(deps.storage, &config)?;
+    Ok(Response::new()
+        .add_attribute("action", "set_batch_mint_enabled")
+        .add_attribute("sender", info.sender)
+        .add_attribute("enabled", enabled.to_string()))
+}
+
+pub fn execute_reveal(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    nonpayable(&info)?;
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.extension.admin {
+        return Err(ContractError::Unauthorized(
+            "Sender is not an admin".to_owned(),
+        ));
+    }
+    // Refunds may have started once the deadline passed
+    if let Some(reveal_deadline) = config.extension.reveal_deadline {
+        if env.block.time > reveal_deadline {
+            return Err(ContractError::RevealDeadlinePassed {});
+        }
+    }
+
+    REVEALED.save(deps.storage, &true)?;
+    Ok(Response::new()
+        .add_attribute("action", "reveal")
+        .add_attribute("sender", info.sender))
+}
+
+pub fn execute_claim_refund(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    token_id: u32,
+) -> Result<Response, ContractError> {
+    nonpayable(&info)?;
+    let config = CONFIG.load(deps.storage)?;
+    let missed_reveal = match config.extension.reveal_deadline {
+        Some(reveal_deadline) => {
+            env.block.time > reveal_deadline && !REVEALED.load(deps.storage)?
+        }
+        None => false,
+    };
+    if !missed_reveal {
+        return Err(ContractError::RefundNotAvailable {});
+    }
 
-    config.extension.base_token_uri = base_token_uri;
-    config.extension.num_tokens = minted_num_tokens + num_tokens;
-    CONFIG.save(deps.storage, &config)?;
+    let sg721_address = SG721_ADDRESS.load(deps.storage)?;
+    let owner: OwnerOfResponse = deps.querier.query_wasm_smart(
+        &sg721_address,
+        &Cw721QueryMsg::OwnerOf {
+            token_id: token_id.to_string(),
+            include_expired: None,
+        },
+    )?;
+    if owner.owner != info.sender {
+        return Err(ContractError::Unauthorized(
+            "Sender is not the token owner".to_owned(),
+        ));
+    }
 
-    MINTABLE_NUM_TOKENS.save(deps.storage, &num_tokens)?;
-    MINTED_NUM_TOKENS.save(deps.storage, &minted_num_tokens)?;
+    let refund = MINT_REFUNDS
+        .may_load(deps.storage, token_id)?
+        .ok_or(ContractError::RefundNotAvailable {})?;
+    MINT_REFUNDS.remove(deps.storage, token_id);
 
-    Ok(res
-        .add_attribute("action", "set new uri")
-        .add_attribute("num_token", num_tokens.to_string())
-        .add_attribute("creation_fee", creation_fee.to_string()))
+    let burn_msg = Sg721ExecuteMsg::<Extension, Empty>::Burn {
+        token_id: token_id.to_string(),
+    };
+    let refund = coin(refund.u128(), config.mint_price.denom);
+    Ok(Response::new()
+        .add_message(WasmMsg::Execute {
+            contract_addr: sg721_address.to_string(),
+            msg: to_binary(&burn_msg)?,
+            funds: vec![],
+        })
+        .add_message(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: vec![refund.clone()],
+        })
+        .add_attribute("action", "claim_refund")
+        .add_attribute("sender", info.sender)
+        .add_attribute("token_id", token_id.to_string())
+        .add_attribute("refund", refund.to_string()))
 }
 
-pub fn execute_set_minting_pause(
+pub fn execute_withdraw(
     deps: DepsMut,
+    env: Env,
     info: MessageInfo,
-    pause: bool,
 ) -> Result<Response, ContractError> {
+    nonpayable(&info)?;
+    check_not_fully_frozen(deps.storage)?;
     let config = CONFIG.load(deps.storage)?;
-    // Check only admin
     if info.sender != config.extension.admin {
         return Err(ContractError::Unauthorized(
             "Sender is not an admin".to_owned(),
         ));
     }
-    MINTING_PAUSED.save(deps.storage, &pause)?;
-    Ok(Response::new().add_attribute("minting paused", pause.to_string()))
+
+    if config.extension.reveal_deadline.is_some() && !REVEALED.load(deps.storage)? {
+        return Err(ContractError::RevealPending {});
+    }
+
+    let paid_minted = PAID_MINTED.load(deps.storage)?;
+    if let Some(soft_cap) = config.extension.soft_cap {
+        if paid_minted < soft_cap {
+            return Err(ContractError::SoftCapNotReached {
+                minted: paid_minted,
+                soft_cap,
+            });
+        }
+    }
+
+    let balance = deps
+        .querier
+        .query_balance(env.contract.address, &config.mint_price.denom)?;
+    // Presale deposits belong to their depositors until claimed
+    let available = balance
+        .amount
+        .checked_sub(TOTAL_DEPOSITS.load(deps.storage)?)
+        .map_err(|_| ContractError::NoFundsToWithdraw {})?;
+    let withdrawn = WITHDRAWN.load(deps.storage)?;
+    let amount = match &config.extension.vesting {
+        Some(vesting) => vested_amount(
+            vesting,
+            config.extension.start_time,
+            env.block.time,
+            available + withdrawn,
+        )
+        .checked_sub(withdrawn)
+        .map_err(|_| ContractError::NoFundsToWithdraw {})?,
+        None => available,
+    };
+    if amount.is_zero() {
+        return Err(ContractError::NoFundsToWithdraw {});
+    }
+    WITHDRAWN.save(deps.storage, &(withdrawn + amount))?;
+    let msgs = payout_msgs(deps.storage, &config, amount, &config.mint_price.denom)?;
+    let amount = coin(amount.u128(), &config.mint_price.denom);
+
+    let mut res = Response::new()
+        .add_attribute("action", "withdraw")
+        .add_attribute("sender", info.sender);
+    for msg in &msgs {
+        match msg {
+            CosmosMsg::Bank(BankMsg::Send { to_address, .. }) => {
+                res = res.add_attribute("recipient", to_address);
+            }
+            CosmosMsg::Wasm(WasmMsg::Execute { contract_addr, .. }) => {
+                res = res.add_attribute("recipient", contract_addr);
+            }
+            _ => {}
+        }
+    }
+    Ok(res
+        .add_attribute("amount", amount.to_string())
+        .add_messages(msgs))
+}
+
+// Pays `amount` to the payout splits, the proceeds target or the payment address,
+// and records the total paid to each recipient
+fn payout_msgs(
+    storage: &mut dyn Storage,
+    config: &Config,
+    amount: Uint128,
+    denom: &str,
+) -> StdResult<Vec<CosmosMsg<StargazeMsgWrapper>>> {
+    let splits = match (
+        &config.extension.payout_splits,
+        &config.extension.proceeds_target,
+    ) {
+        (Some(splits), _) => splits.clone(),
+        (None, Some(ProceedsTarget::Bank { address })) => vec![PayoutSplit {
+            address: address.clone(),
+            bps: 10_000,
+        }],
+        (None, Some(ProceedsTarget::Wasm { contract, msg })) => {
+            if amount.is_zero() {
+                return Ok(vec![]);
+            }
+            PAYOUT_TOTALS.update(storage, (contract, denom), |total| -> StdResult<_> {
+                Ok(total.unwrap_or_default() + amount)
+            })?;
+            return Ok(vec![WasmMsg::Execute {
+                contract_addr: contract.to_string(),
+                msg: msg.clone(),
+                funds: vec![coin(amount.u128(), denom)],
+            }
+            .into()]);
+        }
+        (None, None) => vec![PayoutSplit {
+            address: config
+                .extension
+                .payment_address
+                .clone()
+                .unwrap_or_else(|| config.extension.admin.clone()),
+            bps: 10_000,
+        }],
+    };
+
+    let mut msgs = vec![];
+    let mut remaining = amount;
+    for (i, split) in splits.iter().enumerate() {
+        // The last recipient gets the rounding remainder
+        let share = if i == splits.len() - 1 {
+            remaining
+        } else {
+            amount.multiply_ratio(split.bps, 10_000u128)
+        };
+        remaining -= share;
+        if share.is_zero() {
+            continue;
+        }
+        PAYOUT_TOTALS.update(storage, (&split.address, denom), |total| -> StdResult<_> {
+            Ok(total.unwrap_or_default() + share)
+        })?;
+        msgs.push(
+            BankMsg::Send {
+                to_address: split.address.to_string(),
+                amount: vec![coin(share.u128(), denom)],
+            }
+            .into(),
+        );
+    }
+    Ok(msgs)
+}
+
+// Portion of `total` proceeds vested at `now`, linear from `start_time`
+fn vested_amount(
+    vesting: &VestingConfig,
+    start_time: Timestamp,
+    now: Timestamp,
+    total: Uint128,
+) -> Uint128 {
+    if now < vesting.cliff_time {
+        return Uint128::zero();
+    }
+    let elapsed = now
+        .seconds()
+        .saturating_sub(start_time.seconds())
+        .min(vesting.duration_secs);
+    total.multiply_ratio(elapsed, vesting.duration_secs)
 }
 
 pub fn execute_update_mint_price(
@@ -660,14 +2779,26 @@ pub fn execute_update_mint_price(
     let factory_params = factory.params;
 
     // Check that the price is greater than the minimum
-    if factory_params.min_mint_price.amount.u128() > price {
+    let floor = min_mint_price(
+        &factory_params.min_mint_price,
+        &config,
+        &config.mint_price.denom,
+    );
+    if floor.u128() > price {
         return Err(ContractError::InsufficientMintPrice {
-            expected: factory_params.min_mint_price.amount.u128(),
+            expected: floor.u128(),
             got: price,
         });
     }
 
     config.mint_price = coin(price, config.mint_price.denom);
+    check_max_mint_price(&config.mint_price, &factory_params.extension.max_mint_price)?;
+    if let Some(whitelist) = &config.extension.whitelist {
+        let wl_config: WhitelistConfigResponse = deps
+            .querier
+            .query_wasm_smart(whitelist, &WhitelistQueryMsg::Config {})?;
+        check_whitelist_price(&wl_config.mint_price, &config.mint_price)?;
+    }
     CONFIG.save(deps.storage, &config)?;
     Ok(Response::new()
         .add_attribute("action", "update_mint_price")
@@ -704,6 +2835,25 @@ pub fn execute_update_start_time(
         return Err(ContractError::BeforeGenesisTime {});
     }
 
+    let factory: ParamsResponse = deps
+        .querier
+        .query_wasm_smart(config.factory.clone(), &Sg2QueryMsg::Params {})?;
+    check_start_time_offset(
+        &env,
+        start_time,
+        factory.params.extension.max_start_offset_secs,
+    )?;
+
+    if let Some(cooldown) = config.extension.start_time_update_cooldown_secs {
+        if let Some(last_update) = LAST_START_TIME_UPDATE.may_load(deps.storage)? {
+            let next_update_time = last_update.plus_seconds(cooldown);
+            if env.block.time < next_update_time {
+                return Err(ContractError::StartTimeUpdateCooldown { next_update_time });
+            }
+        }
+    }
+    LAST_START_TIME_UPDATE.save(deps.storage, &env.block.time)?;
+
     config.extension.start_time = start_time;
     CONFIG.save(deps.storage, &config)?;
     Ok(Response::new()
@@ -712,6 +2862,22 @@ pub fn execute_update_start_time(
         .add_attribute("start_time", start_time.to_string()))
 }
 
+// A start time too far out would leave the collection unmintable
+fn check_start_time_offset(
+    env: &Env,
+    start_time: Timestamp,
+    max_start_offset_secs: u64,
+) -> Result<(), ContractError> {
+    let max_start_time = env.block.time.plus_seconds(max_start_offset_secs);
+    if start_time > max_start_time {
+        return Err(ContractError::StartTimeTooFar {
+            max: max_start_time,
+            got: start_time,
+        });
+    }
+    Ok(())
+}
+
 pub fn execute_update_start_trading_time(
     deps: DepsMut,
     env: Env,
@@ -842,10 +3008,8 @@ pub fn execute_burn_remaining(
     let mut total: u32 = 0;
     for key in keys {
         total += 1;
-        MINTABLE_TOKEN_IDS.remove(deps.storage, key?);
+        consume_mintable_token_id(deps.storage, key?)?;
     }
-    // Decrement mintable num tokens
-    MINTABLE_NUM_TOKENS.save(deps.storage, &(mintable_num_tokens - total))?;
 
     let event = Event::new("burn-remaining")
         .add_attribute("sender", info.sender)
@@ -855,9 +3019,10 @@ pub fn execute_burn_remaining(
 }
 
 // if admin_no_fee => no fee,
+// else if phases => active phase price
 // else if in whitelist => whitelist price
 // else => config unit price
-pub fn mint_price(deps: Deps, is_admin: bool) -> Result<Coin, StdError> {
+pub fn mint_price(deps: Deps, env: &Env, is_admin: bool) -> Result<Coin, StdError> {
     let config = CONFIG.load(deps.storage)?;
 
     let factory: ParamsResponse = deps
@@ -872,7 +3037,19 @@ pub fn mint_price(deps: Deps, is_admin: bool) -> Result<Coin, StdError> {
         ));
     }
 
-    if config.extension.whitelist.is_none() {
+    mint_price_at(deps, env.block.time)
+}
+
+// Price of a non-admin mint at `now`
+fn mint_price_at(deps: Deps, now: Timestamp) -> StdResult<Coin> {
+    let config = CONFIG.load(deps.storage)?;
+
+    let phases = PHASES.load(deps.storage)?;
+    if let Some(phase) = active_phase(&phases, now) {
+        return Ok(phase.price.clone());
+    }
+
+    if config.extension.whitelist.is_none() || EARLY_PUBLIC.load(deps.storage)? {
         return Ok(config.mint_price);
     }
 
@@ -882,18 +3059,193 @@ pub fn mint_price(deps: Deps, is_admin: bool) -> Result<Coin, StdError> {
         .querier
         .query_wasm_smart(whitelist, &WhitelistQueryMsg::Config {})?;
 
-    if wl_config.is_active {
+    if whitelist_active_at(&wl_config, now) {
         Ok(wl_config.mint_price)
     } else {
         Ok(config.mint_price)
     }
 }
 
-fn mint_count(deps: Deps, info: &MessageInfo) -> Result<u32, StdError> {
-    let mint_count = (MINTER_ADDRS.key(&info.sender).may_load(deps.storage)?).unwrap_or(0);
+// Same window as the whitelist contract's `is_active`, at any time
+fn whitelist_active_at(wl_config: &WhitelistConfigResponse, now: Timestamp) -> bool {
+    now >= wl_config.start_time && now < wl_config.end_time
+}
+
+// Prices a mint can be paid with right now. The alternative prices only apply to the public mint.
+fn accepted_mint_prices(deps: Deps, env: &Env, is_admin: bool) -> StdResult<Vec<Coin>> {
+    let price = mint_price(deps, env, is_admin)?;
+    let config = CONFIG.load(deps.storage)?;
+    let phases = PHASES.load(deps.storage)?;
+    if is_admin || active_phase(&phases, env.block.time).is_some() || whitelist_is_active(deps)? {
+        return Ok(vec![price]);
+    }
+    Ok(std::iter::once(price)
+        .chain(config.extension.alt_mint_prices)
+        .collect())
+}
+
+// The accepted price in the denom of the funds sent. Without funds this is the mint price,
+// so `check_payment` reports the missing payment.
+fn payment_price(
+    deps: Deps,
+    env: &Env,
+    info: &MessageInfo,
+    is_admin: bool,
+) -> Result<Coin, ContractError> {
+    let prices = accepted_mint_prices(deps, env, is_admin)?;
+    match info.funds.first() {
+        Some(funds) => prices
+            .into_iter()
+            .find(|price| price.denom == funds.denom)
+            .ok_or(ContractError::UnsupportedDenom {}),
+        None => Ok(prices[0].clone()),
+    }
+}
+
+// Lowest allowed mint price in `denom`, from the factory params
+// and the minter's own `min_mint_price`
+fn min_mint_price(factory_min: &Coin, config: &Config, denom: &str) -> Uint128 {
+    [Some(factory_min), config.extension.min_mint_price.as_ref()]
+        .into_iter()
+        .flatten()
+        .filter(|min| min.denom == denom)
+        .map(|min| min.amount)
+        .max()
+        .unwrap_or_default()
+}
+
+struct ClaimWindow {
+    allocation: u32,
+    num_members: u32,
+    end: Timestamp,
+}
+
+// The claim window runs from the whitelist start time for `claim_window_secs`,
+// and closes early with the whitelist so the reserve never holds back the public
+fn claim_window(deps: Deps, config: &Config) -> StdResult<Option<ClaimWindow>> {
+    let (allocation, window_secs, whitelist) = match (
+        config.extension.claim_allocation,
+        config.extension.claim_window_secs,
+        &config.extension.whitelist,
+    ) {
+        (Some(allocation), Some(window_secs), Some(whitelist)) => {
+            (allocation, window_secs, whitelist)
+        }
+        _ => return Ok(None),
+    };
+    let wl_config: WhitelistConfigResponse = deps
+        .querier
+        .query_wasm_smart(whitelist, &WhitelistQueryMsg::Config {})?;
+    Ok(Some(ClaimWindow {
+        allocation,
+        num_members: wl_config.num_members,
+        end: wl_config
+            .start_time
+            .plus_seconds(window_secs)
+            .min(wl_config.end_time),
+    }))
+}
+
+fn active_claim_window(
+    deps: Deps,
+    config: &Config,
+    now: Timestamp,
+) -> StdResult<Option<ClaimWindow>> {
+    Ok(claim_window(deps, config)?.filter(|window| now < window.end))
+}
+
+// Allocation of all members that is still unclaimed
+fn unclaimed_reserve(deps: Deps, window: &ClaimWindow) -> StdResult<u32> {
+    let total_claimed = TOTAL_CLAIMED.load(deps.storage)?;
+    Ok(window
+        .allocation
+        .saturating_mul(window.num_members)
+        .saturating_sub(total_claimed))
+}
+
+fn whitelist_is_active(deps: Deps) -> StdResult<bool> {
+    if EARLY_PUBLIC.load(deps.storage)? {
+        return Ok(false);
+    }
+    let config = CONFIG.load(deps.storage)?;
+    match config.extension.whitelist {
+        Some(whitelist) => {
+            let wl_config: WhitelistConfigResponse = deps
+                .querier
+                .query_wasm_smart(whitelist, &WhitelistQueryMsg::Config {})?;
+            Ok(wl_config.is_active)
+        }
+        None => Ok(false),
+    }
+}
+
+// Per address limit that applies to a mint right now:
+// the active phase limit, or the stricter of the whitelist and public limits while the whitelist is active
+fn effective_per_address_limit(deps: Deps, env: &Env) -> StdResult<u32> {
+    let config = CONFIG.load(deps.storage)?;
+
+    let phases = PHASES.load(deps.storage)?;
+    if let Some(phase) = active_phase(&phases, env.block.time) {
+        return Ok(phase.per_address_limit);
+    }
+
+    if let (Some(whitelist), false) = (
+        &config.extension.whitelist,
+        EARLY_PUBLIC.load(deps.storage)?,
+    ) {
+        let wl_config: WhitelistConfigResponse = deps
+            .querier
+            .query_wasm_smart(whitelist, &WhitelistQueryMsg::Config {})?;
+        if wl_config.is_active {
+            return Ok(wl_config
+                .per_address_limit
+                .min(per_address_limit(&config, env.block.time)));
+        }
+    }
+
+    Ok(per_address_limit(&config, env.block.time))
+}
+
+// The configured per-address limit, raised to `early_per_address_limit` during the early window
+fn per_address_limit(config: &Config, now: Timestamp) -> u32 {
+    match (
+        config.extension.early_per_address_limit,
+        config.extension.early_window_secs,
+    ) {
+        (Some(early_limit), Some(window_secs))
+            if now < config.extension.start_time.plus_seconds(window_secs) =>
+        {
+            early_limit
+        }
+        _ => config.extension.per_address_limit,
+    }
+}
+
+// Adds `num_mints` to a mint counter, failing instead of overflowing
+fn add_mints(count: u32, num_mints: u32) -> Result<u32, ContractError> {
+    count
+        .checked_add(num_mints)
+        .ok_or(ContractError::MintCountOverflow {})
+}
+
+fn mint_count(deps: Deps, sender: &Addr) -> Result<u32, StdError> {
+    let mint_count = (MINTER_ADDRS.key(sender).may_load(deps.storage)?).unwrap_or(0);
     Ok(mint_count)
 }
 
+// Mints counted against the per-address limit. With `reset_limit_on_public`,
+// only public mints count once the public mint is open.
+fn limit_mint_count(deps: Deps, sender: &Addr, is_public: bool) -> StdResult<u32> {
+    let config = CONFIG.load(deps.storage)?;
+    if is_public && config.extension.reset_limit_on_public {
+        Ok(PUBLIC_MINTER_ADDRS
+            .may_load(deps.storage, sender)?
+            .unwrap_or_default())
+    } else {
+        mint_count(deps, sender)
+    }
+}
+
 // Check per address limit to make sure it's <= 1% num tokens
 fn check_dynamic_per_address_limit(
     per_address_limit: u32,
@@ -915,7 +3267,28 @@ pub fn sudo(deps: DepsMut, _env: Env, msg: SudoMsg) -> Result<Response, Contract
             is_explicit,
         } => update_status(deps, is_verified, is_blocked, is_explicit)
             .map_err(|_| ContractError::UpdateStatus {}),
+        SudoMsg::UpdateFullyFrozen { fully_frozen } => {
+            FULLY_FROZEN.save(deps.storage, &fully_frozen)?;
+            Ok(Response::new()
+                .add_attribute("action", "sudo_update_fully_frozen")
+                .add_attribute("fully_frozen", fully_frozen.to_string()))
+        }
+    }
+}
+
+// Minters instantiated before the full freeze existed have no stored flag
+fn check_not_fully_frozen(storage: &dyn Storage) -> Result<(), ContractError> {
+    if FULLY_FROZEN.may_load(storage)?.unwrap_or_default() {
+        return Err(ContractError::FullyFrozen {});
+    }
+    Ok(())
+}
+
+fn check_launch_not_cancelled(storage: &dyn Storage) -> Result<(), ContractError> {
+    if LAUNCH_CANCELLED.may_load(storage)?.unwrap_or_default() {
+        return Err(ContractError::LaunchCancelled {});
     }
+    Ok(())
 }
 
 /// Only governance can update contract params
@@ -929,19 +3302,57 @@ pub fn update_status(
     status.is_verified = is_verified;
     status.is_blocked = is_blocked;
     status.is_explicit = is_explicit;
+    STATUS.save(deps.storage, &status)?;
 
     Ok(Response::new().add_attribute("action", "sudo_update_status"))
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::Config {} => to_binary(&query_config(deps)?),
         QueryMsg::Status {} => to_binary(&query_status(deps)?),
         QueryMsg::StartTime {} => to_binary(&query_start_time(deps)?),
         QueryMsg::MintableNumTokens {} => to_binary(&query_mintable_num_tokens(deps)?),
-        QueryMsg::MintPrice {} => to_binary(&query_mint_price(deps)?),
+        QueryMsg::SupplyConsistency {} => to_binary(&query_supply_consistency(deps)?),
+        QueryMsg::MintPrice {} => to_binary(&query_mint_price(deps, env)?),
         QueryMsg::MintCount { address } => to_binary(&query_mint_count(deps, address)?),
+        QueryMsg::MintCounts { addresses } => to_binary(&query_mint_counts(deps, addresses)?),
+        QueryMsg::Phases {} => to_binary(&query_phases(deps, env)?),
+        QueryMsg::MintInfo { address } => to_binary(&query_mint_info(deps, env, address)?),
+        QueryMsg::EligibilityAt { address, at } => {
+            to_binary(&query_eligibility_at(deps, env, address, at)?)
+        }
+        QueryMsg::CanBatchMint { address, num_mints } => {
+            to_binary(&query_can_batch_mint(deps, env, address, num_mints)?)
+        }
+        QueryMsg::NextTokenId {} => to_binary(&query_next_token_id(deps)?),
+        QueryMsg::IsTokenMintable { token_id } => {
+            to_binary(&query_is_token_mintable(deps, token_id)?)
+        }
+        QueryMsg::ReferralRewards { address } => to_binary(&query_referral_rewards(deps, address)?),
+        QueryMsg::UniqueMinters {} => to_binary(&query_unique_minters(deps)?),
+        QueryMsg::MintHistory { start_after, limit } => {
+            to_binary(&query_mint_history(deps, start_after, limit)?)
+        }
+        QueryMsg::AllowlistOnly {} => to_binary(&query_allowlist_only(deps)?),
+        QueryMsg::AcceptedDenoms {} => to_binary(&query_accepted_denoms(deps, env)?),
+        QueryMsg::Admin {} => to_binary(&query_admin(deps)?),
+        QueryMsg::ContractVersion {} => to_binary(&get_contract_version(deps.storage)?),
+        QueryMsg::NetworkFees {} => to_binary(&query_network_fees(deps)?),
+        QueryMsg::PayoutTotals {} => to_binary(&query_payout_totals(deps)?),
+        QueryMsg::TokenSeed { token_id } => to_binary(&query_token_seed(deps, token_id)?),
+        QueryMsg::ClaimAllocation { address } => {
+            to_binary(&query_claim_allocation(deps, env, address)?)
+        }
+        QueryMsg::MintReceipt { token_id } => to_binary(&query_mint_receipt(deps, token_id)?),
+        QueryMsg::CurrentPhase {} => to_binary(&query_current_phase(deps, env)?),
+        QueryMsg::EffectivePerAddressLimit {} => {
+            to_binary(&query_effective_per_address_limit(deps, env)?)
+        }
+        QueryMsg::ReservedTokens { start_after, limit } => {
+            to_binary(&query_reserved_tokens(deps, start_after, limit)?)
+        }
     }
 }
 
@@ -951,6 +3362,7 @@ fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
 
     Ok(ConfigResponse {
         admin: config.extension.admin.to_string(),
+        creator: config.extension.creator.to_string(),
         base_token_uri: config.extension.base_token_uri,
         sg721_address: sg721_address.to_string(),
         sg721_code_id: config.collection_code_id,
@@ -960,6 +3372,43 @@ fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
         per_address_limit: config.extension.per_address_limit,
         whitelist: config.extension.whitelist.map(|w| w.to_string()),
         factory: config.factory.to_string(),
+        soft_cap: config.extension.soft_cap,
+        batch_mint_enabled: config.extension.batch_mint_enabled,
+        record_history: config.extension.record_history,
+        mint_to_allowlist: config
+            .extension
+            .mint_to_allowlist
+            .map(|addrs| addrs.iter().map(|addr| addr.to_string()).collect()),
+        vesting: config.extension.vesting,
+        referral_bps: config.extension.referral_bps,
+        whitelist_total_cap: config.extension.whitelist_total_cap,
+        reroll_fee: config.extension.reroll_fee,
+        allocation_merkle_root: config.extension.allocation_merkle_root,
+        global_mint_interval_secs: config.extension.global_mint_interval_secs,
+        auto_withdraw_threshold: config.extension.auto_withdraw_threshold,
+        allow_contract_recipients: config.extension.allow_contract_recipients,
+        public_on_whitelist_sellout: config.extension.public_on_whitelist_sellout,
+        reveal_deadline: config.extension.reveal_deadline,
+        payout_splits: config.extension.payout_splits,
+        freeze_delay_secs: config.extension.freeze_delay_secs,
+        token_uri_suffix: config.extension.token_uri_suffix,
+        reset_limit_on_public: config.extension.reset_limit_on_public,
+        min_mint_price: config.extension.min_mint_price,
+        display_denom: config.extension.display_denom,
+        exponent: config.extension.exponent,
+        block_privileged_referrals: config.extension.block_privileged_referrals,
+        early_per_address_limit: config.extension.early_per_address_limit,
+        early_window_secs: config.extension.early_window_secs,
+        start_time_update_cooldown_secs: config.extension.start_time_update_cooldown_secs,
+        presale_deposits: config.extension.presale_deposits,
+        record_receipts: config.extension.record_receipts,
+        require_height_gate: config.extension.require_height_gate,
+        daily_mint_limit: config.extension.daily_mint_limit,
+        proceeds_target: config.extension.proceeds_target,
+        generative: config.extension.generative,
+        claim_allocation: config.extension.claim_allocation,
+        claim_window_secs: config.extension.claim_window_secs,
+        alt_mint_prices: config.extension.alt_mint_prices,
     })
 }
 
@@ -978,6 +3427,19 @@ fn query_mint_count(deps: Deps, address: String) -> StdResult<MintCountResponse>
     })
 }
 
+fn query_mint_counts(deps: Deps, addresses: Vec<String>) -> StdResult<Vec<MintCountResponse>> {
+    if addresses.len() > MAX_MINT_COUNTS_ADDRESSES {
+        return Err(StdError::generic_err(format!(
+            "Too many addresses. max: {}",
+            MAX_MINT_COUNTS_ADDRESSES
+        )));
+    }
+    addresses
+        .into_iter()
+        .map(|address| query_mint_count(deps, address))
+        .collect()
+}
+
 fn query_start_time(deps: Deps) -> StdResult<StartTimeResponse> {
     let config = CONFIG.load(deps.storage)?;
     Ok(StartTimeResponse {
@@ -990,7 +3452,312 @@ fn query_mintable_num_tokens(deps: Deps) -> StdResult<MintableNumTokensResponse>
     Ok(MintableNumTokensResponse { count })
 }
 
-fn query_mint_price(deps: Deps) -> StdResult<MintPriceResponse> {
+fn query_supply_consistency(deps: Deps) -> StdResult<SupplyConsistencyResponse> {
+    let mintable_num_tokens = MINTABLE_NUM_TOKENS.load(deps.storage)?;
+    let mintable_token_ids = MINTABLE_TOKEN_IDS
+        .keys(deps.storage, None, None, Order::Ascending)
+        .count() as u32;
+    Ok(SupplyConsistencyResponse {
+        mintable_num_tokens,
+        mintable_token_ids,
+    })
+}
+
+fn query_mint_history(
+    deps: Deps,
+    start_after: Option<u32>,
+    limit: Option<u32>,
+) -> StdResult<MintHistoryResponse> {
+    let limit = limit
+        .unwrap_or(PAGINATION_DEFAULT_LIMIT)
+        .min(PAGINATION_MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+    let mints = MINT_EVENTS
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            let (token_id, (minter, time)) = item?;
+            Ok(MintEvent {
+                token_id,
+                minter: minter.to_string(),
+                time,
+            })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(MintHistoryResponse { mints })
+}
+
+fn query_admin(deps: Deps) -> StdResult<AdminResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    Ok(AdminResponse {
+        admin: config.extension.admin.to_string(),
+    })
+}
+
+fn query_accepted_denoms(deps: Deps, env: Env) -> StdResult<AcceptedDenomsResponse> {
+    Ok(AcceptedDenomsResponse {
+        prices: accepted_mint_prices(deps, &env, false)?,
+    })
+}
+
+fn query_allowlist_only(deps: Deps) -> StdResult<AllowlistOnlyResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    let allowlist_only = match config.extension.whitelist {
+        Some(whitelist) => {
+            let wl_config: WhitelistConfigResponse = deps
+                .querier
+                .query_wasm_smart(whitelist, &WhitelistQueryMsg::Config {})?;
+            wl_config.end_time == NEVER_EXPIRES
+        }
+        None => false,
+    };
+    Ok(AllowlistOnlyResponse { allowlist_only })
+}
+
+fn query_effective_per_address_limit(
+    deps: Deps,
+    env: Env,
+) -> StdResult<EffectivePerAddressLimitResponse> {
+    Ok(EffectivePerAddressLimitResponse {
+        per_address_limit: effective_per_address_limit(deps, &env)?,
+    })
+}
+
+fn query_reserved_tokens(
+    deps: Deps,
+    start_after: Option<u32>,
+    limit: Option<u32>,
+) -> StdResult<ReservedTokensResponse> {
+    let limit = limit
+        .unwrap_or(PAGINATION_DEFAULT_LIMIT)
+        .min(PAGINATION_MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+    let token_ids = RESERVED_TOKEN_IDS
+        .keys(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(ReservedTokensResponse { token_ids })
+}
+
+fn query_mint_info(deps: Deps, env: Env, address: Option<String>) -> StdResult<MintInfoResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    let address = maybe_addr(deps.api, address)?;
+    let current_price = mint_price(deps, &env, false)?;
+
+    let reason = if let Some(address) = &address {
+        check_sender_can_mint(deps, &env, address, 1).err()
+    } else if let Err(err) =
+        check_mint_open(deps, env.block.height).and_then(|_| check_tokens_left(deps.storage, 1))
+    {
+        Some(err)
+    } else {
+        let phases = PHASES.load(deps.storage)?;
+        let started = if phases.is_empty() {
+            env.block.time >= config.extension.start_time || whitelist_is_active(deps)?
+        } else {
+            active_phase(&phases, env.block.time).is_some()
+        };
+        (!started).then_some(ContractError::BeforeMintStartTime {})
+    };
+
+    let remaining_for_address = match &address {
+        Some(address) => {
+            let limit = effective_per_address_limit(deps, &env)?;
+            let phases = PHASES.load(deps.storage)?;
+            let mint_count = if phases.is_empty() {
+                limit_mint_count(deps, address, !whitelist_is_active(deps)?)?
+            } else {
+                mint_count(deps, address)?
+            };
+            Some(limit.saturating_sub(mint_count))
+        }
+        None => None,
+    };
+
+    Ok(MintInfoResponse {
+        current_price,
+        can_mint: reason.is_none(),
+        reason: reason.map(|err| err.to_string()),
+        remaining_for_address,
+    })
+}
+
+fn query_can_batch_mint(
+    deps: Deps,
+    env: Env,
+    address: String,
+    num_mints: u32,
+) -> StdResult<CanBatchMintResponse> {
+    let address = deps.api.addr_validate(&address)?;
+    let reason = check_batch_mint(deps, &env, &address, num_mints).err();
+
+    Ok(CanBatchMintResponse {
+        allowed: reason.is_none(),
+        reason: reason.map(|err| err.to_string()),
+    })
+}
+
+// Runs the mint checks at `at`, with the current mint counts and block height
+fn query_eligibility_at(
+    deps: Deps,
+    env: Env,
+    address: String,
+    at: Timestamp,
+) -> StdResult<EligibilityAtResponse> {
+    let address = deps.api.addr_validate(&address)?;
+    let reason = check_eligibility(deps, at, env.block.height, &address, 1).err();
+
+    Ok(EligibilityAtResponse {
+        eligible: reason.is_none(),
+        price: mint_price_at(deps, at)?,
+        reason: reason.map(|err| err.to_string()),
+    })
+}
+
+fn query_next_token_id(deps: Deps) -> StdResult<NextTokenIdResponse> {
+    // Public mints take the lowest mintable id
+    let mintable_token_id = MINTABLE_TOKEN_IDS
+        .keys(deps.storage, None, None, Order::Ascending)
+        .next()
+        .transpose()?;
+    let base_token_id = BASE_TOKEN_ID.load(deps.storage)?;
+    Ok(NextTokenIdResponse {
+        token_id: mintable_token_id.map(|token_id| token_id + base_token_id),
+    })
+}
+
+fn query_is_token_mintable(deps: Deps, token_id: u32) -> StdResult<IsTokenMintableResponse> {
+    // Ids of earlier rounds are below the base and never mintable
+    let base_token_id = BASE_TOKEN_ID.load(deps.storage)?;
+    Ok(IsTokenMintableResponse {
+        mintable: token_id
+            .checked_sub(base_token_id)
+            .is_some_and(|id| MINTABLE_TOKEN_IDS.has(deps.storage, id)),
+    })
+}
+
+fn query_referral_rewards(deps: Deps, address: String) -> StdResult<ReferralRewardsResponse> {
+    let addr = deps.api.addr_validate(&address)?;
+    let rewards = REFERRAL_REWARDS
+        .prefix(&addr)
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| {
+            let (denom, amount) = item?;
+            Ok(coin(amount.u128(), denom))
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok(ReferralRewardsResponse {
+        address: addr.to_string(),
+        rewards,
+    })
+}
+
+fn query_network_fees(deps: Deps) -> StdResult<NetworkFeesResponse> {
+    let fees = NETWORK_FEES
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| {
+            let (denom, amount) = item?;
+            Ok(coin(amount.u128(), denom))
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok(NetworkFeesResponse { fees })
+}
+
+fn query_claim_allocation(
+    deps: Deps,
+    env: Env,
+    address: String,
+) -> StdResult<ClaimAllocationResponse> {
+    let address = deps.api.addr_validate(&address)?;
+    let config = CONFIG.load(deps.storage)?;
+    let claimed = CLAIMED_ALLOCATIONS
+        .may_load(deps.storage, &address)?
+        .unwrap_or_default();
+    let total_claimed = TOTAL_CLAIMED.load(deps.storage)?;
+    let window = claim_window(deps, &config)?;
+    // The reserve is released once the window closes
+    let unclaimed_reserve = match &window {
+        Some(window) if env.block.time < window.end => unclaimed_reserve(deps, window)?,
+        _ => 0,
+    };
+
+    Ok(ClaimAllocationResponse {
+        allocation: window.as_ref().map(|w| w.allocation).unwrap_or_default(),
+        claimed,
+        unclaimed_reserve,
+        total_claimed,
+        window_end: window.map(|w| w.end),
+    })
+}
+
+fn query_token_seed(deps: Deps, token_id: u32) -> StdResult<TokenSeedResponse> {
+    Ok(TokenSeedResponse {
+        token_id,
+        seed: TOKEN_SEEDS.may_load(deps.storage, token_id)?,
+    })
+}
+
+fn query_mint_receipt(deps: Deps, token_id: u32) -> StdResult<MintReceiptResponse> {
+    Ok(MintReceiptResponse {
+        token_id,
+        receipt: MINT_RECEIPTS.may_load(deps.storage, token_id)?,
+    })
+}
+
+fn query_payout_totals(deps: Deps) -> StdResult<PayoutTotalsResponse> {
+    let totals = PAYOUT_TOTALS
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| {
+            let ((address, denom), amount) = item?;
+            Ok(PayoutTotal {
+                address: address.to_string(),
+                amount: coin(amount.u128(), denom),
+            })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok(PayoutTotalsResponse { totals })
+}
+
+fn query_unique_minters(deps: Deps) -> StdResult<UniqueMintersResponse> {
+    let count = UNIQUE_MINTERS.load(deps.storage)?;
+    Ok(UniqueMintersResponse { count })
+}
+
+fn query_current_phase(deps: Deps, env: Env) -> StdResult<CurrentPhaseResponse> {
+    let phases = PHASES.load(deps.storage)?;
+    let requires_allowlist = match active_phase(&phases, env.block.time) {
+        Some(phase) => phase.allowlist.is_some(),
+        None => whitelist_is_active(deps)?,
+    };
+    Ok(CurrentPhaseResponse {
+        requires_allowlist,
+        price: mint_price(deps, &env, false)?,
+        per_address_limit: effective_per_address_limit(deps, &env)?,
+    })
+}
+
+fn query_phases(deps: Deps, env: Env) -> StdResult<PhasesResponse> {
+    let phases = PHASES.load(deps.storage)?;
+    let active_start_time = active_phase(&phases, env.block.time).map(|phase| phase.start_time);
+    let phases = phases
+        .into_iter()
+        .enumerate()
+        .map(|(index, phase)| PhaseResponse {
+            index: index as u32,
+            active: Some(phase.start_time) == active_start_time,
+            start_time: phase.start_time,
+            price: phase.price,
+            per_address_limit: phase.per_address_limit,
+            requires_allowlist: phase.allowlist.is_some(),
+            allowlist: phase.allowlist.map(|allowlist| allowlist.to_string()),
+        })
+        .collect();
+    Ok(PhasesResponse { phases })
+}
+
+fn query_mint_price(deps: Deps, env: Env) -> StdResult<MintPriceResponse> {
     let config = CONFIG.load(deps.storage)?;
 
     let factory: ParamsResponse = deps
@@ -999,7 +3766,7 @@ fn query_mint_price(deps: Deps) -> StdResult<MintPriceResponse> {
 
     let factory_params = factory.params;
 
-    let current_price = mint_price(deps, false)?;
+    let current_price = mint_price(deps, &env, false)?;
     let public_price = config.mint_price.clone();
     let whitelist_price: Option<Coin> = if let Some(whitelist) = config.extension.whitelist {
         let wl_config: WhitelistConfigResponse = deps
@@ -1018,14 +3785,35 @@ fn query_mint_price(deps: Deps) -> StdResult<MintPriceResponse> {
         airdrop_price,
         whitelist_price,
         current_price,
+        display_denom: config.extension.display_denom,
+        exponent: config.extension.exponent,
     })
 }
 
 // Reply callback triggered from cw721 contract instantiation
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractError> {
-    if msg.id != INSTANTIATE_SG721_REPLY_ID {
-        return Err(ContractError::InvalidReplyID {});
+pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> Result<Response, ContractError> {
+    match msg.id {
+        INSTANTIATE_SG721_REPLY_ID => reply_instantiate_sg721(deps, env, msg),
+        PAYOUT_REPLY_ID => reply_payout(msg),
+        id => Err(ContractError::InvalidReplyID { id }),
+    }
+}
+
+// The payout submessage only replies on error
+fn reply_payout(msg: Reply) -> Result<Response, ContractError> {
+    match msg.result {
+        SubMsgResult::Err(reason) => Err(ContractError::PayoutFailed { reason }),
+        SubMsgResult::Ok(_) => Ok(Response::default()),
+    }
+}
+
+fn reply_instantiate_sg721(deps: DepsMut, env: Env, msg: Reply) -> Result<Response, ContractError> {
+    // Keep the collection msg and leave the collection unset so the admin can retry
+    if let SubMsgResult::Err(reason) = &msg.result {
+        return Ok(Response::default()
+            .add_attribute("action", "instantiate_sg721_reply")
+            .add_attribute("error", reason));
     }
 
     let reply = parse_reply_instantiate_data(msg);
@@ -1033,10 +3821,58 @@ pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractE
         Ok(res) => {
             let sg721_address = res.contract_address;
             SG721_ADDRESS.save(deps.storage, &Addr::unchecked(sg721_address.clone()))?;
-            Ok(Response::default()
+            COLLECTION_INSTANTIATE_MSG.remove(deps.storage);
+
+            // Mint the reserved token ids to the creator
+            let config = CONFIG.load(deps.storage)?;
+            let creator_reserve = CREATOR_RESERVE.load(deps.storage)?;
+            let mut res = Response::default();
+            for token_id in 1..=creator_reserve {
+                let mint_msg = Sg721ExecuteMsg::<Extension, Empty>::Mint(MintMsg::<Extension> {
+                    token_id: token_id.to_string(),
+                    owner: config.extension.creator.to_string(),
+                    token_uri: Some(token_uri(&config, token_id)),
+                    extension: None,
+                });
+                res = res.add_message(WasmMsg::Execute {
+                    contract_addr: sg721_address.clone(),
+                    msg: to_binary(&mint_msg)?,
+                    funds: vec![],
+                });
+                if config.extension.generative {
+                    TOKEN_SEEDS.save(deps.storage, token_id, &token_seed(&env, token_id))?;
+                }
+            }
+            TOTAL_MINTED.update(deps.storage, |total| add_mints(total, creator_reserve))?;
+
+            Ok(res
                 .add_attribute("action", "instantiate_sg721_reply")
-                .add_attribute("sg721_address", sg721_address))
+                .add_attribute("sg721_address", sg721_address)
+                .add_attribute("creator_reserve", creator_reserve.to_string()))
         }
         Err(_) => Err(ContractError::InstantiateSg721Error {}),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mint_counter_overflow() {
+        // Counting many batches against a huge limit never panics
+        let mut count = u32::MAX - 100;
+        for _ in 0..2 {
+            count = add_mints(count, 50).unwrap();
+        }
+        assert_eq!(count, u32::MAX);
+        assert_eq!(
+            add_mints(count, 1).unwrap_err(),
+            ContractError::MintCountOverflow {}
+        );
+        assert_eq!(
+            add_mints(u32::MAX - 10, 50).unwrap_err(),
+            ContractError::MintCountOverflow {}
+        );
+    }
+}
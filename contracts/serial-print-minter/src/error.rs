@@ -1,4 +1,4 @@
-use cosmwasm_std::{Coin, StdError, Timestamp};
+use cosmwasm_std::{Coin, Decimal, StdError, Timestamp};
 use cw_utils::PaymentError;
 use sg1::FeeError;
 use thiserror::Error;
@@ -24,8 +24,8 @@ pub enum ContractError {
     #[error("UpdateStatus")]
     UpdateStatus {},
 
-    #[error("Invalid reply ID")]
-    InvalidReplyID {},
+    #[error("Invalid reply ID: {id}")]
+    InvalidReplyID { id: u64 },
 
     #[error("Not enough funds sent")]
     NotEnoughFunds {},
@@ -37,11 +37,20 @@ pub enum ContractError {
     IncorrectPaymentAmount(Coin, Coin),
 
     #[error("InvalidNumTokens min: 1")]
-    InvalidNumTokens { },
+    InvalidNumTokens {},
+
+    #[error("Max token limit of {max} exceeded")]
+    MaxTokenLimitExceeded { max: u32 },
+
+    #[error("Invalid creator reserve. max: {max}, got: {got}")]
+    InvalidCreatorReserve { max: u32, got: u32 },
 
     #[error("Sold out")]
     SoldOut {},
 
+    #[error("Mintable token count and mintable token ids are out of sync")]
+    InconsistentState {},
+
     #[error("Not sold out")]
     NotSoldOut {},
 
@@ -51,6 +60,9 @@ pub enum ContractError {
     #[error("Minimum network mint price {expected} got {got}")]
     InsufficientMintPrice { expected: u128, got: u128 },
 
+    #[error("Mint price {got} is above the maximum of {max}")]
+    MintPriceTooHigh { max: u128, got: u128 },
+
     #[error("Update price {updated} higher than allowed price {allowed}")]
     UpdatedMintPriceTooHigh { allowed: u128, updated: u128 },
 
@@ -72,27 +84,81 @@ pub enum ContractError {
     #[error("InvalidStartTime {0} < {1}")]
     InvalidStartTime(Timestamp, Timestamp),
 
+    #[error("Start time {got} is after the latest allowed start time {max}")]
+    StartTimeTooFar { max: Timestamp, got: Timestamp },
+
+    #[error("Start time was updated recently. Next update allowed at {next_update_time}")]
+    StartTimeUpdateCooldown { next_update_time: Timestamp },
+
     #[error("InvalidStartTradingTime {0} < {1}")]
     InvalidStartTradingTime(Timestamp, Timestamp),
 
+    #[error("Payout of mint proceeds failed: {reason}")]
+    PayoutFailed { reason: String },
+
     #[error("Instantiate sg721 error")]
     InstantiateSg721Error {},
 
+    #[error("Collection already instantiated")]
+    CollectionAlreadyInstantiated {},
+
+    #[error("Invalid collection name. Must be 1 to {max_length} characters")]
+    InvalidCollectionName { max_length: usize },
+
+    #[error("Invalid collection symbol. Must be 1 to {max_length} characters")]
+    InvalidCollectionSymbol { max_length: usize },
+
     #[error("Invalid base token URI (must be an IPFS URI)")]
     InvalidBaseTokenURI {},
 
+    #[error("Invalid token URI suffix (must start with a dot)")]
+    InvalidTokenUriSuffix {},
+
+    #[error("Invalid freeze delay (must be greater than 0)")]
+    InvalidFreezeDelay {},
+
     #[error("address not on whitelist: {addr}")]
     NotWhitelisted { addr: String },
 
     #[error("Minting has not started yet")]
     BeforeMintStartTime {},
 
+    #[error("Minting opens at block height {height}")]
+    BeforeMintStartHeight { height: u64 },
+
     #[error("Invalid minting limit per address. max: {max}, min: 1, got: {got}")]
     InvalidPerAddressLimit { max: u32, min: u32, got: u32 },
 
+    #[error("Whitelist sales cap of {cap} reached")]
+    WhitelistCapReached { cap: u32 },
+
     #[error("Max minting limit per address exceeded")]
     MaxPerAddressLimitExceeded {},
 
+    #[error("Daily mint limit of {limit} exceeded")]
+    DailyLimitExceeded { limit: u32 },
+
+    #[error("claim_allocation and claim_window_secs must be set together and above 0")]
+    InvalidClaimWindow {},
+
+    #[error("early_per_address_limit and early_window_secs must be set together and above 0")]
+    InvalidEarlyWindow {},
+
+    #[error("Claim allocation of {allocation} is above the whitelist per address limit or the members can't all be served")]
+    InvalidClaimAllocation { allocation: u32 },
+
+    #[error("Claim allocation of {allocation} exceeded")]
+    ClaimAllocationExceeded { allocation: u32 },
+
+    #[error("Only {available} tokens are available until the claim window closes")]
+    ReservedForClaims { available: u32 },
+
+    #[error("Mint count overflow")]
+    MintCountOverflow {},
+
+    #[error("Recipient {recipient} is not on the mint to allowlist")]
+    RecipientNotAllowed { recipient: String },
+
     #[error("Token id: {token_id} already sold")]
     TokenIdAlreadySold { token_id: u32 },
 
@@ -101,4 +167,145 @@ pub enum ContractError {
 
     #[error("Minting Paused by owner")]
     MintingPaused {},
+
+    #[error("Minter is frozen by governance")]
+    FullyFrozen {},
+
+    #[error("The launch was cancelled")]
+    LaunchCancelled {},
+
+    #[error("The launch can't be cancelled once tokens are minted")]
+    CancelAfterMint {},
+
+    #[error("Soft cap not reached. minted: {minted}, soft cap: {soft_cap}")]
+    SoftCapNotReached { minted: u32, soft_cap: u32 },
+
+    #[error("No funds to withdraw")]
+    NoFundsToWithdraw {},
+
+    #[error("Invalid referral bps. max: {max}, got: {got}")]
+    InvalidReferralBps { max: u64, got: u64 },
+
+    #[error("Invalid factory mint fee bps. max: 10000, got: {got}")]
+    InvalidMintFeeBps { got: u64 },
+
+    #[error("Invalid referral {referral}")]
+    InvalidReferral { referral: String },
+
+    #[error("Invalid vesting. The cliff must be between the start time and the end of the vesting duration")]
+    InvalidVesting {},
+
+    #[error("Batch minting is disabled")]
+    BatchMintDisabled {},
+
+    #[error("Number of mints must be at least 1")]
+    InvalidNumMints {},
+
+    #[error("Phases must start at or after the mint start time, in strictly increasing order")]
+    InvalidPhaseOrder {},
+
+    #[error("Phase allowlist {allowlist} is not a whitelist contract")]
+    InvalidPhaseAllowlist { allowlist: String },
+
+    #[error("Too many phases. max: {max}, got: {got}")]
+    TooManyPhases { max: usize, got: usize },
+
+    #[error("Rerolling is disabled")]
+    RerollDisabled {},
+
+    #[error("Token id {token_id} is listed more than once")]
+    DuplicateTokenId { token_id: u32 },
+
+    #[error("Reserved token id {token_id} is not a mintable token id")]
+    InvalidReservedTokenId { token_id: u32 },
+
+    #[error("Token id {token_id} is not reserved")]
+    TokenIdNotReserved { token_id: u32 },
+
+    #[error("Not enough tokens remaining. requested: {requested}, remaining: {remaining}")]
+    NotEnoughTokensRemaining { requested: u32, remaining: u32 },
+
+    #[error("Merkle allocations are not enabled")]
+    MerkleDisabled {},
+
+    #[error("Invalid Merkle proof")]
+    InvalidMerkleProof {},
+
+    #[error("Minting is paced. Next mint allowed at {next_mint_time}")]
+    GlobalMintIntervalNotElapsed { next_mint_time: Timestamp },
+
+    #[error("This collection can only be minted by whitelist members and {addr} is not one. There is no public sale")]
+    AllowlistOnly { addr: String },
+
+    #[error("Minting to contract {recipient} is not allowed")]
+    ContractRecipientNotAllowed { recipient: String },
+
+    #[error("An airdrop is in progress with {remaining} recipients left")]
+    AirdropInProgress { remaining: u32 },
+
+    #[error("No airdrop in progress")]
+    NoAirdropInProgress {},
+
+    #[error("Too many airdrop recipients in one load. max: {max}")]
+    TooManyAirdropRecipients { max: u32 },
+
+    #[error("Too many mints in one transaction. max: {max}")]
+    TooManyMintsPerTx { max: u32 },
+
+    #[error("Whitelist price {whitelist_price} is above the public price {public_price}")]
+    WhitelistPriceAbovePublicPrice {
+        whitelist_price: Coin,
+        public_price: Coin,
+    },
+
+    #[error("The collection has not been revealed yet")]
+    RevealPending {},
+
+    #[error("The reveal deadline has passed")]
+    RevealDeadlinePassed {},
+
+    #[error("No refund available")]
+    RefundNotAvailable {},
+
+    #[error("The collection info is frozen, the base token uri can't change")]
+    CollectionInfoFrozen {},
+
+    #[error("Freeze already requested")]
+    FreezeAlreadyRequested {},
+
+    #[error("No freeze requested")]
+    NoFreezeRequested {},
+
+    #[error("Freeze can only be confirmed after {confirm_time}")]
+    FreezeDelayNotElapsed { confirm_time: Timestamp },
+
+    #[error("Invalid royalty share. Must be at most 100%")]
+    InvalidRoyaltyShare {},
+
+    #[error("Royalty share {got} is above the maximum of {max}")]
+    RoyaltyTooHigh { max: Decimal, got: Decimal },
+
+    #[error("Royalty share is zero. Leave royalty_info unset for a collection without royalties")]
+    ZeroRoyaltyShare {},
+
+    #[error("Presale deposits are not enabled")]
+    PresaleDepositsDisabled {},
+
+    #[error("No deposit")]
+    NoDeposit {},
+
+    #[error("Alternative mint price in {denom} must be above 0 and the only price in its denom")]
+    InvalidAltMintPrice { denom: String },
+
+    #[error("alt_mint_prices can't be combined with a soft cap, vesting, a reveal deadline or an auto withdraw threshold")]
+    AltMintPricesWithHeldProceeds {},
+
+    #[error("Payment denom is not accepted")]
+    UnsupportedDenom {},
+
+    #[error("Set either payout_splits or proceeds_target, not both")]
+    ConflictingProceedsTarget {},
+
+    #[error("Invalid payout splits. Shares must be non-zero, add up to 10000 bps and go to distinct addresses")]
+    InvalidPayoutSplits {},
 }
@@ -1,31 +1,47 @@
-use crate::contract::instantiate;
+use crate::contract::{instantiate, reply};
 use crate::msg::{
-    ConfigResponse, ExecuteMsg, MintCountResponse, MintPriceResponse, MintableNumTokensResponse,
-    QueryMsg, StartTimeResponse,
+    AcceptedDenomsResponse, AdminResponse, AllowlistOnlyResponse, CanBatchMintResponse,
+    ClaimAllocationResponse, ConfigResponse, CurrentPhaseResponse,
+    EffectivePerAddressLimitResponse, EligibilityAtResponse, ExecuteMsg, IsTokenMintableResponse,
+    MintCountResponse, MintHistoryResponse, MintInfoResponse, MintPriceResponse,
+    MintReceiptResponse, MintableNumTokensResponse, NetworkFeesResponse, NextTokenIdResponse,
+    PayoutTotal, PayoutTotalsResponse, PhasesResponse, QueryMsg, ReferralRewardsResponse,
+    ReservedTokensResponse, StartTimeResponse, SudoMsg as MinterSudoMsg, SupplyConsistencyResponse,
+    TokenSeedResponse, UniqueMintersResponse,
 };
+use crate::state::MintReceipt;
 use crate::ContractError;
+use cosmwasm_schema::cw_serde;
 use cosmwasm_std::testing::{mock_dependencies_with_balance, mock_env, mock_info};
-use cosmwasm_std::{coin, coins, Addr, Empty, Timestamp, Uint128};
-use cosmwasm_std::{Api, Coin};
+use cosmwasm_std::{coin, coins, Addr, Empty, Reply, StdResult, SubMsgResult, Timestamp, Uint128};
+use cosmwasm_std::{
+    to_binary, Api, Binary, Coin, Decimal, Deps, DepsMut, Env, HexBinary, MessageInfo, Response,
+};
 use cw4::Member;
 use cw721::{Cw721QueryMsg, NftInfoResponse, OwnerOfResponse, TokensResponse};
 use cw721_base::ExecuteMsg as Cw721ExecuteMsg;
 use cw_multi_test::{
     next_block, AppResponse, BankSudo, Contract, ContractWrapper, Executor, SudoMsg,
 };
-use serial_print_factory::msg::{VendingMinterCreateMsg, VendingMinterInitMsgExtension};
+use serial_print_factory::msg::{
+    PayoutSplitMsg, PhaseMsg, ProceedsTargetMsg, VendingMinterCreateMsg,
+    VendingMinterInitMsgExtension, VestingConfig,
+};
 use serial_print_factory::state::{ParamsExtension, VendingMinterParams};
 use sg2::msg::Sg2ExecuteMsg;
 use sg2::tests::mock_collection_params;
-use sg721_base::msg::{CollectionInfoResponse, QueryMsg as Sg721QueryMsg};
+use sg721::{ExecuteMsg as Sg721ExecuteMsg, RoyaltyInfoResponse, UpdateCollectionInfoMsg};
+use sg721_base::msg::{CollectionInfoResponse, QueryMsg as Sg721QueryMsg, TokenRoyaltyResponse};
 use sg_multi_test::StargazeApp;
 use sg_splits::msg::ExecuteMsg as SplitsExecuteMsg;
 use sg_std::{StargazeMsgWrapper, GENESIS_MINT_START_TIME, NATIVE_DENOM};
 use sg_whitelist::msg::InstantiateMsg as WhitelistInstantiateMsg;
 use sg_whitelist::msg::{
     AddMembersMsg, ConfigResponse as WhitelistConfigResponse, ExecuteMsg as WhitelistExecuteMsg,
-    QueryMsg as WhitelistQueryMsg,
+    QueryMsg as WhitelistQueryMsg, NEVER_EXPIRES,
 };
+use sg_whitelist::ContractError as WhitelistContractError;
+use sha2::{Digest, Sha256};
 
 const CREATION_FEE: u128 = 0;
 const CREATION_FEE_PER_TOKEN: u128 = 10_000;
@@ -43,6 +59,9 @@ pub const AIRDROP_MINT_PRICE: u128 = 0;
 pub const MINT_FEE_BPS: u64 = 1_000; // 10%
 pub const AIRDROP_MINT_FEE_BPS: u64 = 10_000; // 100%
 pub const MAX_PER_ADDRESS_LIMIT: u32 = 50;
+pub const MAX_MINT_PRICE: u128 = 1_000_000_000_000;
+pub const MAX_ROYALTY_BPS: u64 = 1_000; // 10%
+pub const MAX_START_OFFSET_SECS: u64 = 5 * 365 * 24 * 60 * 60; // the mock chain starts years before genesis
 
 const COLLECTION1_URI: &str = "ipfs://collection1";
 const COLLECTION2_URI: &str = "ipfs://collection2";
@@ -57,7 +76,8 @@ pub fn contract_factory() -> Box<dyn Contract<StargazeMsgWrapper>> {
         serial_print_factory::contract::execute,
         serial_print_factory::contract::instantiate,
         serial_print_factory::contract::query,
-    );
+    )
+    .with_sudo(serial_print_factory::contract::sudo);
     Box::new(contract)
 }
 
@@ -76,7 +96,8 @@ pub fn contract_minter() -> Box<dyn Contract<StargazeMsgWrapper>> {
         crate::contract::instantiate,
         crate::contract::query,
     )
-    .with_reply(crate::contract::reply);
+    .with_reply(crate::contract::reply)
+    .with_sudo(crate::contract::sudo);
     Box::new(contract)
 }
 
@@ -107,6 +128,28 @@ pub fn contract_group() -> Box<dyn Contract<StargazeMsgWrapper>> {
     Box::new(contract)
 }
 
+#[cw_serde]
+enum TreasuryExecuteMsg {
+    Deposit { memo: String },
+}
+
+// Treasury stand-in that accepts deposits and echoes them in its attributes
+pub fn contract_treasury() -> Box<dyn Contract<StargazeMsgWrapper>> {
+    let contract = ContractWrapper::new_with_empty(
+        |_: DepsMut, _: Env, info: MessageInfo, msg: TreasuryExecuteMsg| -> StdResult<Response> {
+            let TreasuryExecuteMsg::Deposit { memo } = msg;
+            Ok(Response::new()
+                .add_attribute("memo", memo)
+                .add_attribute("funds", info.funds[0].to_string()))
+        },
+        |_: DepsMut, _: Env, _: MessageInfo, _: Empty| -> StdResult<Response> {
+            Ok(Response::new())
+        },
+        |_: Deps, _: Env, _: Empty| -> StdResult<Binary> { to_binary(&Empty {}) },
+    );
+    Box::new(contract)
+}
+
 fn setup_whitelist_contract(router: &mut StargazeApp, creator: &Addr) -> Addr {
     let whitelist_code_id = router.store_code(contract_whitelist());
 
@@ -142,6 +185,11 @@ pub fn mock_params() -> VendingMinterParams {
             max_per_address_limit: MAX_PER_ADDRESS_LIMIT,
             airdrop_mint_price: coin(AIRDROP_MINT_PRICE, NATIVE_DENOM),
             airdrop_mint_fee_bps: AIRDROP_MINT_FEE_BPS,
+            require_explicit_content: false,
+            max_start_offset_secs: MAX_START_OFFSET_SECS,
+            max_mint_price: coin(MAX_MINT_PRICE, NATIVE_DENOM),
+            max_royalty_bps: MAX_ROYALTY_BPS,
+            max_token_limit: MAX_TOKEN_LIMIT,
         },
     }
 }
@@ -155,6 +203,43 @@ pub fn mock_init_extension(splits_addr: Option<String>) -> VendingMinterInitMsgE
         mint_price: coin(MIN_MINT_PRICE, NATIVE_DENOM),
         per_address_limit: 5,
         whitelist: None,
+        creator_reserve: None,
+        soft_cap: None,
+        phases: None,
+        record_history: None,
+        mint_to_allowlist: None,
+        vesting: None,
+        referral_bps: None,
+        whitelist_total_cap: None,
+        reroll_fee: None,
+        allocation_merkle_root: None,
+        admin: None,
+        global_mint_interval_secs: None,
+        auto_withdraw_threshold: None,
+        allow_contract_recipients: None,
+        public_on_whitelist_sellout: None,
+        reveal_deadline: None,
+        payout_splits: None,
+        freeze_delay_secs: None,
+        token_uri_suffix: None,
+        reset_limit_on_public: None,
+        min_mint_price: None,
+        display_denom: None,
+        exponent: None,
+        block_privileged_referrals: None,
+        early_per_address_limit: None,
+        early_window_secs: None,
+        start_time_update_cooldown_secs: None,
+        presale_deposits: None,
+        record_receipts: None,
+        require_height_gate: None,
+        daily_mint_limit: None,
+        proceeds_target: None,
+        generative: None,
+        reserved_token_ids: None,
+        claim_allocation: None,
+        claim_window_secs: None,
+        alt_mint_prices: None,
     }
 }
 
@@ -200,43 +285,58 @@ fn setup_minter_contract(
     num_tokens: u32,
     splits_addr: Option<String>,
 ) -> (Addr, ConfigResponse) {
-    let minter_code_id = router.store_code(contract_minter());
-    println!("minter_code_id: {}", minter_code_id);
-    let creation_fee = coins(CREATION_FEE_PER_TOKEN * (num_tokens as u128), NATIVE_DENOM);
-
-    let factory_code_id = router.store_code(contract_factory());
-    println!("factory_code_id: {}", factory_code_id);
-
-    let mut params = mock_params();
-    params.code_id = minter_code_id;
-
-    let factory_addr = router
-        .instantiate_contract(
-            factory_code_id,
-            creator.clone(),
-            &serial_print_factory::msg::InstantiateMsg { params },
-            &[],
-            "factory",
-            None,
-        )
-        .unwrap();
-
-    let sg721_code_id = router.store_code(contract_sg721());
-    println!("sg721_code_id: {}", sg721_code_id);
+    setup_minter_contract_with(router, creator, num_tokens, |msg| {
+        msg.init_msg.mint_price = coin(MINT_PRICE, NATIVE_DENOM);
+        msg.init_msg.payment_address = splits_addr;
+    })
+}
 
-    let mut msg = mock_create_minter(splits_addr);
-    msg.init_msg.mint_price = coin(MINT_PRICE, NATIVE_DENOM);
+// Mock create minter msg for a collection of `num_tokens` by `creator`
+fn create_minter_msg(
+    creator: &Addr,
+    sg721_code_id: u64,
+    num_tokens: u32,
+) -> VendingMinterCreateMsg {
+    let mut msg = mock_create_minter(None);
     msg.init_msg.num_tokens = num_tokens;
     msg.collection_params.code_id = sg721_code_id;
     msg.collection_params.info.creator = creator.to_string();
+    msg
+}
 
-    let msg = Sg2ExecuteMsg::CreateMinter(msg);
+// Address of the minter instantiated by a `CreateMinter` response
+fn minter_addr_from(res: &AppResponse) -> Addr {
+    let instantiate = res.events.iter().find(|e| e.ty == "instantiate").unwrap();
+    let addr = instantiate
+        .attributes
+        .iter()
+        .find(|a| a.key == "_contract_addr")
+        .unwrap();
+    Addr::unchecked(&addr.value)
+}
 
-    let res = router.execute_contract(creator.clone(), factory_addr, &msg, &creation_fee);
-    assert!(res.is_ok());
+// Upload contract code and create a minter through the factory,
+// with `configure` applied to the mock create minter msg
+fn setup_minter_contract_with(
+    router: &mut StargazeApp,
+    creator: &Addr,
+    num_tokens: u32,
+    configure: impl FnOnce(&mut VendingMinterCreateMsg),
+) -> (Addr, ConfigResponse) {
+    let (factory_addr, sg721_code_id) = setup_factory_contract(router, creator);
+    let creation_fee = coins(CREATION_FEE_PER_TOKEN * (num_tokens as u128), NATIVE_DENOM);
 
-    // could get the minter address from the response above, but we know its contract1
-    let minter_addr = Addr::unchecked("contract1");
+    let mut msg = create_minter_msg(creator, sg721_code_id, num_tokens);
+    configure(&mut msg);
+    let res = router
+        .execute_contract(
+            creator.clone(),
+            factory_addr,
+            &Sg2ExecuteMsg::CreateMinter(msg),
+            &creation_fee,
+        )
+        .unwrap();
+    let minter_addr = minter_addr_from(&res);
 
     let config: ConfigResponse = router
         .wrap()
@@ -284,11 +384,11 @@ fn setup_minter_contract_with_splits(
 
     let msg = Sg2ExecuteMsg::CreateMinter(msg);
 
-    let res = router.execute_contract(creator.clone(), factory_addr, &msg, &creation_fee);
-    assert!(res.is_ok());
+    let res = router
+        .execute_contract(creator.clone(), factory_addr, &msg, &creation_fee)
+        .unwrap();
 
-    // 1 = group, 2 = splits, 3 = minter, 4 = factory, 5 = sg721
-    let minter_addr = Addr::unchecked("contract3");
+    let minter_addr = minter_addr_from(&res);
 
     let config: ConfigResponse = router
         .wrap()
@@ -498,7 +598,10 @@ fn happy_path() {
     setup_block_time(&mut router, GENESIS_MINT_START_TIME + 1, None);
 
     // Fail with incorrect tokens
-    let mint_msg = ExecuteMsg::Mint {};
+    let mint_msg = ExecuteMsg::Mint {
+        referral: None,
+        recipient: None,
+    };
     let err = router.execute_contract(
         buyer.clone(),
         minter_addr.clone(),
@@ -508,7 +611,10 @@ fn happy_path() {
     assert!(err.is_err());
 
     // Succeeds if funds are sent
-    let mint_msg = ExecuteMsg::Mint {};
+    let mint_msg = ExecuteMsg::Mint {
+        referral: None,
+        recipient: None,
+    };
     let res = router.execute_contract(
         buyer.clone(),
         minter_addr.clone(),
@@ -614,7 +720,10 @@ fn happy_path() {
     assert_eq!(res.owner, buyer.to_string());
 
     // Errors if sold out
-    let mint_msg = ExecuteMsg::Mint {};
+    let mint_msg = ExecuteMsg::Mint {
+        referral: None,
+        recipient: None,
+    };
     let res = router.execute_contract(
         buyer.clone(),
         minter_addr.clone(),
@@ -683,12 +792,9 @@ fn invalid_whitelist_instantiate() {
 
     let sg721_code_id = router.store_code(contract_sg721());
 
-    let mut msg = mock_create_minter(None);
+    let mut msg = create_minter_msg(&creator, sg721_code_id, num_tokens);
     msg.init_msg.whitelist = Some("invalid address".to_string());
     msg.init_msg.mint_price = coin(MINT_PRICE, NATIVE_DENOM);
-    msg.init_msg.num_tokens = num_tokens;
-    msg.collection_params.code_id = sg721_code_id;
-    msg.collection_params.info.creator = creator.to_string();
 
     let msg = Sg2ExecuteMsg::CreateMinter(msg);
 
@@ -823,7 +929,10 @@ fn mint_count_query() {
     setup_block_time(&mut router, GENESIS_MINT_START_TIME, Some(10));
 
     // Mint succeeds
-    let mint_msg = ExecuteMsg::Mint {};
+    let mint_msg = ExecuteMsg::Mint {
+        referral: None,
+        recipient: None,
+    };
     let res = router.execute_contract(
         buyer.clone(),
         minter_addr.clone(),
@@ -846,7 +955,10 @@ fn mint_count_query() {
     assert_eq!(res.address, buyer.to_string());
 
     // Mint fails, over whitelist per address limit
-    let mint_msg = ExecuteMsg::Mint {};
+    let mint_msg = ExecuteMsg::Mint {
+        referral: None,
+        recipient: None,
+    };
     let err = router
         .execute_contract(
             buyer.clone(),
@@ -864,7 +976,10 @@ fn mint_count_query() {
     setup_block_time(&mut router, GENESIS_MINT_START_TIME + 20_000, Some(11));
 
     // Public mint succeeds
-    let mint_msg = ExecuteMsg::Mint {};
+    let mint_msg = ExecuteMsg::Mint {
+        referral: None,
+        recipient: None,
+    };
     let res = router.execute_contract(
         buyer.clone(),
         minter_addr.clone(),
@@ -913,7 +1028,10 @@ fn mint_count_query() {
     assert!(res.is_ok());
 
     // Mint succeeds
-    let mint_msg = ExecuteMsg::Mint {};
+    let mint_msg = ExecuteMsg::Mint {
+        referral: None,
+        recipient: None,
+    };
     let res = router.execute_contract(
         buyer.clone(),
         minter_addr.clone(),
@@ -936,7 +1054,10 @@ fn mint_count_query() {
     assert_eq!(res.address, buyer.to_string());
 
     // Mint fails
-    let mint_msg = ExecuteMsg::Mint {};
+    let mint_msg = ExecuteMsg::Mint {
+        referral: None,
+        recipient: None,
+    };
     let err = router
         .execute_contract(
             buyer.clone(),
@@ -1058,7 +1179,10 @@ fn whitelist_access_len_add_remove_expiration() {
     assert!(res.is_ok());
 
     // Mint fails, buyer is not on whitelist
-    let mint_msg = ExecuteMsg::Mint {};
+    let mint_msg = ExecuteMsg::Mint {
+        referral: None,
+        recipient: None,
+    };
     let res = router.execute_contract(
         buyer.clone(),
         minter_addr.clone(),
@@ -1076,7 +1200,10 @@ fn whitelist_access_len_add_remove_expiration() {
     assert!(res.is_ok());
 
     // Mint fails, not whitelist price
-    let mint_msg = ExecuteMsg::Mint {};
+    let mint_msg = ExecuteMsg::Mint {
+        referral: None,
+        recipient: None,
+    };
     router
         .execute_contract(
             buyer.clone(),
@@ -1108,7 +1235,10 @@ fn whitelist_access_len_add_remove_expiration() {
     );
 
     // Mint succeeds with whitelist price
-    let mint_msg = ExecuteMsg::Mint {};
+    let mint_msg = ExecuteMsg::Mint {
+        referral: None,
+        recipient: None,
+    };
     let res = router.execute_contract(
         buyer.clone(),
         minter_addr.clone(),
@@ -1118,7 +1248,10 @@ fn whitelist_access_len_add_remove_expiration() {
     assert!(res.is_ok());
 
     // Mint fails, over whitelist per address limit
-    let mint_msg = ExecuteMsg::Mint {};
+    let mint_msg = ExecuteMsg::Mint {
+        referral: None,
+        recipient: None,
+    };
     let err = router
         .execute_contract(
             buyer.clone(),
@@ -1146,7 +1279,10 @@ fn whitelist_access_len_add_remove_expiration() {
     assert!(res.is_ok());
 
     // Mint fails, buyer exceeded per address limit
-    let mint_msg = ExecuteMsg::Mint {};
+    let mint_msg = ExecuteMsg::Mint {
+        referral: None,
+        recipient: None,
+    };
     let err = router
         .execute_contract(
             buyer.clone(),
@@ -1167,7 +1303,10 @@ fn whitelist_access_len_add_remove_expiration() {
     assert!(res.is_ok());
 
     // Mint fails
-    let mint_msg = ExecuteMsg::Mint {};
+    let mint_msg = ExecuteMsg::Mint {
+        referral: None,
+        recipient: None,
+    };
     let res = router.execute_contract(
         buyer,
         minter_addr,
@@ -1198,7 +1337,10 @@ fn before_start_time() {
     assert!(res.is_err());
 
     // Buyer can't mint before start_time
-    let mint_msg = ExecuteMsg::Mint {};
+    let mint_msg = ExecuteMsg::Mint {
+        referral: None,
+        recipient: None,
+    };
     let res = router.execute_contract(
         buyer.clone(),
         minter_addr.clone(),
@@ -1221,7 +1363,10 @@ fn before_start_time() {
     setup_block_time(&mut router, GENESIS_MINT_START_TIME + 10_000_000, None);
 
     // Mint succeeds
-    let mint_msg = ExecuteMsg::Mint {};
+    let mint_msg = ExecuteMsg::Mint {
+        referral: None,
+        recipient: None,
+    };
     let res = router.execute_contract(
         buyer,
         minter_addr,
@@ -1290,7 +1435,10 @@ fn check_per_address_limit() {
     assert!(res.is_ok());
 
     // First mint succeeds
-    let mint_msg = ExecuteMsg::Mint {};
+    let mint_msg = ExecuteMsg::Mint {
+        referral: None,
+        recipient: None,
+    };
     let res = router.execute_contract(
         buyer.clone(),
         minter_addr.clone(),
@@ -1301,7 +1449,10 @@ fn check_per_address_limit() {
     assert!(res.is_ok());
 
     // Second mint fails from exceeding per address limit
-    let mint_msg = ExecuteMsg::Mint {};
+    let mint_msg = ExecuteMsg::Mint {
+        referral: None,
+        recipient: None,
+    };
     let res = router.execute_contract(
         buyer,
         minter_addr,
@@ -1341,11 +1492,8 @@ fn check_dynamic_per_address_limit() {
 
     let sg721_code_id = router.store_code(contract_sg721());
 
-    let mut msg = mock_create_minter(None);
+    let mut msg = create_minter_msg(&creator, sg721_code_id, num_tokens);
     msg.init_msg.mint_price = coin(MINT_PRICE, NATIVE_DENOM);
-    msg.init_msg.num_tokens = num_tokens;
-    msg.collection_params.code_id = sg721_code_id;
-    msg.collection_params.info.creator = creator.to_string();
 
     let msg = Sg2ExecuteMsg::CreateMinter(msg);
 
@@ -1366,16 +1514,14 @@ fn check_dynamic_per_address_limit() {
     // should succeed with 1000 tokens and 5 per_address_limit
     let num_tokens = 1000;
     let creation_fee = coins(CREATION_FEE_PER_TOKEN * num_tokens as u128, NATIVE_DENOM);
-    let mut msg = mock_create_minter(None);
+    let mut msg = create_minter_msg(&creator, sg721_code_id, num_tokens);
     msg.init_msg.mint_price = coin(MINT_PRICE, NATIVE_DENOM);
-    msg.init_msg.num_tokens = num_tokens;
-    msg.collection_params.code_id = sg721_code_id;
-    msg.collection_params.info.creator = creator.to_string();
     let msg = Sg2ExecuteMsg::CreateMinter(msg);
-    let res = router.execute_contract(creator.clone(), factory_addr, &msg, &creation_fee);
-    assert!(res.is_ok());
+    let res = router
+        .execute_contract(creator.clone(), factory_addr, &msg, &creation_fee)
+        .unwrap();
 
-    let minter_addr = Addr::unchecked("contract1");
+    let minter_addr = minter_addr_from(&res);
 
     // if per address limit > 1%, throw error when updating per_address_limit
     let update_msg = ExecuteMsg::UpdatePerAddressLimit {
@@ -1429,7 +1575,10 @@ fn mint_for_token_id_addr() {
     // Test token id already sold
     // 1. random mint token_id
     // 2. mint_for same token_id
-    let mint_msg = ExecuteMsg::Mint {};
+    let mint_msg = ExecuteMsg::Mint {
+        referral: None,
+        recipient: None,
+    };
     let res = router.execute_contract(
         buyer.clone(),
         minter_addr.clone(),
@@ -1630,9 +1779,13 @@ fn test_invalid_start_time() {
         NATIVE_DENOM,
     );
 
-    router
+    let err = router
         .execute_contract(creator.clone(), factory_addr.clone(), &msg, &creation_fee)
         .unwrap_err();
+    assert_eq!(
+        ContractError::BeforeGenesisTime {}.to_string(),
+        err.source().unwrap().source().unwrap().to_string()
+    );
 
     // move date after genesis mint
     setup_block_time(&mut router, GENESIS_MINT_START_TIME + 1000, None);
@@ -1648,11 +1801,11 @@ fn test_invalid_start_time() {
     setup_block_time(&mut router, GENESIS_MINT_START_TIME + 400, None);
     minter_msg.init_msg.start_time = Timestamp::from_nanos(GENESIS_MINT_START_TIME + 500);
     let msg = Sg2ExecuteMsg::CreateMinter(minter_msg);
-    router
+    let res = router
         .execute_contract(creator.clone(), factory_addr, &msg, &creation_fee)
         .unwrap();
 
-    let minter_addr = Addr::unchecked("contract1");
+    let minter_addr = minter_addr_from(&res);
 
     // Update to a start time in the past
     let msg = ExecuteMsg::UpdateStartTime(Timestamp::from_nanos(GENESIS_MINT_START_TIME - 100));
@@ -1712,11 +1865,8 @@ fn invalid_trading_time_during_init() {
     let sg721_code_id = router.store_code(contract_sg721());
     println!("sg721_code_id: {}", sg721_code_id);
 
-    let mut msg = mock_create_minter(None);
+    let mut msg = create_minter_msg(&creator, sg721_code_id, num_tokens);
     msg.init_msg.mint_price = coin(MINT_PRICE, NATIVE_DENOM);
-    msg.init_msg.num_tokens = num_tokens;
-    msg.collection_params.code_id = sg721_code_id;
-    msg.collection_params.info.creator = creator.to_string();
     // make trading time beyond factory max trading start time offset
     msg.collection_params.info.start_trading_time = Some(
         msg.init_msg
@@ -1811,7 +1961,10 @@ fn unhappy_path() {
     let (minter_addr, _config) = setup_minter_contract(&mut router, &creator, num_tokens, None);
 
     // Fails if too little funds are sent
-    let mint_msg = ExecuteMsg::Mint {};
+    let mint_msg = ExecuteMsg::Mint {
+        referral: None,
+        recipient: None,
+    };
     let res = router.execute_contract(
         buyer.clone(),
         minter_addr.clone(),
@@ -1821,7 +1974,10 @@ fn unhappy_path() {
     assert!(res.is_err());
 
     // Fails if too many funds are sent
-    let mint_msg = ExecuteMsg::Mint {};
+    let mint_msg = ExecuteMsg::Mint {
+        referral: None,
+        recipient: None,
+    };
     let res = router.execute_contract(
         buyer.clone(),
         minter_addr.clone(),
@@ -1831,7 +1987,10 @@ fn unhappy_path() {
     assert!(res.is_err());
 
     // Fails wrong denom is sent
-    let mint_msg = ExecuteMsg::Mint {};
+    let mint_msg = ExecuteMsg::Mint {
+        referral: None,
+        recipient: None,
+    };
     let res = router.execute_contract(buyer, minter_addr, &mint_msg, &coins(MINT_PRICE, "uatom"));
     assert!(res.is_err());
 }
@@ -1865,6 +2024,22 @@ fn update_mint_price() {
     let res = router.execute_contract(creator.clone(), minter_addr.clone(), &update_msg, &[]);
     assert!(res.is_err());
 
+    // Update mint price above the factory max
+    let update_msg = ExecuteMsg::UpdateMintPrice {
+        price: MAX_MINT_PRICE + 1,
+    };
+    let err = router
+        .execute_contract(creator.clone(), minter_addr.clone(), &update_msg, &[])
+        .unwrap_err();
+    assert_eq!(
+        ContractError::MintPriceTooHigh {
+            max: MAX_MINT_PRICE,
+            got: MAX_MINT_PRICE + 1,
+        }
+        .to_string(),
+        err.source().unwrap().to_string()
+    );
+
     // Update mint price higher
     let update_msg = ExecuteMsg::UpdateMintPrice {
         price: MINT_PRICE - 1,
@@ -1897,7 +2072,10 @@ fn update_mint_price() {
     assert!(res.is_ok());
 
     // Mint succeeds
-    let mint_msg = ExecuteMsg::Mint {};
+    let mint_msg = ExecuteMsg::Mint {
+        referral: None,
+        recipient: None,
+    };
     let res = router.execute_contract(
         buyer,
         minter_addr,
@@ -1923,7 +2101,10 @@ fn mint_and_split() {
     );
     setup_block_time(&mut app, GENESIS_MINT_START_TIME + 1, None);
 
-    let mint_msg = ExecuteMsg::Mint {};
+    let mint_msg = ExecuteMsg::Mint {
+        referral: None,
+        recipient: None,
+    };
     let res = app.execute_contract(
         buyer,
         minter_addr,
@@ -1967,7 +2148,10 @@ fn burn_remaining() {
     setup_block_time(&mut router, GENESIS_MINT_START_TIME + 1, None);
 
     // Succeeds if funds are sent
-    let mint_msg = ExecuteMsg::Mint {};
+    let mint_msg = ExecuteMsg::Mint {
+        referral: None,
+        recipient: None,
+    };
     let res = router.execute_contract(
         buyer.clone(),
         minter_addr.clone(),
@@ -2053,7 +2237,10 @@ fn burn_remaining() {
     );
 
     // Errors if sold out
-    let mint_msg = ExecuteMsg::Mint {};
+    let mint_msg = ExecuteMsg::Mint {
+        referral: None,
+        recipient: None,
+    };
     let res = router.execute_contract(
         buyer,
         minter_addr.clone(),
@@ -2175,7 +2362,7 @@ fn set_token_uri() {
     // Set new token uri with uri, num_tokens: 10
     let set_token_uri_msg_2 = ExecuteMsg::SetTokenUri {
         uri: COLLECTION3_URI.to_string(),
-        num_tokens: num_tokens,
+        num_tokens,
     };
     let res = router.execute_contract(
         creator.clone(),
@@ -2295,7 +2482,10 @@ fn set_pause() {
     setup_block_time(&mut router, GENESIS_MINT_START_TIME + 400, None);
 
     // Mintable Ok at init
-    let mint_msg = ExecuteMsg::Mint {};
+    let mint_msg = ExecuteMsg::Mint {
+        referral: None,
+        recipient: None,
+    };
     let res = router.execute_contract(
         buyer.clone(),
         minter_addr.clone(),
@@ -2445,3 +2635,6790 @@ fn dynamic_fee_for_set_new_uri() {
     );
     assert!(res.is_ok());
 }
+
+#[test]
+fn creator_reserve() {
+    let mut router = custom_mock_app();
+    let num_tokens = 10;
+    let (creator, buyer) = setup_accounts(&mut router, num_tokens);
+
+    let (factory_addr, sg721_code_id) = setup_factory_contract(&mut router, &creator);
+    let creation_fee = coins(CREATION_FEE_PER_TOKEN * num_tokens as u128, NATIVE_DENOM);
+
+    let mut msg = create_minter_msg(&creator, sg721_code_id, num_tokens);
+    msg.init_msg.mint_price = coin(MINT_PRICE, NATIVE_DENOM);
+
+    // Fails when reserving more than the collection size
+    msg.init_msg.creator_reserve = Some(num_tokens + 1);
+    let err = router
+        .execute_contract(
+            creator.clone(),
+            factory_addr.clone(),
+            &Sg2ExecuteMsg::CreateMinter(msg.clone()),
+            &creation_fee,
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::InvalidCreatorReserve {
+            max: num_tokens,
+            got: num_tokens + 1
+        }
+        .to_string(),
+        err.source().unwrap().source().unwrap().to_string()
+    );
+
+    msg.init_msg.creator_reserve = Some(3);
+    let res = router
+        .execute_contract(
+            creator.clone(),
+            factory_addr,
+            &Sg2ExecuteMsg::CreateMinter(msg),
+            &creation_fee,
+        )
+        .unwrap();
+
+    let minter_addr = minter_addr_from(&res);
+    let config: ConfigResponse = router
+        .wrap()
+        .query_wasm_smart(minter_addr.clone(), &QueryMsg::Config {})
+        .unwrap();
+
+    // Public mintable count excludes the reserve
+    let res: MintableNumTokensResponse = router
+        .wrap()
+        .query_wasm_smart(minter_addr.clone(), &QueryMsg::MintableNumTokens {})
+        .unwrap();
+    assert_eq!(res.count, num_tokens - 3);
+
+    // Creator owns the reserved tokens
+    let res: TokensResponse = router
+        .wrap()
+        .query_wasm_smart(
+            config.sg721_address.clone(),
+            &Cw721QueryMsg::Tokens {
+                owner: creator.to_string(),
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(res.tokens, vec!["1", "2", "3"]);
+
+    // Next public mint starts after the reserve
+    setup_block_time(&mut router, GENESIS_MINT_START_TIME + 1, None);
+    let res = router
+        .execute_contract(
+            buyer,
+            minter_addr,
+            &ExecuteMsg::Mint {
+                referral: None,
+                recipient: None,
+            },
+            &coins(MINT_PRICE, NATIVE_DENOM),
+        )
+        .unwrap();
+    assert_eq!(4, get_token_id(&res));
+}
+
+#[test]
+fn mint_for_token_id_out_of_range() {
+    let mut router = custom_mock_app();
+    let num_tokens = 100;
+    let (creator, buyer) = setup_accounts(&mut router, num_tokens);
+    let (minter_addr, _) = setup_minter_contract(&mut router, &creator, num_tokens, None);
+
+    setup_block_time(&mut router, GENESIS_MINT_START_TIME, None);
+
+    let mint_for_msg = ExecuteMsg::MintFor {
+        token_id: 99999,
+        recipient: buyer.to_string(),
+    };
+    let err = router
+        .execute_contract(creator.clone(), minter_addr.clone(), &mint_for_msg, &[])
+        .unwrap_err();
+    assert_eq!(
+        ContractError::InvalidTokenId {}.to_string(),
+        err.source().unwrap().to_string()
+    );
+
+    // Last id in range is still mintable
+    let mint_for_msg = ExecuteMsg::MintFor {
+        token_id: num_tokens,
+        recipient: buyer.to_string(),
+    };
+    let res = router
+        .execute_contract(creator, minter_addr.clone(), &mint_for_msg, &[])
+        .unwrap();
+    assert_eq!(num_tokens, get_token_id(&res));
+
+    let res: MintableNumTokensResponse = router
+        .wrap()
+        .query_wasm_smart(minter_addr, &QueryMsg::MintableNumTokens {})
+        .unwrap();
+    assert_eq!(res.count, num_tokens - 1);
+}
+
+#[test]
+fn public_mint_at_whitelist_end_time() {
+    let mut router = custom_mock_app();
+    let num_tokens = 10;
+    let (creator, buyer) = setup_accounts(&mut router, num_tokens);
+    let (minter_addr, _) = setup_minter_contract(&mut router, &creator, num_tokens, None);
+    let whitelist_addr = setup_whitelist_contract(&mut router, &creator);
+    let end_time = Timestamp::from_nanos(GENESIS_MINT_START_TIME + 10000000);
+
+    setup_block_time(&mut router, GENESIS_MINT_START_TIME - 10, None);
+    let set_whitelist_msg = ExecuteMsg::SetWhitelist {
+        whitelist: whitelist_addr.to_string(),
+    };
+    router
+        .execute_contract(creator, minter_addr.clone(), &set_whitelist_msg, &[])
+        .unwrap();
+
+    // One block before the end time the whitelist still gates the mint
+    setup_block_time(&mut router, end_time.nanos() - 1, None);
+    let err = router
+        .execute_contract(
+            buyer.clone(),
+            minter_addr.clone(),
+            &ExecuteMsg::Mint {
+                referral: None,
+                recipient: None,
+            },
+            &coins(MINT_PRICE, NATIVE_DENOM),
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::NotWhitelisted {
+            addr: buyer.to_string()
+        }
+        .to_string(),
+        err.source().unwrap().to_string()
+    );
+
+    // At exactly the end time the whitelist has ended
+    setup_block_time(&mut router, end_time.nanos(), None);
+    let wl_config: WhitelistConfigResponse = router
+        .wrap()
+        .query_wasm_smart(whitelist_addr, &WhitelistQueryMsg::Config {})
+        .unwrap();
+    assert!(!wl_config.is_active);
+
+    // and the public can mint at the public price
+    let res: MintPriceResponse = router
+        .wrap()
+        .query_wasm_smart(minter_addr.clone(), &QueryMsg::MintPrice {})
+        .unwrap();
+    assert_eq!(res.current_price, coin(MINT_PRICE, NATIVE_DENOM));
+    let res = router.execute_contract(
+        buyer,
+        minter_addr,
+        &ExecuteMsg::Mint {
+            referral: None,
+            recipient: None,
+        },
+        &coins(MINT_PRICE, NATIVE_DENOM),
+    );
+    assert!(res.is_ok());
+}
+
+#[test]
+fn soft_cap_withdraw() {
+    let mut router = custom_mock_app();
+    let num_tokens = 10;
+    let (creator, buyer) = setup_accounts(&mut router, num_tokens);
+
+    let (minter_addr, _) = setup_minter_contract_with(&mut router, &creator, num_tokens, |msg| {
+        msg.init_msg.mint_price = coin(MINT_PRICE, NATIVE_DENOM);
+        msg.init_msg.soft_cap = Some(2);
+    });
+
+    setup_block_time(&mut router, GENESIS_MINT_START_TIME + 1, None);
+    let creator_balance = router.wrap().query_all_balances(creator.clone()).unwrap()[0]
+        .amount
+        .u128();
+
+    // Free admin mints don't count toward the soft cap
+    for _ in 0..2 {
+        router
+            .execute_contract(
+                creator.clone(),
+                minter_addr.clone(),
+                &ExecuteMsg::MintTo {
+                    recipient: buyer.to_string(),
+                },
+                &[],
+            )
+            .unwrap();
+    }
+    let err = router
+        .execute_contract(
+            creator.clone(),
+            minter_addr.clone(),
+            &ExecuteMsg::Withdraw {},
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::SoftCapNotReached {
+            minted: 0,
+            soft_cap: 2
+        }
+        .to_string(),
+        err.source().unwrap().to_string()
+    );
+
+    // Under the soft cap proceeds are held and withdraw is blocked
+    let res = router
+        .execute_contract(
+            buyer.clone(),
+            minter_addr.clone(),
+            &ExecuteMsg::Mint {
+                referral: None,
+                recipient: None,
+            },
+            &coins(MINT_PRICE, NATIVE_DENOM),
+        )
+        .unwrap();
+    assert!(!res.events.iter().any(|e| e.ty == "wasm-soft_cap_reached"));
+    let err = router
+        .execute_contract(
+            creator.clone(),
+            minter_addr.clone(),
+            &ExecuteMsg::Withdraw {},
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::SoftCapNotReached {
+            minted: 1,
+            soft_cap: 2
+        }
+        .to_string(),
+        err.source().unwrap().to_string()
+    );
+
+    // Crossing the soft cap emits the event
+    let res = router
+        .execute_contract(
+            buyer,
+            minter_addr.clone(),
+            &ExecuteMsg::Mint {
+                referral: None,
+                recipient: None,
+            },
+            &coins(MINT_PRICE, NATIVE_DENOM),
+        )
+        .unwrap();
+    assert!(res.events.iter().any(|e| e.ty == "wasm-soft_cap_reached"));
+
+    // Only the admin can withdraw
+    let err = router
+        .execute_contract(
+            Addr::unchecked("not-admin"),
+            minter_addr.clone(),
+            &ExecuteMsg::Withdraw {},
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::Unauthorized("Sender is not an admin".to_owned()).to_string(),
+        err.source().unwrap().to_string()
+    );
+
+    router
+        .execute_contract(
+            creator.clone(),
+            minter_addr.clone(),
+            &ExecuteMsg::Withdraw {},
+            &[],
+        )
+        .unwrap();
+    let balance = router.wrap().query_all_balances(creator).unwrap()[0]
+        .amount
+        .u128();
+    assert_eq!(balance, creator_balance + 2 * (MINT_PRICE - MINT_FEE));
+    let minter_balance = router.wrap().query_all_balances(minter_addr).unwrap();
+    assert!(minter_balance.is_empty());
+}
+
+#[test]
+fn mintable_num_tokens_stays_in_sync() {
+    let mut router = custom_mock_app();
+    let num_tokens = 5;
+    let (creator, buyer) = setup_accounts(&mut router, num_tokens);
+    let (minter_addr, _) = setup_minter_contract(&mut router, &creator, num_tokens, None);
+
+    setup_block_time(&mut router, GENESIS_MINT_START_TIME + 1, None);
+
+    // Public mint takes the lowest id
+    let res = router
+        .execute_contract(
+            buyer.clone(),
+            minter_addr.clone(),
+            &ExecuteMsg::Mint {
+                referral: None,
+                recipient: None,
+            },
+            &coins(MINT_PRICE, NATIVE_DENOM),
+        )
+        .unwrap();
+    assert_eq!(1, get_token_id(&res));
+
+    // Admin mints a specific id and then the next available one
+    let mint_for_msg = ExecuteMsg::MintFor {
+        token_id: 3,
+        recipient: buyer.to_string(),
+    };
+    router
+        .execute_contract(creator.clone(), minter_addr.clone(), &mint_for_msg, &[])
+        .unwrap();
+    let mint_to_msg = ExecuteMsg::MintTo {
+        recipient: buyer.to_string(),
+    };
+    let res = router
+        .execute_contract(creator, minter_addr.clone(), &mint_to_msg, &[])
+        .unwrap();
+    assert_eq!(2, get_token_id(&res));
+
+    let res: MintableNumTokensResponse = router
+        .wrap()
+        .query_wasm_smart(minter_addr.clone(), &QueryMsg::MintableNumTokens {})
+        .unwrap();
+    assert_eq!(res.count, 2);
+
+    // The counter matches the remaining ids: exactly two more mints succeed
+    for token_id in [4, 5] {
+        let res = router
+            .execute_contract(
+                buyer.clone(),
+                minter_addr.clone(),
+                &ExecuteMsg::Mint {
+                    referral: None,
+                    recipient: None,
+                },
+                &coins(MINT_PRICE, NATIVE_DENOM),
+            )
+            .unwrap();
+        assert_eq!(token_id, get_token_id(&res));
+    }
+    let res: MintableNumTokensResponse = router
+        .wrap()
+        .query_wasm_smart(minter_addr.clone(), &QueryMsg::MintableNumTokens {})
+        .unwrap();
+    assert_eq!(res.count, 0);
+
+    let err = router
+        .execute_contract(
+            buyer,
+            minter_addr,
+            &ExecuteMsg::Mint {
+                referral: None,
+                recipient: None,
+            },
+            &coins(MINT_PRICE, NATIVE_DENOM),
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::SoldOut {}.to_string(),
+        err.source().unwrap().to_string()
+    );
+}
+
+#[test]
+fn mint_phases() {
+    let mut router = custom_mock_app();
+    let num_tokens = 10;
+    let (creator, buyer) = setup_accounts(&mut router, num_tokens);
+
+    let (factory_addr, sg721_code_id) = setup_factory_contract(&mut router, &creator);
+    let whitelist_addr = setup_whitelist_contract(&mut router, &creator);
+    router
+        .execute_contract(
+            creator.clone(),
+            whitelist_addr.clone(),
+            &WhitelistExecuteMsg::AddMembers(AddMembersMsg {
+                to_add: vec![buyer.to_string()],
+            }),
+            &[],
+        )
+        .unwrap();
+    let creation_fee = coins(CREATION_FEE_PER_TOKEN * num_tokens as u128, NATIVE_DENOM);
+
+    let start_time = Timestamp::from_nanos(GENESIS_MINT_START_TIME);
+    let phase =
+        |secs: u64, price: u128, per_address_limit: u32, allowlist: Option<String>| PhaseMsg {
+            start_time: start_time.plus_seconds(secs),
+            price: coin(price, NATIVE_DENOM),
+            per_address_limit,
+            allowlist,
+        };
+
+    let mut msg = create_minter_msg(&creator, sg721_code_id, num_tokens);
+    msg.init_msg.mint_price = coin(MINT_PRICE, NATIVE_DENOM);
+
+    // At most 10 phases
+    msg.init_msg.phases = Some(
+        (1..=11)
+            .map(|i| phase(i * 100, 60_000_000, 1, None))
+            .collect(),
+    );
+    let err = router
+        .execute_contract(
+            creator.clone(),
+            factory_addr.clone(),
+            &Sg2ExecuteMsg::CreateMinter(msg.clone()),
+            &creation_fee,
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::TooManyPhases { max: 10, got: 11 }.to_string(),
+        err.source().unwrap().source().unwrap().to_string()
+    );
+
+    // Phases out of order fail
+    msg.init_msg.phases = Some(vec![
+        phase(200, 70_000_000, 2, None),
+        phase(100, 60_000_000, 1, None),
+    ]);
+    let err = router
+        .execute_contract(
+            creator.clone(),
+            factory_addr.clone(),
+            &Sg2ExecuteMsg::CreateMinter(msg.clone()),
+            &creation_fee,
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::InvalidPhaseOrder {}.to_string(),
+        err.source().unwrap().source().unwrap().to_string()
+    );
+
+    msg.init_msg.phases = Some(vec![
+        phase(100, 60_000_000, 1, Some(whitelist_addr.to_string())),
+        phase(200, 70_000_000, 2, None),
+        phase(300, 80_000_000, 3, None),
+    ]);
+    let res = router
+        .execute_contract(
+            creator,
+            factory_addr,
+            &Sg2ExecuteMsg::CreateMinter(msg),
+            &creation_fee,
+        )
+        .unwrap();
+    let minter_addr = minter_addr_from(&res);
+
+    let res: PhasesResponse = router
+        .wrap()
+        .query_wasm_smart(minter_addr.clone(), &QueryMsg::Phases {})
+        .unwrap();
+    assert_eq!(res.phases.len(), 3);
+    assert_eq!(res.phases[0].allowlist, Some(whitelist_addr.to_string()));
+
+    let mint = |router: &mut StargazeApp, sender: &Addr, price: u128| {
+        router.execute_contract(
+            sender.clone(),
+            minter_addr.clone(),
+            &ExecuteMsg::Mint {
+                referral: None,
+                recipient: None,
+            },
+            &coins(price, NATIVE_DENOM),
+        )
+    };
+
+    // Before the first phase nothing can be minted
+    setup_block_time(&mut router, start_time.plus_seconds(99).nanos(), None);
+    let err = mint(&mut router, &buyer, 60_000_000).unwrap_err();
+    assert_eq!(
+        ContractError::BeforeMintStartTime {}.to_string(),
+        err.source().unwrap().to_string()
+    );
+
+    // Phase 1 is gated by the allowlist
+    setup_block_time(&mut router, start_time.plus_seconds(100).nanos(), None);
+    let outsider = Addr::unchecked("outsider");
+    router
+        .sudo(SudoMsg::Bank(BankSudo::Mint {
+            to_address: outsider.to_string(),
+            amount: coins(INITIAL_BALANCE, NATIVE_DENOM),
+        }))
+        .unwrap();
+    let err = mint(&mut router, &outsider, 60_000_000).unwrap_err();
+    assert_eq!(
+        ContractError::NotWhitelisted {
+            addr: outsider.to_string()
+        }
+        .to_string(),
+        err.source().unwrap().to_string()
+    );
+    let res: MintPriceResponse = router
+        .wrap()
+        .query_wasm_smart(minter_addr.clone(), &QueryMsg::MintPrice {})
+        .unwrap();
+    assert_eq!(res.current_price, coin(60_000_000, NATIVE_DENOM));
+    mint(&mut router, &buyer, 60_000_000).unwrap();
+    let err = mint(&mut router, &buyer, 60_000_000).unwrap_err();
+    assert_eq!(
+        ContractError::MaxPerAddressLimitExceeded {}.to_string(),
+        err.source().unwrap().to_string()
+    );
+
+    // Phase 2 is public at its own price
+    setup_block_time(&mut router, start_time.plus_seconds(200).nanos(), None);
+    assert!(mint(&mut router, &buyer, 60_000_000).is_err());
+    mint(&mut router, &buyer, 70_000_000).unwrap();
+    mint(&mut router, &outsider, 70_000_000).unwrap();
+
+    // Phase 3 raises the price and the limit
+    setup_block_time(&mut router, start_time.plus_seconds(300).nanos(), None);
+    let err = mint(&mut router, &buyer, 70_000_000).unwrap_err();
+    assert_eq!(
+        ContractError::IncorrectPaymentAmount(
+            coin(70_000_000, NATIVE_DENOM),
+            coin(80_000_000, NATIVE_DENOM)
+        )
+        .to_string(),
+        err.source().unwrap().to_string()
+    );
+    mint(&mut router, &buyer, 80_000_000).unwrap();
+    let err = mint(&mut router, &buyer, 80_000_000).unwrap_err();
+    assert_eq!(
+        ContractError::MaxPerAddressLimitExceeded {}.to_string(),
+        err.source().unwrap().to_string()
+    );
+}
+
+#[test]
+fn unique_minters() {
+    let mut router = custom_mock_app();
+    let num_tokens = 10;
+    let (creator, buyer) = setup_accounts(&mut router, num_tokens);
+    let (minter_addr, _) = setup_minter_contract(&mut router, &creator, num_tokens, None);
+
+    setup_block_time(&mut router, GENESIS_MINT_START_TIME + 1, None);
+    let buyer2 = Addr::unchecked("buyer2");
+    router
+        .sudo(SudoMsg::Bank(BankSudo::Mint {
+            to_address: buyer2.to_string(),
+            amount: coins(INITIAL_BALANCE, NATIVE_DENOM),
+        }))
+        .unwrap();
+
+    for sender in [&buyer, &buyer, &buyer2] {
+        router
+            .execute_contract(
+                sender.clone(),
+                minter_addr.clone(),
+                &ExecuteMsg::Mint {
+                    referral: None,
+                    recipient: None,
+                },
+                &coins(MINT_PRICE, NATIVE_DENOM),
+            )
+            .unwrap();
+    }
+
+    let res: UniqueMintersResponse = router
+        .wrap()
+        .query_wasm_smart(minter_addr, &QueryMsg::UniqueMinters {})
+        .unwrap();
+    assert_eq!(res.count, 2);
+}
+
+#[test]
+fn batch_mint_enabled_switch() {
+    let mut router = custom_mock_app();
+    let num_tokens = 10;
+    let (creator, buyer) = setup_accounts(&mut router, num_tokens);
+    let (minter_addr, config) = setup_minter_contract(&mut router, &creator, num_tokens, None);
+    assert!(config.batch_mint_enabled);
+
+    setup_block_time(&mut router, GENESIS_MINT_START_TIME + 1, None);
+
+    let res = router
+        .execute_contract(
+            buyer.clone(),
+            minter_addr.clone(),
+            &ExecuteMsg::BatchMint { num_mints: 2 },
+            &coins(MINT_PRICE * 2, NATIVE_DENOM),
+        )
+        .unwrap();
+    let token_ids: Vec<_> = res.events[1]
+        .attributes
+        .iter()
+        .filter(|attr| attr.key == "token_id")
+        .map(|attr| attr.value.clone())
+        .collect();
+    assert_eq!(token_ids, vec!["1", "2"]);
+
+    // Only the admin can toggle batch minting
+    let disable_msg = ExecuteMsg::SetBatchMintEnabled { enabled: false };
+    let err = router
+        .execute_contract(buyer.clone(), minter_addr.clone(), &disable_msg, &[])
+        .unwrap_err();
+    assert_eq!(
+        ContractError::Unauthorized("Sender is not an admin".to_owned()).to_string(),
+        err.source().unwrap().to_string()
+    );
+    router
+        .execute_contract(creator, minter_addr.clone(), &disable_msg, &[])
+        .unwrap();
+
+    let err = router
+        .execute_contract(
+            buyer.clone(),
+            minter_addr.clone(),
+            &ExecuteMsg::BatchMint { num_mints: 2 },
+            &coins(MINT_PRICE * 2, NATIVE_DENOM),
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::BatchMintDisabled {}.to_string(),
+        err.source().unwrap().to_string()
+    );
+
+    // Single mints are unaffected
+    let res = router
+        .execute_contract(
+            buyer,
+            minter_addr.clone(),
+            &ExecuteMsg::Mint {
+                referral: None,
+                recipient: None,
+            },
+            &coins(MINT_PRICE, NATIVE_DENOM),
+        )
+        .unwrap();
+    assert_eq!(3, get_token_id(&res));
+
+    let res: MintableNumTokensResponse = router
+        .wrap()
+        .query_wasm_smart(minter_addr, &QueryMsg::MintableNumTokens {})
+        .unwrap();
+    assert_eq!(res.count, num_tokens - 3);
+}
+
+#[test]
+fn invalid_collection_name_and_symbol() {
+    let mut router = custom_mock_app();
+    let num_tokens = 10;
+    let (creator, _) = setup_accounts(&mut router, num_tokens);
+
+    let (factory_addr, sg721_code_id) = setup_factory_contract(&mut router, &creator);
+    let creation_fee = coins(CREATION_FEE_PER_TOKEN * num_tokens as u128, NATIVE_DENOM);
+
+    let mut msg = create_minter_msg(&creator, sg721_code_id, num_tokens);
+
+    let mut empty_symbol_msg = msg.clone();
+    empty_symbol_msg.collection_params.symbol = "  ".to_string();
+    let err = router
+        .execute_contract(
+            creator.clone(),
+            factory_addr.clone(),
+            &Sg2ExecuteMsg::CreateMinter(empty_symbol_msg),
+            &creation_fee,
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::InvalidCollectionSymbol { max_length: 12 }.to_string(),
+        err.source().unwrap().source().unwrap().to_string()
+    );
+
+    let mut long_name_msg = msg.clone();
+    long_name_msg.collection_params.name = "a".repeat(65);
+    let err = router
+        .execute_contract(
+            creator.clone(),
+            factory_addr.clone(),
+            &Sg2ExecuteMsg::CreateMinter(long_name_msg),
+            &creation_fee,
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::InvalidCollectionName { max_length: 64 }.to_string(),
+        err.source().unwrap().source().unwrap().to_string()
+    );
+
+    // Surrounding whitespace is trimmed before being stored
+    msg.collection_params.name = " Collection Name ".to_string();
+    let res = router
+        .execute_contract(
+            creator,
+            factory_addr,
+            &Sg2ExecuteMsg::CreateMinter(msg),
+            &creation_fee,
+        )
+        .unwrap();
+    let config: ConfigResponse = router
+        .wrap()
+        .query_wasm_smart(minter_addr_from(&res), &QueryMsg::Config {})
+        .unwrap();
+    let res: cw721::ContractInfoResponse = router
+        .wrap()
+        .query_wasm_smart(config.sg721_address, &Sg721QueryMsg::ContractInfo {})
+        .unwrap();
+    assert_eq!(res.name, "Collection Name");
+}
+
+#[test]
+fn payout_failure_reply() {
+    let mut deps = mock_dependencies_with_balance(&coins(2, "token"));
+
+    // The payout submessage only replies on error
+    let msg = Reply {
+        id: 2,
+        result: SubMsgResult::Err("cannot receive funds".to_string()),
+    };
+    let err = reply(deps.as_mut(), mock_env(), msg).unwrap_err();
+    assert_eq!(
+        ContractError::PayoutFailed {
+            reason: "cannot receive funds".to_string()
+        },
+        err
+    );
+
+    let msg = Reply {
+        id: 3,
+        result: SubMsgResult::Err("unknown".to_string()),
+    };
+    let err = reply(deps.as_mut(), mock_env(), msg).unwrap_err();
+    assert_eq!(ContractError::InvalidReplyID { id: 3 }, err);
+}
+
+#[test]
+fn mint_history() {
+    let mut router = custom_mock_app();
+    let num_tokens = 10;
+    let (creator, buyer) = setup_accounts(&mut router, num_tokens);
+
+    let (minter_addr, _) = setup_minter_contract_with(&mut router, &creator, num_tokens, |msg| {
+        msg.init_msg.mint_price = coin(MINT_PRICE, NATIVE_DENOM);
+        msg.init_msg.record_history = Some(true);
+    });
+
+    let first_time = GENESIS_MINT_START_TIME + 1;
+    let second_time = GENESIS_MINT_START_TIME + 1_000;
+    setup_block_time(&mut router, first_time, None);
+    router
+        .execute_contract(
+            buyer.clone(),
+            minter_addr.clone(),
+            &ExecuteMsg::Mint {
+                referral: None,
+                recipient: None,
+            },
+            &coins(MINT_PRICE, NATIVE_DENOM),
+        )
+        .unwrap();
+    setup_block_time(&mut router, second_time, None);
+    router
+        .execute_contract(
+            creator.clone(),
+            minter_addr.clone(),
+            &ExecuteMsg::MintTo {
+                recipient: buyer.to_string(),
+            },
+            &[],
+        )
+        .unwrap();
+
+    let res: MintHistoryResponse = router
+        .wrap()
+        .query_wasm_smart(
+            minter_addr.clone(),
+            &QueryMsg::MintHistory {
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(res.mints.len(), 2);
+    assert_eq!(res.mints[0].token_id, 1);
+    assert_eq!(res.mints[0].minter, buyer.to_string());
+    assert_eq!(res.mints[0].time, Timestamp::from_nanos(first_time));
+    assert_eq!(res.mints[1].token_id, 2);
+    assert_eq!(res.mints[1].minter, creator.to_string());
+    assert_eq!(res.mints[1].time, Timestamp::from_nanos(second_time));
+
+    let res: MintHistoryResponse = router
+        .wrap()
+        .query_wasm_smart(
+            minter_addr,
+            &QueryMsg::MintHistory {
+                start_after: Some(1),
+                limit: Some(1),
+            },
+        )
+        .unwrap();
+    assert_eq!(res.mints.len(), 1);
+    assert_eq!(res.mints[0].token_id, 2);
+}
+
+#[test]
+fn mint_to_allowlist() {
+    let mut router = custom_mock_app();
+    let num_tokens = 10;
+    let (creator, buyer) = setup_accounts(&mut router, num_tokens);
+
+    let (minter_addr, _) = setup_minter_contract_with(&mut router, &creator, num_tokens, |msg| {
+        msg.init_msg.mint_to_allowlist = Some(vec![buyer.to_string()]);
+    });
+
+    setup_block_time(&mut router, GENESIS_MINT_START_TIME + 1, None);
+
+    let partner = "partner".to_string();
+    let err = router
+        .execute_contract(
+            creator.clone(),
+            minter_addr.clone(),
+            &ExecuteMsg::MintTo {
+                recipient: partner.clone(),
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::RecipientNotAllowed {
+            recipient: partner.clone()
+        }
+        .to_string(),
+        err.source().unwrap().to_string()
+    );
+    let err = router
+        .execute_contract(
+            creator.clone(),
+            minter_addr.clone(),
+            &ExecuteMsg::MintFor {
+                token_id: 5,
+                recipient: partner.clone(),
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::RecipientNotAllowed { recipient: partner }.to_string(),
+        err.source().unwrap().to_string()
+    );
+
+    router
+        .execute_contract(
+            creator.clone(),
+            minter_addr.clone(),
+            &ExecuteMsg::MintTo {
+                recipient: buyer.to_string(),
+            },
+            &[],
+        )
+        .unwrap();
+    router
+        .execute_contract(
+            creator,
+            minter_addr,
+            &ExecuteMsg::MintFor {
+                token_id: 5,
+                recipient: buyer.to_string(),
+            },
+            &[],
+        )
+        .unwrap();
+}
+
+#[test]
+fn vesting_withdraw() {
+    let mut router = custom_mock_app();
+    let num_tokens = 10;
+    let (creator, buyer) = setup_accounts(&mut router, num_tokens);
+
+    let (factory_addr, sg721_code_id) = setup_factory_contract(&mut router, &creator);
+    let creation_fee = coins(CREATION_FEE_PER_TOKEN * num_tokens as u128, NATIVE_DENOM);
+    let start_time = Timestamp::from_nanos(GENESIS_MINT_START_TIME);
+
+    let mut msg = mock_create_minter(None);
+    msg.init_msg.mint_price = coin(MINT_PRICE, NATIVE_DENOM);
+    msg.init_msg.num_tokens = num_tokens;
+    msg.init_msg.vesting = Some(VestingConfig {
+        cliff_time: start_time.plus_seconds(100),
+        duration_secs: 1_000,
+    });
+    msg.collection_params.code_id = sg721_code_id;
+    msg.collection_params.info.creator = creator.to_string();
+    let res = router
+        .execute_contract(
+            creator.clone(),
+            factory_addr,
+            &Sg2ExecuteMsg::CreateMinter(msg),
+            &creation_fee,
+        )
+        .unwrap();
+    let minter_addr = minter_addr_from(&res);
+
+    setup_block_time(&mut router, start_time.plus_seconds(1).nanos(), None);
+    for _ in 0..2 {
+        router
+            .execute_contract(
+                buyer.clone(),
+                minter_addr.clone(),
+                &ExecuteMsg::BatchMint { num_mints: 1 },
+                &coins(MINT_PRICE, NATIVE_DENOM),
+            )
+            .unwrap();
+    }
+    let proceeds = 2 * (MINT_PRICE - MINT_FEE);
+    let creator_balance = |router: &StargazeApp| {
+        router
+            .wrap()
+            .query_balance(creator.clone(), NATIVE_DENOM)
+            .unwrap()
+            .amount
+            .u128()
+    };
+    let initial_balance = creator_balance(&router);
+
+    // Nothing vests before the cliff
+    let err = router
+        .execute_contract(
+            creator.clone(),
+            minter_addr.clone(),
+            &ExecuteMsg::Withdraw {},
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::NoFundsToWithdraw {}.to_string(),
+        err.source().unwrap().to_string()
+    );
+
+    // At the cliff a tenth of the duration has elapsed
+    setup_block_time(&mut router, start_time.plus_seconds(100).nanos(), None);
+    router
+        .execute_contract(
+            creator.clone(),
+            minter_addr.clone(),
+            &ExecuteMsg::Withdraw {},
+            &[],
+        )
+        .unwrap();
+    assert_eq!(creator_balance(&router), initial_balance + proceeds / 10);
+
+    // Withdrawing again in the same block releases nothing more
+    let err = router
+        .execute_contract(
+            creator.clone(),
+            minter_addr.clone(),
+            &ExecuteMsg::Withdraw {},
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::NoFundsToWithdraw {}.to_string(),
+        err.source().unwrap().to_string()
+    );
+
+    // After the full duration the remainder is released
+    setup_block_time(&mut router, start_time.plus_seconds(2_000).nanos(), None);
+    router
+        .execute_contract(
+            creator.clone(),
+            minter_addr.clone(),
+            &ExecuteMsg::Withdraw {},
+            &[],
+        )
+        .unwrap();
+    assert_eq!(creator_balance(&router), initial_balance + proceeds);
+    let minter_balance = router.wrap().query_all_balances(minter_addr).unwrap();
+    assert!(minter_balance.is_empty());
+}
+
+#[test]
+fn mint_with_referral() {
+    let mut router = custom_mock_app();
+    let num_tokens = 10;
+    let (creator, buyer) = setup_accounts(&mut router, num_tokens);
+
+    let (factory_addr, sg721_code_id) = setup_factory_contract(&mut router, &creator);
+    let creation_fee = coins(CREATION_FEE_PER_TOKEN * num_tokens as u128, NATIVE_DENOM);
+
+    let mut msg = create_minter_msg(&creator, sg721_code_id, num_tokens);
+    msg.init_msg.mint_price = coin(MINT_PRICE, NATIVE_DENOM);
+
+    // The referral share can't exceed what's left after the network fee
+    msg.init_msg.referral_bps = Some(9_500);
+    let err = router
+        .execute_contract(
+            creator.clone(),
+            factory_addr.clone(),
+            &Sg2ExecuteMsg::CreateMinter(msg.clone()),
+            &creation_fee,
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::InvalidReferralBps {
+            max: 9_000,
+            got: 9_500
+        }
+        .to_string(),
+        err.source().unwrap().source().unwrap().to_string()
+    );
+
+    // 5% referral
+    msg.init_msg.referral_bps = Some(500);
+    let res = router
+        .execute_contract(
+            creator.clone(),
+            factory_addr,
+            &Sg2ExecuteMsg::CreateMinter(msg),
+            &creation_fee,
+        )
+        .unwrap();
+    let minter_addr = minter_addr_from(&res);
+
+    setup_block_time(&mut router, GENESIS_MINT_START_TIME + 1, None);
+    let referrer = Addr::unchecked("referrer");
+    let creator_balance = router
+        .wrap()
+        .query_balance(creator.clone(), NATIVE_DENOM)
+        .unwrap()
+        .amount
+        .u128();
+
+    router
+        .execute_contract(
+            buyer,
+            minter_addr.clone(),
+            &ExecuteMsg::Mint {
+                referral: Some(referrer.to_string()),
+                recipient: None,
+            },
+            &coins(MINT_PRICE, NATIVE_DENOM),
+        )
+        .unwrap();
+
+    let referral_amount = MINT_PRICE * 5 / 100;
+    let balance = router
+        .wrap()
+        .query_balance(referrer.clone(), NATIVE_DENOM)
+        .unwrap();
+    assert_eq!(balance.amount.u128(), referral_amount);
+    let res: ReferralRewardsResponse = router
+        .wrap()
+        .query_wasm_smart(
+            minter_addr,
+            &QueryMsg::ReferralRewards {
+                address: referrer.to_string(),
+            },
+        )
+        .unwrap();
+    assert_eq!(res.rewards, coins(referral_amount, NATIVE_DENOM));
+
+    let balance = router
+        .wrap()
+        .query_balance(creator, NATIVE_DENOM)
+        .unwrap()
+        .amount
+        .u128();
+    assert_eq!(
+        balance,
+        creator_balance + MINT_PRICE - MINT_FEE - referral_amount
+    );
+}
+
+#[test]
+fn next_token_id() {
+    let mut router = custom_mock_app();
+    let num_tokens = 3;
+    let (creator, buyer) = setup_accounts(&mut router, num_tokens);
+    let (minter_addr, _) = setup_minter_contract(&mut router, &creator, num_tokens, None);
+
+    setup_block_time(&mut router, GENESIS_MINT_START_TIME + 1, None);
+
+    // Take id 1 out of order so the next public mint skips it
+    router
+        .execute_contract(
+            creator,
+            minter_addr.clone(),
+            &ExecuteMsg::MintFor {
+                token_id: 1,
+                recipient: buyer.to_string(),
+            },
+            &[],
+        )
+        .unwrap();
+
+    for _ in 0..num_tokens - 1 {
+        let res: NextTokenIdResponse = router
+            .wrap()
+            .query_wasm_smart(minter_addr.clone(), &QueryMsg::NextTokenId {})
+            .unwrap();
+        let res_mint = router
+            .execute_contract(
+                buyer.clone(),
+                minter_addr.clone(),
+                &ExecuteMsg::Mint {
+                    referral: None,
+                    recipient: None,
+                },
+                &coins(MINT_PRICE, NATIVE_DENOM),
+            )
+            .unwrap();
+        assert_eq!(res.token_id, Some(get_token_id(&res_mint)));
+    }
+
+    let res: NextTokenIdResponse = router
+        .wrap()
+        .query_wasm_smart(minter_addr, &QueryMsg::NextTokenId {})
+        .unwrap();
+    assert_eq!(res.token_id, None);
+}
+
+#[test]
+fn mint_info() {
+    let mut router = custom_mock_app();
+    let num_tokens = 10;
+    let (creator, buyer) = setup_accounts(&mut router, num_tokens);
+    let (minter_addr, _) = setup_minter_contract(&mut router, &creator, num_tokens, None);
+    let whitelist_addr = setup_whitelist_contract(&mut router, &creator);
+
+    setup_block_time(&mut router, GENESIS_MINT_START_TIME - 10, None);
+    router
+        .execute_contract(
+            creator.clone(),
+            minter_addr.clone(),
+            &ExecuteMsg::SetWhitelist {
+                whitelist: whitelist_addr.to_string(),
+            },
+            &[],
+        )
+        .unwrap();
+    router
+        .execute_contract(
+            creator,
+            whitelist_addr,
+            &WhitelistExecuteMsg::AddMembers(AddMembersMsg {
+                to_add: vec![buyer.to_string()],
+            }),
+            &[],
+        )
+        .unwrap();
+
+    // Nothing is open before the start time
+    let res: MintInfoResponse = router
+        .wrap()
+        .query_wasm_smart(minter_addr.clone(), &QueryMsg::MintInfo { address: None })
+        .unwrap();
+    assert!(!res.can_mint);
+    assert_eq!(
+        res.reason,
+        Some(ContractError::BeforeMintStartTime {}.to_string())
+    );
+
+    // While the whitelist is active only members can mint, at the whitelist price
+    setup_block_time(&mut router, GENESIS_MINT_START_TIME + 101, None);
+    let res: MintInfoResponse = router
+        .wrap()
+        .query_wasm_smart(
+            minter_addr.clone(),
+            &QueryMsg::MintInfo {
+                address: Some(buyer.to_string()),
+            },
+        )
+        .unwrap();
+    assert_eq!(
+        res,
+        MintInfoResponse {
+            current_price: coin(WHITELIST_AMOUNT, NATIVE_DENOM),
+            can_mint: true,
+            reason: None,
+            remaining_for_address: Some(WL_PER_ADDRESS_LIMIT),
+        }
+    );
+
+    let outsider = "outsider".to_string();
+    let res: MintInfoResponse = router
+        .wrap()
+        .query_wasm_smart(
+            minter_addr.clone(),
+            &QueryMsg::MintInfo {
+                address: Some(outsider.clone()),
+            },
+        )
+        .unwrap();
+    assert!(!res.can_mint);
+    assert_eq!(
+        res.reason,
+        Some(ContractError::NotWhitelisted { addr: outsider }.to_string())
+    );
+
+    // Once the member has used their allocation they can't mint more
+    router
+        .execute_contract(
+            buyer.clone(),
+            minter_addr.clone(),
+            &ExecuteMsg::Mint {
+                referral: None,
+                recipient: None,
+            },
+            &coins(WHITELIST_AMOUNT, NATIVE_DENOM),
+        )
+        .unwrap();
+    let res: MintInfoResponse = router
+        .wrap()
+        .query_wasm_smart(
+            minter_addr,
+            &QueryMsg::MintInfo {
+                address: Some(buyer.to_string()),
+            },
+        )
+        .unwrap();
+    assert!(!res.can_mint);
+    assert_eq!(res.remaining_for_address, Some(0));
+    assert_eq!(
+        res.reason,
+        Some(ContractError::MaxPerAddressLimitExceeded {}.to_string())
+    );
+}
+
+#[test]
+fn instantiate_with_zero_tokens() {
+    let mut router = custom_mock_app();
+    let (creator, _) = setup_accounts(&mut router, 0);
+    let (factory_addr, sg721_code_id) = setup_factory_contract(&mut router, &creator);
+    let minter_code_id = router.store_code(contract_minter());
+
+    let mut msg = mock_create_minter(None);
+    msg.init_msg.num_tokens = 0;
+    msg.collection_params.code_id = sg721_code_id;
+    msg.collection_params.info.creator = creator.to_string();
+
+    // Instantiate directly from the factory address, past the factory's own checks
+    let err = router
+        .instantiate_contract(minter_code_id, factory_addr, &msg, &[], "minter", None)
+        .unwrap_err();
+    assert_eq!(
+        ContractError::InvalidNumTokens {}.to_string(),
+        err.root_cause().to_string()
+    );
+}
+
+#[test]
+fn whitelist_total_cap() {
+    let mut router = custom_mock_app();
+    let num_tokens = 10;
+    let (creator, buyer) = setup_accounts(&mut router, num_tokens);
+
+    let (factory_addr, sg721_code_id) = setup_factory_contract(&mut router, &creator);
+    let whitelist_addr = setup_whitelist_contract(&mut router, &creator);
+    let buyer2 = Addr::unchecked("buyer2");
+    router
+        .sudo(SudoMsg::Bank(BankSudo::Mint {
+            to_address: buyer2.to_string(),
+            amount: coins(INITIAL_BALANCE, NATIVE_DENOM),
+        }))
+        .unwrap();
+    router
+        .execute_contract(
+            creator.clone(),
+            whitelist_addr.clone(),
+            &WhitelistExecuteMsg::AddMembers(AddMembersMsg {
+                to_add: vec![buyer.to_string(), buyer2.to_string()],
+            }),
+            &[],
+        )
+        .unwrap();
+
+    let creation_fee = coins(CREATION_FEE_PER_TOKEN * num_tokens as u128, NATIVE_DENOM);
+    let mut msg = create_minter_msg(&creator, sg721_code_id, num_tokens);
+    msg.init_msg.mint_price = coin(MINT_PRICE, NATIVE_DENOM);
+    msg.init_msg.whitelist = Some(whitelist_addr.to_string());
+    msg.init_msg.whitelist_total_cap = Some(1);
+    let res = router
+        .execute_contract(
+            creator,
+            factory_addr,
+            &Sg2ExecuteMsg::CreateMinter(msg),
+            &creation_fee,
+        )
+        .unwrap();
+    let minter_addr = minter_addr_from(&res);
+
+    setup_block_time(&mut router, GENESIS_MINT_START_TIME + 101, None);
+    router
+        .execute_contract(
+            buyer,
+            minter_addr.clone(),
+            &ExecuteMsg::Mint {
+                referral: None,
+                recipient: None,
+            },
+            &coins(WHITELIST_AMOUNT, NATIVE_DENOM),
+        )
+        .unwrap();
+
+    // The whitelist has sold its allocation
+    let err = router
+        .execute_contract(
+            buyer2.clone(),
+            minter_addr.clone(),
+            &ExecuteMsg::Mint {
+                referral: None,
+                recipient: None,
+            },
+            &coins(WHITELIST_AMOUNT, NATIVE_DENOM),
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::WhitelistCapReached { cap: 1 }.to_string(),
+        err.source().unwrap().to_string()
+    );
+
+    // Public mint is still open once the whitelist ends
+    setup_block_time(&mut router, GENESIS_MINT_START_TIME + 10000000, None);
+    let res = router.execute_contract(
+        buyer2,
+        minter_addr,
+        &ExecuteMsg::Mint {
+            referral: None,
+            recipient: None,
+        },
+        &coins(MINT_PRICE, NATIVE_DENOM),
+    );
+    assert!(res.is_ok());
+}
+
+#[test]
+fn reroll_returns_token_id_to_pool() {
+    let mut router = custom_mock_app();
+    let num_tokens = 3;
+    let (creator, buyer) = setup_accounts(&mut router, num_tokens);
+    let (factory_addr, sg721_code_id) = setup_factory_contract(&mut router, &creator);
+
+    let reroll_fee = 5_000_000;
+    let creation_fee = coins(CREATION_FEE_PER_TOKEN * num_tokens as u128, NATIVE_DENOM);
+    let mut msg = create_minter_msg(&creator, sg721_code_id, num_tokens);
+    msg.init_msg.mint_price = coin(MINT_PRICE, NATIVE_DENOM);
+    msg.init_msg.reroll_fee = Some(coin(reroll_fee, NATIVE_DENOM));
+    let res = router
+        .execute_contract(
+            creator.clone(),
+            factory_addr,
+            &Sg2ExecuteMsg::CreateMinter(msg),
+            &creation_fee,
+        )
+        .unwrap();
+    let minter_addr = minter_addr_from(&res);
+    let config: ConfigResponse = router
+        .wrap()
+        .query_wasm_smart(minter_addr.clone(), &QueryMsg::Config {})
+        .unwrap();
+    let sg721_addr = Addr::unchecked(config.sg721_address);
+
+    setup_block_time(&mut router, GENESIS_MINT_START_TIME + 1, None);
+    let res = router
+        .execute_contract(
+            buyer.clone(),
+            minter_addr.clone(),
+            &ExecuteMsg::Mint {
+                referral: None,
+                recipient: None,
+            },
+            &coins(MINT_PRICE, NATIVE_DENOM),
+        )
+        .unwrap();
+    let token_id = get_token_id(&res);
+
+    // The minter can only burn the token once the holder approves it
+    let approve_msg: Cw721ExecuteMsg<Empty, Empty> = Cw721ExecuteMsg::Approve {
+        spender: minter_addr.to_string(),
+        token_id: token_id.to_string(),
+        expires: None,
+    };
+    router
+        .execute_contract(buyer.clone(), sg721_addr.clone(), &approve_msg, &[])
+        .unwrap();
+
+    // Only the holder can reroll
+    let err = router
+        .execute_contract(
+            creator.clone(),
+            minter_addr.clone(),
+            &ExecuteMsg::Reroll { token_id },
+            &coins(reroll_fee, NATIVE_DENOM),
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::Unauthorized("Sender is not the token owner".to_owned()).to_string(),
+        err.source().unwrap().to_string()
+    );
+
+    let creator_balance = router
+        .wrap()
+        .query_balance(creator.to_string(), NATIVE_DENOM)
+        .unwrap();
+    let res = router
+        .execute_contract(
+            buyer.clone(),
+            minter_addr.clone(),
+            &ExecuteMsg::Reroll { token_id },
+            &coins(reroll_fee, NATIVE_DENOM),
+        )
+        .unwrap();
+    let new_token_id = get_token_id(&res);
+    assert_ne!(token_id, new_token_id);
+
+    // The new token belongs to the buyer and the old one is gone
+    let res: OwnerOfResponse = router
+        .wrap()
+        .query_wasm_smart(
+            sg721_addr.clone(),
+            &Cw721QueryMsg::OwnerOf {
+                token_id: new_token_id.to_string(),
+                include_expired: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(res.owner, buyer.to_string());
+    let res: StdResult<OwnerOfResponse> = router.wrap().query_wasm_smart(
+        sg721_addr,
+        &Cw721QueryMsg::OwnerOf {
+            token_id: token_id.to_string(),
+            include_expired: None,
+        },
+    );
+    assert!(res.is_err());
+
+    // The burned id is back in the pool and up next
+    let res: MintableNumTokensResponse = router
+        .wrap()
+        .query_wasm_smart(minter_addr.clone(), &QueryMsg::MintableNumTokens {})
+        .unwrap();
+    assert_eq!(res.count, num_tokens - 1);
+    let res: NextTokenIdResponse = router
+        .wrap()
+        .query_wasm_smart(minter_addr, &QueryMsg::NextTokenId {})
+        .unwrap();
+    assert_eq!(res.token_id, Some(token_id));
+
+    // The fee is paid to the creator
+    let balance = router
+        .wrap()
+        .query_balance(creator.to_string(), NATIVE_DENOM)
+        .unwrap();
+    assert_eq!(
+        balance.amount,
+        creator_balance.amount + Uint128::from(reroll_fee)
+    );
+}
+
+#[test]
+fn whitelist_mint_requires_whitelist_price() {
+    let mut router = custom_mock_app();
+    let num_tokens = 10;
+    let (creator, buyer) = setup_accounts(&mut router, num_tokens);
+
+    let (factory_addr, sg721_code_id) = setup_factory_contract(&mut router, &creator);
+    let whitelist_addr = setup_whitelist_contract(&mut router, &creator);
+    router
+        .execute_contract(
+            creator.clone(),
+            whitelist_addr.clone(),
+            &WhitelistExecuteMsg::AddMembers(AddMembersMsg {
+                to_add: vec![buyer.to_string()],
+            }),
+            &[],
+        )
+        .unwrap();
+
+    let creation_fee = coins(CREATION_FEE_PER_TOKEN * num_tokens as u128, NATIVE_DENOM);
+    let mut msg = create_minter_msg(&creator, sg721_code_id, num_tokens);
+    msg.init_msg.mint_price = coin(MINT_PRICE, NATIVE_DENOM);
+    msg.init_msg.whitelist = Some(whitelist_addr.to_string());
+    let res = router
+        .execute_contract(
+            creator,
+            factory_addr,
+            &Sg2ExecuteMsg::CreateMinter(msg),
+            &creation_fee,
+        )
+        .unwrap();
+    let minter_addr = minter_addr_from(&res);
+
+    setup_block_time(&mut router, GENESIS_MINT_START_TIME + 101, None);
+
+    // The public price is rejected while the whitelist is active
+    let err = router
+        .execute_contract(
+            buyer.clone(),
+            minter_addr.clone(),
+            &ExecuteMsg::Mint {
+                referral: None,
+                recipient: None,
+            },
+            &coins(MINT_PRICE, NATIVE_DENOM),
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::IncorrectPaymentAmount(
+            coin(MINT_PRICE, NATIVE_DENOM),
+            coin(WHITELIST_AMOUNT, NATIVE_DENOM)
+        )
+        .to_string(),
+        err.source().unwrap().to_string()
+    );
+
+    let res = router.execute_contract(
+        buyer,
+        minter_addr,
+        &ExecuteMsg::Mint {
+            referral: None,
+            recipient: None,
+        },
+        &coins(WHITELIST_AMOUNT, NATIVE_DENOM),
+    );
+    assert!(res.is_ok());
+}
+
+#[test]
+fn reserved_tokens_query() {
+    let mut router = custom_mock_app();
+    let num_tokens = 10;
+    let (creator, _) = setup_accounts(&mut router, num_tokens);
+    let (minter_addr, _) = setup_minter_contract(&mut router, &creator, num_tokens, None);
+
+    router
+        .execute_contract(
+            creator.clone(),
+            minter_addr.clone(),
+            &ExecuteMsg::ReserveTokens {
+                token_ids: vec![7, 2, 5],
+            },
+            &[],
+        )
+        .unwrap();
+
+    let res: ReservedTokensResponse = router
+        .wrap()
+        .query_wasm_smart(
+            minter_addr.clone(),
+            &QueryMsg::ReservedTokens {
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(res.token_ids, vec![2, 5, 7]);
+    let res: MintableNumTokensResponse = router
+        .wrap()
+        .query_wasm_smart(minter_addr.clone(), &QueryMsg::MintableNumTokens {})
+        .unwrap();
+    assert_eq!(res.count, num_tokens - 3);
+
+    // Paginated
+    let res: ReservedTokensResponse = router
+        .wrap()
+        .query_wasm_smart(
+            minter_addr.clone(),
+            &QueryMsg::ReservedTokens {
+                start_after: Some(2),
+                limit: Some(1),
+            },
+        )
+        .unwrap();
+    assert_eq!(res.token_ids, vec![5]);
+
+    router
+        .execute_contract(
+            creator.clone(),
+            minter_addr.clone(),
+            &ExecuteMsg::ReleaseTokens { token_ids: vec![5] },
+            &[],
+        )
+        .unwrap();
+    let res: ReservedTokensResponse = router
+        .wrap()
+        .query_wasm_smart(
+            minter_addr.clone(),
+            &QueryMsg::ReservedTokens {
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(res.token_ids, vec![2, 7]);
+    let res: MintableNumTokensResponse = router
+        .wrap()
+        .query_wasm_smart(minter_addr.clone(), &QueryMsg::MintableNumTokens {})
+        .unwrap();
+    assert_eq!(res.count, num_tokens - 2);
+
+    // Releasing an id twice fails
+    let err = router
+        .execute_contract(
+            creator,
+            minter_addr,
+            &ExecuteMsg::ReleaseTokens { token_ids: vec![5] },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::TokenIdNotReserved { token_id: 5 }.to_string(),
+        err.source().unwrap().to_string()
+    );
+}
+
+#[test]
+fn set_token_uri_drops_reservations() {
+    let mut router = custom_mock_app();
+    let num_tokens = 10;
+    let (creator, buyer) = setup_accounts(&mut router, num_tokens);
+    let (minter_addr, _) = setup_minter_contract(&mut router, &creator, num_tokens, None);
+
+    setup_block_time(&mut router, GENESIS_MINT_START_TIME + 1, None);
+    router
+        .execute_contract(
+            creator.clone(),
+            minter_addr.clone(),
+            &ExecuteMsg::MintFor {
+                token_id: 1,
+                recipient: buyer.to_string(),
+            },
+            &[],
+        )
+        .unwrap();
+    router
+        .execute_contract(
+            creator.clone(),
+            minter_addr.clone(),
+            &ExecuteMsg::ReserveTokens {
+                token_ids: vec![2, 5],
+            },
+            &[],
+        )
+        .unwrap();
+
+    // Validated before the fee is checked
+    let err = router
+        .execute_contract(
+            creator.clone(),
+            minter_addr.clone(),
+            &ExecuteMsg::SetTokenUri {
+                uri: COLLECTION2_URI.to_string(),
+                num_tokens: 0,
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::InvalidNumTokens {}.to_string(),
+        err.source().unwrap().to_string()
+    );
+
+    let new_num_tokens = 3;
+    router
+        .execute_contract(
+            creator.clone(),
+            minter_addr.clone(),
+            &ExecuteMsg::SetTokenUri {
+                uri: COLLECTION2_URI.to_string(),
+                num_tokens: new_num_tokens,
+            },
+            &coins(
+                CREATION_FEE_PER_TOKEN * new_num_tokens as u128,
+                NATIVE_DENOM,
+            ),
+        )
+        .unwrap();
+
+    // The reservations of the old round are gone and not counted as minted
+    let res: ReservedTokensResponse = router
+        .wrap()
+        .query_wasm_smart(
+            minter_addr.clone(),
+            &QueryMsg::ReservedTokens {
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+    assert!(res.token_ids.is_empty());
+    let err = router
+        .execute_contract(
+            creator,
+            minter_addr.clone(),
+            &ExecuteMsg::ReleaseTokens { token_ids: vec![2] },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::TokenIdNotReserved { token_id: 2 }.to_string(),
+        err.source().unwrap().to_string()
+    );
+    let res: ConfigResponse = router
+        .wrap()
+        .query_wasm_smart(minter_addr.clone(), &QueryMsg::Config {})
+        .unwrap();
+    assert_eq!(res.num_tokens, 1 + new_num_tokens);
+    let res: MintableNumTokensResponse = router
+        .wrap()
+        .query_wasm_smart(minter_addr, &QueryMsg::MintableNumTokens {})
+        .unwrap();
+    assert_eq!(res.count, new_num_tokens);
+}
+
+#[test]
+fn batch_mint_not_enough_tokens_remaining() {
+    let mut router = custom_mock_app();
+    let num_tokens = 3;
+    let (creator, buyer) = setup_accounts(&mut router, num_tokens);
+    let (minter_addr, _) = setup_minter_contract(&mut router, &creator, num_tokens, None);
+
+    setup_block_time(&mut router, GENESIS_MINT_START_TIME + 1, None);
+    router
+        .execute_contract(
+            buyer.clone(),
+            minter_addr.clone(),
+            &ExecuteMsg::Mint {
+                referral: None,
+                recipient: None,
+            },
+            &coins(MINT_PRICE, NATIVE_DENOM),
+        )
+        .unwrap();
+
+    let balance = router
+        .wrap()
+        .query_balance(buyer.to_string(), NATIVE_DENOM)
+        .unwrap();
+    let err = router
+        .execute_contract(
+            buyer.clone(),
+            minter_addr.clone(),
+            &ExecuteMsg::BatchMint { num_mints: 3 },
+            &coins(MINT_PRICE * 3, NATIVE_DENOM),
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::NotEnoughTokensRemaining {
+            requested: 3,
+            remaining: 2
+        }
+        .to_string(),
+        err.source().unwrap().to_string()
+    );
+
+    // Nothing was charged or minted
+    let res = router
+        .wrap()
+        .query_balance(buyer.to_string(), NATIVE_DENOM)
+        .unwrap();
+    assert_eq!(res, balance);
+    let res: MintableNumTokensResponse = router
+        .wrap()
+        .query_wasm_smart(minter_addr, &QueryMsg::MintableNumTokens {})
+        .unwrap();
+    assert_eq!(res.count, 2);
+}
+
+#[test]
+fn retry_collection_instantiate() {
+    let mut router = custom_mock_app();
+    let num_tokens = 10;
+    let (creator, buyer) = setup_accounts(&mut router, num_tokens);
+    let (factory_addr, sg721_code_id) = setup_factory_contract(&mut router, &creator);
+
+    // Code id 1 is the minter, which can't be instantiated as a collection.
+    // The minter is created but left without a collection.
+    let creation_fee = coins(CREATION_FEE_PER_TOKEN * num_tokens as u128, NATIVE_DENOM);
+    let msg = create_minter_msg(&creator, 1, num_tokens);
+    let res = router
+        .execute_contract(
+            creator.clone(),
+            factory_addr,
+            &Sg2ExecuteMsg::CreateMinter(msg),
+            &creation_fee,
+        )
+        .unwrap();
+    let minter_addr = minter_addr_from(&res);
+
+    setup_block_time(&mut router, GENESIS_MINT_START_TIME + 1, None);
+    let mint_msg = ExecuteMsg::Mint {
+        referral: None,
+        recipient: None,
+    };
+    router
+        .execute_contract(
+            buyer.clone(),
+            minter_addr.clone(),
+            &mint_msg,
+            &coins(MIN_MINT_PRICE, NATIVE_DENOM),
+        )
+        .unwrap_err();
+
+    // Only the admin can retry
+    let retry_msg = ExecuteMsg::RetryCollectionInstantiate { sg721_code_id };
+    let err = router
+        .execute_contract(buyer.clone(), minter_addr.clone(), &retry_msg, &[])
+        .unwrap_err();
+    assert_eq!(
+        ContractError::Unauthorized("Sender is not an admin".to_owned()).to_string(),
+        err.source().unwrap().to_string()
+    );
+
+    // The collection doesn't accept funds
+    router
+        .execute_contract(
+            creator.clone(),
+            minter_addr.clone(),
+            &retry_msg,
+            &coins(1, NATIVE_DENOM),
+        )
+        .unwrap_err();
+
+    // Retrying with the right code id instantiates the collection
+    router
+        .execute_contract(creator.clone(), minter_addr.clone(), &retry_msg, &[])
+        .unwrap();
+    let config: ConfigResponse = router
+        .wrap()
+        .query_wasm_smart(minter_addr.clone(), &QueryMsg::Config {})
+        .unwrap();
+    assert_eq!(config.sg721_code_id, sg721_code_id);
+
+    router
+        .execute_contract(
+            buyer,
+            minter_addr.clone(),
+            &mint_msg,
+            &coins(MIN_MINT_PRICE, NATIVE_DENOM),
+        )
+        .unwrap();
+
+    // The collection can only be instantiated once
+    let err = router
+        .execute_contract(creator, minter_addr, &retry_msg, &[])
+        .unwrap_err();
+    assert_eq!(
+        ContractError::CollectionAlreadyInstantiated {}.to_string(),
+        err.source().unwrap().to_string()
+    );
+}
+
+#[test]
+fn locked_whitelist_still_mints() {
+    let mut router = custom_mock_app();
+    let num_tokens = 10;
+    let (creator, buyer) = setup_accounts(&mut router, num_tokens);
+
+    let (factory_addr, sg721_code_id) = setup_factory_contract(&mut router, &creator);
+    let whitelist_addr = setup_whitelist_contract(&mut router, &creator);
+    router
+        .execute_contract(
+            creator.clone(),
+            whitelist_addr.clone(),
+            &WhitelistExecuteMsg::AddMembers(AddMembersMsg {
+                to_add: vec![buyer.to_string()],
+            }),
+            &[],
+        )
+        .unwrap();
+
+    let creation_fee = coins(CREATION_FEE_PER_TOKEN * num_tokens as u128, NATIVE_DENOM);
+    let mut msg = create_minter_msg(&creator, sg721_code_id, num_tokens);
+    msg.init_msg.mint_price = coin(MINT_PRICE, NATIVE_DENOM);
+    msg.init_msg.whitelist = Some(whitelist_addr.to_string());
+    let res = router
+        .execute_contract(
+            creator.clone(),
+            factory_addr,
+            &Sg2ExecuteMsg::CreateMinter(msg),
+            &creation_fee,
+        )
+        .unwrap();
+    let minter_addr = minter_addr_from(&res);
+
+    router
+        .execute_contract(
+            creator.clone(),
+            whitelist_addr.clone(),
+            &WhitelistExecuteMsg::LockWhitelist {},
+            &[],
+        )
+        .unwrap();
+
+    let err = router
+        .execute_contract(
+            creator.clone(),
+            whitelist_addr.clone(),
+            &WhitelistExecuteMsg::AddMembers(AddMembersMsg {
+                to_add: vec!["buyer2".to_string()],
+            }),
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        WhitelistContractError::WhitelistLocked {}.to_string(),
+        err.source().unwrap().to_string()
+    );
+    let err = router
+        .execute_contract(
+            creator,
+            whitelist_addr,
+            &WhitelistExecuteMsg::UpdateEndTime(Timestamp::from_nanos(
+                GENESIS_MINT_START_TIME + 20000000,
+            )),
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        WhitelistContractError::WhitelistLocked {}.to_string(),
+        err.source().unwrap().to_string()
+    );
+
+    setup_block_time(&mut router, GENESIS_MINT_START_TIME + 101, None);
+    let res = router.execute_contract(
+        buyer,
+        minter_addr,
+        &ExecuteMsg::Mint {
+            referral: None,
+            recipient: None,
+        },
+        &coins(WHITELIST_AMOUNT, NATIVE_DENOM),
+    );
+    assert!(res.is_ok());
+}
+
+#[test]
+fn effective_per_address_limit_query() {
+    let mut router = custom_mock_app();
+    let num_tokens = 10;
+    let (creator, _) = setup_accounts(&mut router, num_tokens);
+
+    let (factory_addr, sg721_code_id) = setup_factory_contract(&mut router, &creator);
+    let whitelist_addr = setup_whitelist_contract(&mut router, &creator);
+
+    let creation_fee = coins(CREATION_FEE_PER_TOKEN * num_tokens as u128, NATIVE_DENOM);
+    let mut msg = create_minter_msg(&creator, sg721_code_id, num_tokens);
+    msg.init_msg.mint_price = coin(MINT_PRICE, NATIVE_DENOM);
+    msg.init_msg.whitelist = Some(whitelist_addr.to_string());
+    let per_address_limit = msg.init_msg.per_address_limit;
+    let res = router
+        .execute_contract(
+            creator,
+            factory_addr,
+            &Sg2ExecuteMsg::CreateMinter(msg),
+            &creation_fee,
+        )
+        .unwrap();
+    let minter_addr = minter_addr_from(&res);
+
+    // Whitelist limit while the whitelist is active
+    setup_block_time(&mut router, GENESIS_MINT_START_TIME + 101, None);
+    let res: EffectivePerAddressLimitResponse = router
+        .wrap()
+        .query_wasm_smart(minter_addr.clone(), &QueryMsg::EffectivePerAddressLimit {})
+        .unwrap();
+    assert_eq!(res.per_address_limit, WL_PER_ADDRESS_LIMIT);
+
+    // Public limit once it expires
+    setup_block_time(&mut router, GENESIS_MINT_START_TIME + 10000000, None);
+    let res: EffectivePerAddressLimitResponse = router
+        .wrap()
+        .query_wasm_smart(minter_addr, &QueryMsg::EffectivePerAddressLimit {})
+        .unwrap();
+    assert_eq!(res.per_address_limit, per_address_limit);
+}
+
+#[test]
+fn merkle_allocations() {
+    let mut router = custom_mock_app();
+    let num_tokens = 10;
+    let (creator, buyer) = setup_accounts(&mut router, num_tokens);
+    let buyer2 = Addr::unchecked("buyer2");
+    router
+        .sudo(SudoMsg::Bank(BankSudo::Mint {
+            to_address: buyer2.to_string(),
+            amount: coins(INITIAL_BALANCE, NATIVE_DENOM),
+        }))
+        .unwrap();
+    let (factory_addr, sg721_code_id) = setup_factory_contract(&mut router, &creator);
+
+    // Two leaf tree, siblings are hashed in sorted order
+    let leaf1 = Sha256::digest(format!("{}:1", buyer).as_bytes()).to_vec();
+    let leaf2 = Sha256::digest(format!("{}:2", buyer2).as_bytes()).to_vec();
+    let (first, second) = if leaf1 <= leaf2 {
+        (&leaf1, &leaf2)
+    } else {
+        (&leaf2, &leaf1)
+    };
+    let root = Sha256::new()
+        .chain_update(first)
+        .chain_update(second)
+        .finalize()
+        .to_vec();
+
+    let creation_fee = coins(CREATION_FEE_PER_TOKEN * num_tokens as u128, NATIVE_DENOM);
+    let mut msg = create_minter_msg(&creator, sg721_code_id, num_tokens);
+    msg.init_msg.allocation_merkle_root = Some(HexBinary::from(root));
+    let res = router
+        .execute_contract(
+            creator,
+            factory_addr,
+            &Sg2ExecuteMsg::CreateMinter(msg),
+            &creation_fee,
+        )
+        .unwrap();
+    let minter_addr = minter_addr_from(&res);
+    setup_block_time(&mut router, GENESIS_MINT_START_TIME + 1, None);
+
+    let buyer_mint = ExecuteMsg::MerkleMint {
+        allocation: 1,
+        proof: vec![HexBinary::from(leaf2.clone())],
+    };
+    let buyer2_mint = ExecuteMsg::MerkleMint {
+        allocation: 2,
+        proof: vec![HexBinary::from(leaf1)],
+    };
+
+    // Claiming a larger allocation than the leaf fails the proof
+    let err = router
+        .execute_contract(
+            buyer.clone(),
+            minter_addr.clone(),
+            &ExecuteMsg::MerkleMint {
+                allocation: 2,
+                proof: vec![HexBinary::from(leaf2)],
+            },
+            &coins(MIN_MINT_PRICE, NATIVE_DENOM),
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::InvalidMerkleProof {}.to_string(),
+        err.source().unwrap().to_string()
+    );
+
+    // buyer can mint once
+    router
+        .execute_contract(
+            buyer.clone(),
+            minter_addr.clone(),
+            &buyer_mint,
+            &coins(MIN_MINT_PRICE, NATIVE_DENOM),
+        )
+        .unwrap();
+    let err = router
+        .execute_contract(
+            buyer,
+            minter_addr.clone(),
+            &buyer_mint,
+            &coins(MIN_MINT_PRICE, NATIVE_DENOM),
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::MaxPerAddressLimitExceeded {}.to_string(),
+        err.source().unwrap().to_string()
+    );
+
+    // buyer2 can mint twice
+    for _ in 0..2 {
+        router
+            .execute_contract(
+                buyer2.clone(),
+                minter_addr.clone(),
+                &buyer2_mint,
+                &coins(MIN_MINT_PRICE, NATIVE_DENOM),
+            )
+            .unwrap();
+    }
+    let err = router
+        .execute_contract(
+            buyer2.clone(),
+            minter_addr.clone(),
+            &buyer2_mint,
+            &coins(MIN_MINT_PRICE, NATIVE_DENOM),
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::MaxPerAddressLimitExceeded {}.to_string(),
+        err.source().unwrap().to_string()
+    );
+
+    let res: MintCountResponse = router
+        .wrap()
+        .query_wasm_smart(
+            minter_addr,
+            &QueryMsg::MintCount {
+                address: buyer2.to_string(),
+            },
+        )
+        .unwrap();
+    assert_eq!(res.count, 2);
+}
+
+#[test]
+fn creator_distinct_from_admin() {
+    let mut router = custom_mock_app();
+    let num_tokens = 10;
+    let (creator, _) = setup_accounts(&mut router, num_tokens);
+    let admin = Addr::unchecked("admin");
+    let (minter_addr, _) = setup_minter_contract_with(&mut router, &creator, num_tokens, |msg| {
+        msg.init_msg.admin = Some(admin.to_string());
+        msg.init_msg.creator_reserve = Some(2);
+        msg.collection_params.info.royalty_info = Some(RoyaltyInfoResponse {
+            payment_address: creator.to_string(),
+            share: Decimal::percent(10),
+        });
+    });
+
+    let config: ConfigResponse = router
+        .wrap()
+        .query_wasm_smart(minter_addr.clone(), &QueryMsg::Config {})
+        .unwrap();
+    assert_eq!(config.admin, admin.to_string());
+    assert_eq!(config.creator, creator.to_string());
+
+    // The collection is attributed to the creator, who receives royalties
+    let res: CollectionInfoResponse = router
+        .wrap()
+        .query_wasm_smart(&config.sg721_address, &Sg721QueryMsg::CollectionInfo {})
+        .unwrap();
+    assert_eq!(res.creator, creator.to_string());
+    assert_eq!(
+        res.royalty_info.unwrap().payment_address,
+        creator.to_string()
+    );
+
+    // and owns the creator reserve
+    for token_id in ["1", "2"] {
+        let res: OwnerOfResponse = router
+            .wrap()
+            .query_wasm_smart(
+                &config.sg721_address,
+                &Cw721QueryMsg::OwnerOf {
+                    token_id: token_id.to_string(),
+                    include_expired: None,
+                },
+            )
+            .unwrap();
+        assert_eq!(res.owner, creator.to_string());
+    }
+
+    // Only the admin has operational control
+    let pause_msg = ExecuteMsg::SetMintingPause { pause: true };
+    let err = router
+        .execute_contract(creator, minter_addr.clone(), &pause_msg, &[])
+        .unwrap_err();
+    assert_eq!(
+        ContractError::Unauthorized("Sender is not an admin".to_owned()).to_string(),
+        err.source().unwrap().to_string()
+    );
+    router
+        .execute_contract(admin, minter_addr, &pause_msg, &[])
+        .unwrap();
+}
+
+#[test]
+fn global_mint_interval() {
+    let mut router = custom_mock_app();
+    let num_tokens = 10;
+    let (creator, buyer) = setup_accounts(&mut router, num_tokens);
+    let (minter_addr, _) = setup_minter_contract_with(&mut router, &creator, num_tokens, |msg| {
+        msg.init_msg.global_mint_interval_secs = Some(10);
+    });
+
+    let start = Timestamp::from_nanos(GENESIS_MINT_START_TIME);
+    setup_block_time(&mut router, start.nanos(), None);
+    router
+        .execute_contract(
+            buyer,
+            minter_addr.clone(),
+            &ExecuteMsg::Mint {
+                referral: None,
+                recipient: None,
+            },
+            &coins(MIN_MINT_PRICE, NATIVE_DENOM),
+        )
+        .unwrap();
+
+    // The interval applies to every address
+    setup_block_time(&mut router, start.plus_seconds(5).nanos(), None);
+    let err = router
+        .execute_contract(
+            creator.clone(),
+            minter_addr.clone(),
+            &ExecuteMsg::Mint {
+                referral: None,
+                recipient: None,
+            },
+            &coins(MIN_MINT_PRICE, NATIVE_DENOM),
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::GlobalMintIntervalNotElapsed {
+            next_mint_time: start.plus_seconds(10)
+        }
+        .to_string(),
+        err.source().unwrap().to_string()
+    );
+
+    setup_block_time(&mut router, start.plus_seconds(10).nanos(), None);
+    router
+        .execute_contract(
+            creator,
+            minter_addr,
+            &ExecuteMsg::Mint {
+                referral: None,
+                recipient: None,
+            },
+            &coins(MIN_MINT_PRICE, NATIVE_DENOM),
+        )
+        .unwrap();
+}
+
+#[test]
+fn never_expiring_whitelist() {
+    let mut router = custom_mock_app();
+    let num_tokens = 10;
+    let (creator, buyer) = setup_accounts(&mut router, num_tokens);
+
+    let (factory_addr, sg721_code_id) = setup_factory_contract(&mut router, &creator);
+    let whitelist_addr = setup_whitelist_contract(&mut router, &creator);
+    router
+        .execute_contract(
+            creator.clone(),
+            whitelist_addr.clone(),
+            &WhitelistExecuteMsg::UpdateEndTime(NEVER_EXPIRES),
+            &[],
+        )
+        .unwrap();
+    router
+        .execute_contract(
+            creator.clone(),
+            whitelist_addr.clone(),
+            &WhitelistExecuteMsg::AddMembers(AddMembersMsg {
+                to_add: vec![buyer.to_string()],
+            }),
+            &[],
+        )
+        .unwrap();
+
+    let creation_fee = coins(CREATION_FEE_PER_TOKEN * num_tokens as u128, NATIVE_DENOM);
+    let mut msg = create_minter_msg(&creator, sg721_code_id, num_tokens);
+    msg.init_msg.mint_price = coin(MINT_PRICE, NATIVE_DENOM);
+    msg.init_msg.whitelist = Some(whitelist_addr.to_string());
+    let res = router
+        .execute_contract(
+            creator.clone(),
+            factory_addr,
+            &Sg2ExecuteMsg::CreateMinter(msg),
+            &creation_fee,
+        )
+        .unwrap();
+    let minter_addr = minter_addr_from(&res);
+
+    let res: AllowlistOnlyResponse = router
+        .wrap()
+        .query_wasm_smart(minter_addr.clone(), &QueryMsg::AllowlistOnly {})
+        .unwrap();
+    assert!(res.allowlist_only);
+
+    // Long after a regular whitelist would have ended
+    setup_block_time(&mut router, GENESIS_MINT_START_TIME + 100_000_000_000, None);
+    let err = router
+        .execute_contract(
+            creator.clone(),
+            minter_addr.clone(),
+            &ExecuteMsg::Mint {
+                referral: None,
+                recipient: None,
+            },
+            &coins(WHITELIST_AMOUNT, NATIVE_DENOM),
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::AllowlistOnly {
+            addr: creator.to_string()
+        }
+        .to_string(),
+        err.source().unwrap().to_string()
+    );
+
+    let res = router.execute_contract(
+        buyer,
+        minter_addr,
+        &ExecuteMsg::Mint {
+            referral: None,
+            recipient: None,
+        },
+        &coins(WHITELIST_AMOUNT, NATIVE_DENOM),
+    );
+    assert!(res.is_ok());
+}
+
+#[test]
+fn auto_withdraw_threshold() {
+    let mut router = custom_mock_app();
+    let num_tokens = 10;
+    let (creator, buyer) = setup_accounts(&mut router, num_tokens);
+    let (minter_addr, _) = setup_minter_contract_with(&mut router, &creator, num_tokens, |msg| {
+        msg.init_msg.auto_withdraw_threshold = Some(Uint128::new(100_000_000));
+    });
+    setup_block_time(&mut router, GENESIS_MINT_START_TIME + 1, None);
+
+    let creator_balance = router
+        .wrap()
+        .query_balance(creator.to_string(), NATIVE_DENOM)
+        .unwrap()
+        .amount;
+    // Each mint leaves the price minus the 10% network fee
+    let seller_amount = MIN_MINT_PRICE * 9 / 10;
+
+    // Proceeds are held below the threshold
+    for i in 1..=2 {
+        router
+            .execute_contract(
+                buyer.clone(),
+                minter_addr.clone(),
+                &ExecuteMsg::Mint {
+                    referral: None,
+                    recipient: None,
+                },
+                &coins(MIN_MINT_PRICE, NATIVE_DENOM),
+            )
+            .unwrap();
+        let balance = router
+            .wrap()
+            .query_balance(minter_addr.to_string(), NATIVE_DENOM)
+            .unwrap();
+        assert_eq!(balance.amount.u128(), seller_amount * i);
+    }
+
+    // and swept once they reach it
+    let res = router
+        .execute_contract(
+            buyer,
+            minter_addr.clone(),
+            &ExecuteMsg::Mint {
+                referral: None,
+                recipient: None,
+            },
+            &coins(MIN_MINT_PRICE, NATIVE_DENOM),
+        )
+        .unwrap();
+    assert!(res.events.iter().any(|e| e.ty == "wasm-auto_withdraw"));
+    let balance = router
+        .wrap()
+        .query_balance(minter_addr.to_string(), NATIVE_DENOM)
+        .unwrap();
+    assert_eq!(balance.amount, Uint128::zero());
+    let balance = router
+        .wrap()
+        .query_balance(creator.to_string(), NATIVE_DENOM)
+        .unwrap();
+    assert_eq!(
+        balance.amount,
+        creator_balance + Uint128::from(seller_amount * 3)
+    );
+}
+
+#[test]
+fn accepted_denoms_query() {
+    let mut router = custom_mock_app();
+    let num_tokens = 10;
+    let (creator, _) = setup_accounts(&mut router, num_tokens);
+    let (minter_addr, config) = setup_minter_contract(&mut router, &creator, num_tokens, None);
+
+    let res: AcceptedDenomsResponse = router
+        .wrap()
+        .query_wasm_smart(minter_addr, &QueryMsg::AcceptedDenoms {})
+        .unwrap();
+    assert_eq!(res.prices, vec![config.mint_price]);
+}
+
+#[test]
+fn mint_to_gift_recipient() {
+    let mut router = custom_mock_app();
+    let num_tokens = 10;
+    let (creator, buyer) = setup_accounts(&mut router, num_tokens);
+    let (minter_addr, config) = setup_minter_contract(&mut router, &creator, num_tokens, None);
+    let friend = Addr::unchecked("friend");
+
+    setup_block_time(&mut router, GENESIS_MINT_START_TIME + 1, None);
+    let buyer_balance = router
+        .wrap()
+        .query_balance(buyer.to_string(), NATIVE_DENOM)
+        .unwrap();
+    let res = router
+        .execute_contract(
+            buyer.clone(),
+            minter_addr.clone(),
+            &ExecuteMsg::Mint {
+                referral: None,
+                recipient: Some(friend.to_string()),
+            },
+            &coins(MINT_PRICE, NATIVE_DENOM),
+        )
+        .unwrap();
+    let token_id = get_token_id(&res);
+
+    // The buyer paid
+    let balance = router
+        .wrap()
+        .query_balance(buyer.to_string(), NATIVE_DENOM)
+        .unwrap();
+    assert_eq!(
+        balance.amount,
+        buyer_balance.amount - Uint128::from(MINT_PRICE)
+    );
+
+    // and the token landed with the recipient
+    let res: OwnerOfResponse = router
+        .wrap()
+        .query_wasm_smart(
+            config.sg721_address,
+            &Cw721QueryMsg::OwnerOf {
+                token_id: token_id.to_string(),
+                include_expired: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(res.owner, friend.to_string());
+
+    // The mint counts towards the buyer's limit
+    let res: MintCountResponse = router
+        .wrap()
+        .query_wasm_smart(
+            minter_addr,
+            &QueryMsg::MintCount {
+                address: buyer.to_string(),
+            },
+        )
+        .unwrap();
+    assert_eq!(res.count, 1);
+}
+
+#[test]
+fn block_contract_recipients() {
+    let mut router = custom_mock_app();
+    let num_tokens = 10;
+    let (creator, buyer) = setup_accounts(&mut router, num_tokens);
+    let (factory_addr, sg721_code_id) = setup_factory_contract(&mut router, &creator);
+
+    let creation_fee = coins(CREATION_FEE_PER_TOKEN * num_tokens as u128, NATIVE_DENOM);
+    let mut msg = create_minter_msg(&creator, sg721_code_id, num_tokens);
+    msg.init_msg.allow_contract_recipients = Some(false);
+    let res = router
+        .execute_contract(
+            creator.clone(),
+            factory_addr.clone(),
+            &Sg2ExecuteMsg::CreateMinter(msg),
+            &creation_fee,
+        )
+        .unwrap();
+    let minter_addr = minter_addr_from(&res);
+    setup_block_time(&mut router, GENESIS_MINT_START_TIME + 1, None);
+
+    let expected = ContractError::ContractRecipientNotAllowed {
+        recipient: factory_addr.to_string(),
+    }
+    .to_string();
+    let err = router
+        .execute_contract(
+            buyer.clone(),
+            minter_addr.clone(),
+            &ExecuteMsg::Mint {
+                referral: None,
+                recipient: Some(factory_addr.to_string()),
+            },
+            &coins(MIN_MINT_PRICE, NATIVE_DENOM),
+        )
+        .unwrap_err();
+    assert_eq!(expected, err.source().unwrap().to_string());
+    let err = router
+        .execute_contract(
+            creator.clone(),
+            minter_addr.clone(),
+            &ExecuteMsg::MintTo {
+                recipient: factory_addr.to_string(),
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(expected, err.source().unwrap().to_string());
+    let err = router
+        .execute_contract(
+            creator.clone(),
+            minter_addr.clone(),
+            &ExecuteMsg::MintFor {
+                token_id: 1,
+                recipient: factory_addr.to_string(),
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(expected, err.source().unwrap().to_string());
+
+    // Regular accounts still receive tokens
+    router
+        .execute_contract(
+            creator,
+            minter_addr,
+            &ExecuteMsg::MintTo {
+                recipient: buyer.to_string(),
+            },
+            &[],
+        )
+        .unwrap();
+}
+
+#[test]
+fn public_opens_early_on_whitelist_sellout() {
+    let mut router = custom_mock_app();
+    let num_tokens = 10;
+    let (creator, buyer) = setup_accounts(&mut router, num_tokens);
+
+    let (factory_addr, sg721_code_id) = setup_factory_contract(&mut router, &creator);
+    let whitelist_addr = setup_whitelist_contract(&mut router, &creator);
+    let buyer2 = Addr::unchecked("buyer2");
+    router
+        .sudo(SudoMsg::Bank(BankSudo::Mint {
+            to_address: buyer2.to_string(),
+            amount: coins(INITIAL_BALANCE, NATIVE_DENOM),
+        }))
+        .unwrap();
+    router
+        .execute_contract(
+            creator.clone(),
+            whitelist_addr.clone(),
+            &WhitelistExecuteMsg::AddMembers(AddMembersMsg {
+                to_add: vec![buyer.to_string()],
+            }),
+            &[],
+        )
+        .unwrap();
+
+    let creation_fee = coins(CREATION_FEE_PER_TOKEN * num_tokens as u128, NATIVE_DENOM);
+    let mut msg = create_minter_msg(&creator, sg721_code_id, num_tokens);
+    msg.init_msg.mint_price = coin(MINT_PRICE, NATIVE_DENOM);
+    msg.init_msg.start_time = Timestamp::from_nanos(GENESIS_MINT_START_TIME + 1_000_000);
+    msg.init_msg.whitelist = Some(whitelist_addr.to_string());
+    msg.init_msg.whitelist_total_cap = Some(1);
+    msg.init_msg.public_on_whitelist_sellout = Some(true);
+    let res = router
+        .execute_contract(
+            creator,
+            factory_addr,
+            &Sg2ExecuteMsg::CreateMinter(msg),
+            &creation_fee,
+        )
+        .unwrap();
+    let minter_addr = minter_addr_from(&res);
+
+    // Public buyers wait for the start time while the whitelist is selling
+    setup_block_time(&mut router, GENESIS_MINT_START_TIME + 101, None);
+    let err = router
+        .execute_contract(
+            buyer2.clone(),
+            minter_addr.clone(),
+            &ExecuteMsg::Mint {
+                referral: None,
+                recipient: None,
+            },
+            &coins(MINT_PRICE, NATIVE_DENOM),
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::NotWhitelisted {
+            addr: buyer2.to_string()
+        }
+        .to_string(),
+        err.source().unwrap().to_string()
+    );
+
+    // The whitelist sells its allocation
+    let res = router
+        .execute_contract(
+            buyer,
+            minter_addr.clone(),
+            &ExecuteMsg::Mint {
+                referral: None,
+                recipient: None,
+            },
+            &coins(WHITELIST_AMOUNT, NATIVE_DENOM),
+        )
+        .unwrap();
+    assert!(res
+        .events
+        .iter()
+        .any(|e| e.ty == "wasm-early_public_opened"));
+
+    // Public mint opens before the scheduled start time, at the public price
+    let res = router.execute_contract(
+        buyer2,
+        minter_addr,
+        &ExecuteMsg::Mint {
+            referral: None,
+            recipient: None,
+        },
+        &coins(MINT_PRICE, NATIVE_DENOM),
+    );
+    assert!(res.is_ok());
+}
+
+#[test]
+fn unknown_reply_id() {
+    let mut deps = mock_dependencies_with_balance(&coins(2, "token"));
+
+    let msg = Reply {
+        id: 42,
+        result: SubMsgResult::Err("unknown".to_string()),
+    };
+    let err = reply(deps.as_mut(), mock_env(), msg).unwrap_err();
+    assert_eq!("Invalid reply ID: 42", err.to_string());
+}
+
+#[test]
+fn batch_mint_per_tx_cap() {
+    let mut router = custom_mock_app();
+    let num_tokens = 100;
+    let (creator, buyer) = setup_accounts(&mut router, num_tokens);
+    let (minter_addr, _) = setup_minter_contract(&mut router, &creator, num_tokens, None);
+
+    setup_block_time(&mut router, GENESIS_MINT_START_TIME + 1, None);
+    let err = router
+        .execute_contract(
+            buyer,
+            minter_addr,
+            &ExecuteMsg::BatchMint { num_mints: 51 },
+            &coins(MINT_PRICE, NATIVE_DENOM),
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::TooManyMintsPerTx { max: 50 }.to_string(),
+        err.source().unwrap().to_string()
+    );
+}
+
+#[test]
+fn admin_query() {
+    let mut router = custom_mock_app();
+    let num_tokens = 10;
+    let (creator, _) = setup_accounts(&mut router, num_tokens);
+    let (minter_addr, _) = setup_minter_contract(&mut router, &creator, num_tokens, None);
+
+    let res: AdminResponse = router
+        .wrap()
+        .query_wasm_smart(minter_addr, &QueryMsg::Admin {})
+        .unwrap();
+    assert_eq!(res.admin, creator.to_string());
+}
+
+#[test]
+fn whitelist_price_above_public_price() {
+    let mut router = custom_mock_app();
+    let num_tokens = 10;
+    let (creator, _) = setup_accounts(&mut router, num_tokens);
+
+    let (factory_addr, sg721_code_id) = setup_factory_contract(&mut router, &creator);
+    let whitelist_addr = setup_whitelist_contract(&mut router, &creator);
+
+    // The whitelist sells at WHITELIST_AMOUNT, above a public price of MIN_MINT_PRICE
+    let creation_fee = coins(CREATION_FEE_PER_TOKEN * num_tokens as u128, NATIVE_DENOM);
+    let mut msg = create_minter_msg(&creator, sg721_code_id, num_tokens);
+    msg.init_msg.mint_price = coin(MIN_MINT_PRICE, NATIVE_DENOM);
+    msg.init_msg.whitelist = Some(whitelist_addr.to_string());
+    let err = router
+        .execute_contract(
+            creator.clone(),
+            factory_addr.clone(),
+            &Sg2ExecuteMsg::CreateMinter(msg),
+            &creation_fee,
+        )
+        .unwrap_err();
+    let expected = ContractError::WhitelistPriceAbovePublicPrice {
+        whitelist_price: coin(WHITELIST_AMOUNT, NATIVE_DENOM),
+        public_price: coin(MIN_MINT_PRICE, NATIVE_DENOM),
+    };
+    assert_eq!(
+        expected.to_string(),
+        err.source().unwrap().source().unwrap().to_string()
+    );
+
+    // Same check when setting the whitelist later
+    let mut msg = create_minter_msg(&creator, sg721_code_id, num_tokens);
+    msg.init_msg.mint_price = coin(MIN_MINT_PRICE, NATIVE_DENOM);
+    let res = router
+        .execute_contract(
+            creator.clone(),
+            factory_addr.clone(),
+            &Sg2ExecuteMsg::CreateMinter(msg),
+            &creation_fee,
+        )
+        .unwrap();
+    let minter_addr = minter_addr_from(&res);
+    let err = router
+        .execute_contract(
+            creator.clone(),
+            minter_addr,
+            &ExecuteMsg::SetWhitelist {
+                whitelist: whitelist_addr.to_string(),
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(expected.to_string(), err.source().unwrap().to_string());
+
+    // And when lowering the public price below a whitelist that is already set
+    let mut msg = create_minter_msg(&creator, sg721_code_id, num_tokens);
+    msg.init_msg.mint_price = coin(WHITELIST_AMOUNT, NATIVE_DENOM);
+    msg.init_msg.whitelist = Some(whitelist_addr.to_string());
+    let res = router
+        .execute_contract(
+            creator.clone(),
+            factory_addr,
+            &Sg2ExecuteMsg::CreateMinter(msg),
+            &creation_fee,
+        )
+        .unwrap();
+    let minter_addr = minter_addr_from(&res);
+    setup_block_time(&mut router, GENESIS_MINT_START_TIME - 10, None);
+    let err = router
+        .execute_contract(
+            creator,
+            minter_addr,
+            &ExecuteMsg::UpdateMintPrice {
+                price: MIN_MINT_PRICE,
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(expected.to_string(), err.source().unwrap().to_string());
+}
+
+#[test]
+fn refund_after_missed_reveal() {
+    let mut router = custom_mock_app();
+    let num_tokens = 10;
+    let (creator, buyer) = setup_accounts(&mut router, num_tokens);
+    let (factory_addr, sg721_code_id) = setup_factory_contract(&mut router, &creator);
+
+    let start = Timestamp::from_nanos(GENESIS_MINT_START_TIME);
+    let reveal_deadline = start.plus_seconds(100);
+    let creation_fee = coins(CREATION_FEE_PER_TOKEN * num_tokens as u128, NATIVE_DENOM);
+    let mut msg = create_minter_msg(&creator, sg721_code_id, num_tokens);
+    msg.init_msg.reveal_deadline = Some(reveal_deadline);
+    let res = router
+        .execute_contract(
+            creator.clone(),
+            factory_addr,
+            &Sg2ExecuteMsg::CreateMinter(msg),
+            &creation_fee,
+        )
+        .unwrap();
+    let minter_addr = minter_addr_from(&res);
+    let config: ConfigResponse = router
+        .wrap()
+        .query_wasm_smart(minter_addr.clone(), &QueryMsg::Config {})
+        .unwrap();
+    let sg721_addr = Addr::unchecked(config.sg721_address);
+
+    setup_block_time(&mut router, start.plus_seconds(1).nanos(), None);
+    let res = router
+        .execute_contract(
+            buyer.clone(),
+            minter_addr.clone(),
+            &ExecuteMsg::Mint {
+                referral: None,
+                recipient: None,
+            },
+            &coins(MIN_MINT_PRICE, NATIVE_DENOM),
+        )
+        .unwrap();
+    let token_id = get_token_id(&res);
+
+    // Proceeds are held until the reveal
+    let err = router
+        .execute_contract(
+            creator.clone(),
+            minter_addr.clone(),
+            &ExecuteMsg::Withdraw {},
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::RevealPending {}.to_string(),
+        err.source().unwrap().to_string()
+    );
+
+    // No refunds before the deadline
+    let refund_msg = ExecuteMsg::ClaimRefund { token_id };
+    let err = router
+        .execute_contract(buyer.clone(), minter_addr.clone(), &refund_msg, &[])
+        .unwrap_err();
+    assert_eq!(
+        ContractError::RefundNotAvailable {}.to_string(),
+        err.source().unwrap().to_string()
+    );
+
+    setup_block_time(&mut router, reveal_deadline.plus_seconds(1).nanos(), None);
+    let err = router
+        .execute_contract(creator, minter_addr.clone(), &ExecuteMsg::Reveal {}, &[])
+        .unwrap_err();
+    assert_eq!(
+        ContractError::RevealDeadlinePassed {}.to_string(),
+        err.source().unwrap().to_string()
+    );
+
+    let approve_msg: Cw721ExecuteMsg<Empty, Empty> = Cw721ExecuteMsg::Approve {
+        spender: minter_addr.to_string(),
+        token_id: token_id.to_string(),
+        expires: None,
+    };
+    router
+        .execute_contract(buyer.clone(), sg721_addr.clone(), &approve_msg, &[])
+        .unwrap();
+    let balance = router
+        .wrap()
+        .query_balance(buyer.to_string(), NATIVE_DENOM)
+        .unwrap();
+    router
+        .execute_contract(buyer.clone(), minter_addr.clone(), &refund_msg, &[])
+        .unwrap();
+
+    // The buyer gets the proceeds back, the network fee is not refunded
+    let res = router
+        .wrap()
+        .query_balance(buyer.to_string(), NATIVE_DENOM)
+        .unwrap();
+    assert_eq!(
+        res.amount,
+        balance.amount + Uint128::from(MIN_MINT_PRICE * 9 / 10)
+    );
+    let res: StdResult<OwnerOfResponse> = router.wrap().query_wasm_smart(
+        sg721_addr,
+        &Cw721QueryMsg::OwnerOf {
+            token_id: token_id.to_string(),
+            include_expired: None,
+        },
+    );
+    assert!(res.is_err());
+
+    // A token is only refunded once
+    let err = router
+        .execute_contract(buyer, minter_addr, &refund_msg, &[])
+        .unwrap_err();
+    assert!(err.source().is_some());
+}
+
+#[test]
+fn reroll_moves_receipt_and_refund() {
+    let mut router = custom_mock_app();
+    let num_tokens = 10;
+    let (creator, buyer) = setup_accounts(&mut router, num_tokens);
+
+    let start = Timestamp::from_nanos(GENESIS_MINT_START_TIME);
+    let reveal_deadline = start.plus_seconds(100);
+    let reroll_fee = 5_000_000;
+    let (minter_addr, config) =
+        setup_minter_contract_with(&mut router, &creator, num_tokens, |msg| {
+            msg.init_msg.record_receipts = Some(true);
+            msg.init_msg.reveal_deadline = Some(reveal_deadline);
+            msg.init_msg.reroll_fee = Some(coin(reroll_fee, NATIVE_DENOM));
+        });
+    let sg721_addr = Addr::unchecked(config.sg721_address);
+
+    setup_block_time(&mut router, start.plus_seconds(1).nanos(), Some(10));
+    let res = router
+        .execute_contract(
+            buyer.clone(),
+            minter_addr.clone(),
+            &ExecuteMsg::Mint {
+                referral: None,
+                recipient: None,
+            },
+            &coins(MIN_MINT_PRICE, NATIVE_DENOM),
+        )
+        .unwrap();
+    let token_id = get_token_id(&res);
+
+    let approve_msg: Cw721ExecuteMsg<Empty, Empty> = Cw721ExecuteMsg::Approve {
+        spender: minter_addr.to_string(),
+        token_id: token_id.to_string(),
+        expires: None,
+    };
+    router
+        .execute_contract(buyer.clone(), sg721_addr.clone(), &approve_msg, &[])
+        .unwrap();
+    let res = router
+        .execute_contract(
+            buyer.clone(),
+            minter_addr.clone(),
+            &ExecuteMsg::Reroll { token_id },
+            &coins(reroll_fee, NATIVE_DENOM),
+        )
+        .unwrap();
+    let new_token_id = get_token_id(&res);
+
+    // The receipt follows the replacement token
+    let res: MintReceiptResponse = router
+        .wrap()
+        .query_wasm_smart(minter_addr.clone(), &QueryMsg::MintReceipt { token_id })
+        .unwrap();
+    assert_eq!(res.receipt, None);
+    let res: MintReceiptResponse = router
+        .wrap()
+        .query_wasm_smart(
+            minter_addr.clone(),
+            &QueryMsg::MintReceipt {
+                token_id: new_token_id,
+            },
+        )
+        .unwrap();
+    assert_eq!(
+        res.receipt,
+        Some(MintReceipt {
+            height: 10,
+            price: coin(MIN_MINT_PRICE, NATIVE_DENOM),
+        })
+    );
+
+    // And so does the refund once the reveal deadline is missed
+    setup_block_time(&mut router, reveal_deadline.plus_seconds(1).nanos(), None);
+    let approve_msg: Cw721ExecuteMsg<Empty, Empty> = Cw721ExecuteMsg::Approve {
+        spender: minter_addr.to_string(),
+        token_id: new_token_id.to_string(),
+        expires: None,
+    };
+    router
+        .execute_contract(buyer.clone(), sg721_addr, &approve_msg, &[])
+        .unwrap();
+    let balance = router
+        .wrap()
+        .query_balance(buyer.to_string(), NATIVE_DENOM)
+        .unwrap();
+    router
+        .execute_contract(
+            buyer.clone(),
+            minter_addr,
+            &ExecuteMsg::ClaimRefund {
+                token_id: new_token_id,
+            },
+            &[],
+        )
+        .unwrap();
+    let res = router
+        .wrap()
+        .query_balance(buyer.to_string(), NATIVE_DENOM)
+        .unwrap();
+    assert_eq!(
+        res.amount,
+        balance.amount + Uint128::from(MIN_MINT_PRICE * 9 / 10)
+    );
+}
+
+#[test]
+fn payout_totals_with_splits() {
+    let mut router = custom_mock_app();
+    let num_tokens = 10;
+    let (creator, buyer) = setup_accounts(&mut router, num_tokens);
+    let (factory_addr, sg721_code_id) = setup_factory_contract(&mut router, &creator);
+
+    let creation_fee = coins(CREATION_FEE_PER_TOKEN * num_tokens as u128, NATIVE_DENOM);
+    let mut msg = mock_create_minter(None);
+    msg.init_msg.num_tokens = num_tokens;
+    msg.init_msg.payout_splits = Some(vec![
+        PayoutSplitMsg {
+            address: "artist".to_string(),
+            bps: 7_000,
+        },
+        PayoutSplitMsg {
+            address: "developer".to_string(),
+            bps: 3_000,
+        },
+    ]);
+    msg.collection_params.code_id = sg721_code_id;
+    msg.collection_params.info.creator = creator.to_string();
+
+    // Shares must add up to 100% without overflowing, be non-zero and go to distinct addresses
+    let invalid_splits = [
+        vec![("artist", 7_000), ("developer", 2_000)],
+        vec![("artist", u64::MAX), ("developer", 10_001)],
+        vec![("artist", 10_000), ("developer", 0)],
+        vec![("artist", 7_000), ("artist", 3_000)],
+    ];
+    for splits in invalid_splits {
+        let mut invalid_msg = msg.clone();
+        invalid_msg.init_msg.payout_splits = Some(
+            splits
+                .into_iter()
+                .map(|(address, bps)| PayoutSplitMsg {
+                    address: address.to_string(),
+                    bps,
+                })
+                .collect(),
+        );
+        let err = router
+            .execute_contract(
+                creator.clone(),
+                factory_addr.clone(),
+                &Sg2ExecuteMsg::CreateMinter(invalid_msg),
+                &creation_fee,
+            )
+            .unwrap_err();
+        assert_eq!(
+            ContractError::InvalidPayoutSplits {}.to_string(),
+            err.source().unwrap().source().unwrap().to_string()
+        );
+    }
+
+    let res = router
+        .execute_contract(
+            creator,
+            factory_addr,
+            &Sg2ExecuteMsg::CreateMinter(msg),
+            &creation_fee,
+        )
+        .unwrap();
+    let minter_addr = minter_addr_from(&res);
+
+    setup_block_time(&mut router, GENESIS_MINT_START_TIME + 1, None);
+    for _ in 0..3 {
+        router
+            .execute_contract(
+                buyer.clone(),
+                minter_addr.clone(),
+                &ExecuteMsg::Mint {
+                    referral: None,
+                    recipient: None,
+                },
+                &coins(MIN_MINT_PRICE, NATIVE_DENOM),
+            )
+            .unwrap();
+    }
+
+    // 10% network fee, the rest is split 70/30
+    let proceeds = MIN_MINT_PRICE * 3 * 9 / 10;
+    let res: PayoutTotalsResponse = router
+        .wrap()
+        .query_wasm_smart(minter_addr, &QueryMsg::PayoutTotals {})
+        .unwrap();
+    assert_eq!(
+        res.totals,
+        vec![
+            PayoutTotal {
+                address: "artist".to_string(),
+                amount: coin(proceeds * 7 / 10, NATIVE_DENOM),
+            },
+            PayoutTotal {
+                address: "developer".to_string(),
+                amount: coin(proceeds * 3 / 10, NATIVE_DENOM),
+            },
+        ]
+    );
+    let balance = router.wrap().query_balance("artist", NATIVE_DENOM).unwrap();
+    assert_eq!(balance.amount.u128(), proceeds * 7 / 10);
+    let balance = router
+        .wrap()
+        .query_balance("developer", NATIVE_DENOM)
+        .unwrap();
+    assert_eq!(balance.amount.u128(), proceeds * 3 / 10);
+}
+
+#[test]
+fn set_token_royalty() {
+    let mut router = custom_mock_app();
+    let (creator, buyer) = setup_accounts(&mut router, 10);
+    let (minter_addr, config) = setup_minter_contract(&mut router, &creator, 10, None);
+
+    let set_royalty_msg = |token_id: u32, share: Decimal| ExecuteMsg::SetTokenRoyalty {
+        token_id,
+        share,
+        recipient: "artist".to_string(),
+    };
+    let err = router
+        .execute_contract(
+            buyer,
+            minter_addr.clone(),
+            &set_royalty_msg(1, Decimal::percent(5)),
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::Unauthorized("Sender is not an admin".to_string()).to_string(),
+        err.source().unwrap().to_string()
+    );
+
+    let invalid = [
+        (
+            1,
+            Decimal::percent(101),
+            ContractError::InvalidRoyaltyShare {},
+        ),
+        (
+            1,
+            Decimal::percent(15),
+            ContractError::RoyaltyTooHigh {
+                max: Decimal::from_ratio(MAX_ROYALTY_BPS, 10_000u128),
+                got: Decimal::percent(15),
+            },
+        ),
+        (0, Decimal::percent(5), ContractError::InvalidTokenId {}),
+        (11, Decimal::percent(5), ContractError::InvalidTokenId {}),
+    ];
+    for (token_id, share, expected) in invalid {
+        let err = router
+            .execute_contract(
+                creator.clone(),
+                minter_addr.clone(),
+                &set_royalty_msg(token_id, share),
+                &[],
+            )
+            .unwrap_err();
+        assert_eq!(expected.to_string(), err.source().unwrap().to_string());
+    }
+
+    router
+        .execute_contract(
+            creator,
+            minter_addr,
+            &set_royalty_msg(1, Decimal::percent(5)),
+            &[],
+        )
+        .unwrap();
+
+    // The override is held by the collection
+    let res: TokenRoyaltyResponse = router
+        .wrap()
+        .query_wasm_smart(
+            &config.sg721_address,
+            &Sg721QueryMsg::TokenRoyalty {
+                token_id: "1".to_string(),
+            },
+        )
+        .unwrap();
+    assert_eq!(
+        res.royalty_info,
+        Some(RoyaltyInfoResponse {
+            payment_address: "artist".to_string(),
+            share: Decimal::percent(5),
+        })
+    );
+
+    // Other tokens keep the collection royalty
+    let res: TokenRoyaltyResponse = router
+        .wrap()
+        .query_wasm_smart(
+            &config.sg721_address,
+            &Sg721QueryMsg::TokenRoyalty {
+                token_id: "2".to_string(),
+            },
+        )
+        .unwrap();
+    assert_eq!(res.royalty_info, mock_collection_params().info.royalty_info);
+}
+
+#[test]
+fn two_step_freeze() {
+    let mut router = custom_mock_app();
+    let (creator, buyer) = setup_accounts(&mut router, 10);
+    let (minter_addr, config) = setup_minter_contract(&mut router, &creator, 10, None);
+    let delay = config.freeze_delay_secs;
+
+    let err = router
+        .execute_contract(
+            creator.clone(),
+            minter_addr.clone(),
+            &ExecuteMsg::ConfirmFreeze {},
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::NoFreezeRequested {}.to_string(),
+        err.source().unwrap().to_string()
+    );
+
+    let err = router
+        .execute_contract(
+            buyer,
+            minter_addr.clone(),
+            &ExecuteMsg::RequestFreeze {},
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::Unauthorized("Sender is not an admin".to_string()).to_string(),
+        err.source().unwrap().to_string()
+    );
+
+    // A request can be cancelled before it is confirmed
+    setup_block_time(&mut router, GENESIS_MINT_START_TIME, None);
+    router
+        .execute_contract(
+            creator.clone(),
+            minter_addr.clone(),
+            &ExecuteMsg::RequestFreeze {},
+            &[],
+        )
+        .unwrap();
+    router
+        .execute_contract(
+            creator.clone(),
+            minter_addr.clone(),
+            &ExecuteMsg::CancelFreeze {},
+            &[],
+        )
+        .unwrap();
+    setup_block_time(
+        &mut router,
+        GENESIS_MINT_START_TIME + delay * 1_000_000_000,
+        None,
+    );
+    router
+        .execute_contract(
+            creator.clone(),
+            minter_addr.clone(),
+            &ExecuteMsg::ConfirmFreeze {},
+            &[],
+        )
+        .unwrap_err();
+
+    // Confirming before the delay fails
+    let requested_at = Timestamp::from_nanos(GENESIS_MINT_START_TIME).plus_seconds(delay);
+    router
+        .execute_contract(
+            creator.clone(),
+            minter_addr.clone(),
+            &ExecuteMsg::RequestFreeze {},
+            &[],
+        )
+        .unwrap();
+    setup_block_time(
+        &mut router,
+        requested_at.plus_seconds(delay - 1).nanos(),
+        None,
+    );
+    let err = router
+        .execute_contract(
+            creator.clone(),
+            minter_addr.clone(),
+            &ExecuteMsg::ConfirmFreeze {},
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::FreezeDelayNotElapsed {
+            confirm_time: requested_at.plus_seconds(delay)
+        }
+        .to_string(),
+        err.source().unwrap().to_string()
+    );
+
+    setup_block_time(&mut router, requested_at.plus_seconds(delay).nanos(), None);
+    router
+        .execute_contract(
+            creator.clone(),
+            minter_addr,
+            &ExecuteMsg::ConfirmFreeze {},
+            &[],
+        )
+        .unwrap();
+
+    // The collection info is frozen
+    let update_msg: Sg721ExecuteMsg<Empty, Empty> = Sg721ExecuteMsg::UpdateCollectionInfo {
+        collection_info: UpdateCollectionInfoMsg {
+            description: Some("new description".to_string()),
+            image: None,
+            external_link: None,
+            explicit_content: None,
+            royalty_info: None,
+        },
+    };
+    let err = router
+        .execute_contract(
+            creator,
+            Addr::unchecked(config.sg721_address),
+            &update_msg,
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        sg721_base::ContractError::CollectionInfoFrozen {}.to_string(),
+        err.source().unwrap().to_string()
+    );
+}
+
+#[test]
+fn zero_freeze_delay() {
+    let mut router = custom_mock_app();
+    let num_tokens = 10;
+    let (creator, _) = setup_accounts(&mut router, num_tokens);
+    let (factory_addr, sg721_code_id) = setup_factory_contract(&mut router, &creator);
+
+    let creation_fee = coins(CREATION_FEE_PER_TOKEN * num_tokens as u128, NATIVE_DENOM);
+    let mut msg = create_minter_msg(&creator, sg721_code_id, num_tokens);
+    msg.init_msg.freeze_delay_secs = Some(0);
+    let err = router
+        .execute_contract(
+            creator,
+            factory_addr,
+            &Sg2ExecuteMsg::CreateMinter(msg),
+            &creation_fee,
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::InvalidFreezeDelay {}.to_string(),
+        err.source().unwrap().source().unwrap().to_string()
+    );
+}
+
+#[test]
+fn factory_requires_explicit_content() {
+    let mut router = custom_mock_app();
+    let num_tokens = 10;
+    let (creator, _) = setup_accounts(&mut router, num_tokens);
+    let (factory_addr, sg721_code_id) = setup_factory_contract(&mut router, &creator);
+
+    let update_msg = serial_print_factory::msg::SudoMsg::UpdateParams(Box::new(
+        serial_print_factory::msg::VendingUpdateParamsMsg {
+            code_id: None,
+            creation_fee: None,
+            min_mint_price: None,
+            mint_fee_bps: None,
+            max_trading_offset_secs: None,
+            extension: serial_print_factory::msg::VendingUpdateParamsExtension {
+                max_per_address_limit: None,
+                airdrop_mint_price: None,
+                airdrop_mint_fee_bps: None,
+                require_explicit_content: Some(true),
+                max_start_offset_secs: None,
+                max_mint_price: None,
+                max_royalty_bps: None,
+                max_token_limit: None,
+            },
+        },
+    ));
+    router.wasm_sudo(factory_addr.clone(), &update_msg).unwrap();
+
+    let creation_fee = coins(CREATION_FEE_PER_TOKEN * num_tokens as u128, NATIVE_DENOM);
+    let mut msg = create_minter_msg(&creator, sg721_code_id, num_tokens);
+    msg.collection_params.info.explicit_content = None;
+    let err = router
+        .execute_contract(
+            creator.clone(),
+            factory_addr.clone(),
+            &Sg2ExecuteMsg::CreateMinter(msg.clone()),
+            &creation_fee,
+        )
+        .unwrap_err();
+    assert_eq!(
+        serial_print_factory::ContractError::ExplicitContentRequired {}.to_string(),
+        err.source().unwrap().to_string()
+    );
+
+    msg.collection_params.info.explicit_content = Some(false);
+    router
+        .execute_contract(
+            creator,
+            factory_addr,
+            &Sg2ExecuteMsg::CreateMinter(msg),
+            &creation_fee,
+        )
+        .unwrap();
+}
+
+#[test]
+fn mint_counts_query() {
+    let mut router = custom_mock_app();
+    let num_tokens = 10;
+    let (creator, buyer) = setup_accounts(&mut router, num_tokens);
+    let (minter_addr, _config) = setup_minter_contract(&mut router, &creator, num_tokens, None);
+
+    setup_block_time(&mut router, GENESIS_MINT_START_TIME + 1, None);
+    let mint_msg = ExecuteMsg::Mint {
+        referral: None,
+        recipient: None,
+    };
+    for sender in [&buyer, &buyer, &creator] {
+        router
+            .execute_contract(
+                sender.clone(),
+                minter_addr.clone(),
+                &mint_msg,
+                &coins(MINT_PRICE, NATIVE_DENOM),
+            )
+            .unwrap();
+    }
+
+    let res: Vec<MintCountResponse> = router
+        .wrap()
+        .query_wasm_smart(
+            minter_addr.clone(),
+            &QueryMsg::MintCounts {
+                addresses: vec![buyer.to_string(), creator.to_string(), "nobody".to_string()],
+            },
+        )
+        .unwrap();
+    let counts: Vec<(String, u32)> = res.into_iter().map(|r| (r.address, r.count)).collect();
+    assert_eq!(
+        counts,
+        vec![
+            (buyer.to_string(), 2),
+            (creator.to_string(), 1),
+            ("nobody".to_string(), 0),
+        ]
+    );
+
+    // At most 50 addresses per query
+    let res: StdResult<Vec<MintCountResponse>> = router.wrap().query_wasm_smart(
+        minter_addr,
+        &QueryMsg::MintCounts {
+            addresses: vec![buyer.to_string(); 51],
+        },
+    );
+    assert!(res.is_err());
+}
+
+#[test]
+fn base_token_uri_trailing_slash() {
+    let mut router = custom_mock_app();
+    let num_tokens = 10;
+    let (creator, buyer) = setup_accounts(&mut router, num_tokens);
+    let (minter_addr, config) =
+        setup_minter_contract_with(&mut router, &creator, num_tokens, |msg| {
+            msg.init_msg.base_token_uri = format!("{}/", COLLECTION1_URI);
+        });
+    let sg721_addr = Addr::unchecked(config.sg721_address);
+
+    let res: ConfigResponse = router
+        .wrap()
+        .query_wasm_smart(minter_addr.clone(), &QueryMsg::Config {})
+        .unwrap();
+    assert_eq!(res.base_token_uri, COLLECTION1_URI);
+
+    setup_block_time(&mut router, GENESIS_MINT_START_TIME + 1, None);
+    let res = router
+        .execute_contract(
+            buyer,
+            minter_addr,
+            &ExecuteMsg::Mint {
+                referral: None,
+                recipient: None,
+            },
+            &coins(MIN_MINT_PRICE, NATIVE_DENOM),
+        )
+        .unwrap();
+    let token_id = get_token_id(&res);
+
+    let res: NftInfoResponse<Option<String>> = router
+        .wrap()
+        .query_wasm_smart(
+            sg721_addr,
+            &Cw721QueryMsg::NftInfo {
+                token_id: token_id.to_string(),
+            },
+        )
+        .unwrap();
+    assert_eq!(
+        res.token_uri.unwrap(),
+        format!("{}/{}", COLLECTION1_URI, token_id)
+    );
+}
+
+#[test]
+fn token_uri_suffix() {
+    let mut router = custom_mock_app();
+    let num_tokens = 10;
+    let (creator, buyer) = setup_accounts(&mut router, num_tokens);
+    let (factory_addr, sg721_code_id) = setup_factory_contract(&mut router, &creator);
+
+    let creation_fee = coins(CREATION_FEE_PER_TOKEN * num_tokens as u128, NATIVE_DENOM);
+    let mut msg = create_minter_msg(&creator, sg721_code_id, num_tokens);
+    msg.init_msg.token_uri_suffix = Some("json".to_string());
+    let err = router
+        .execute_contract(
+            creator.clone(),
+            factory_addr.clone(),
+            &Sg2ExecuteMsg::CreateMinter(msg.clone()),
+            &creation_fee,
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::InvalidTokenUriSuffix {}.to_string(),
+        err.source().unwrap().source().unwrap().to_string()
+    );
+
+    msg.init_msg.token_uri_suffix = Some(".json".to_string());
+    let res = router
+        .execute_contract(
+            creator,
+            factory_addr,
+            &Sg2ExecuteMsg::CreateMinter(msg),
+            &creation_fee,
+        )
+        .unwrap();
+    let minter_addr = minter_addr_from(&res);
+    let config: ConfigResponse = router
+        .wrap()
+        .query_wasm_smart(minter_addr.clone(), &QueryMsg::Config {})
+        .unwrap();
+    let sg721_addr = Addr::unchecked(config.sg721_address);
+
+    setup_block_time(&mut router, GENESIS_MINT_START_TIME + 1, None);
+    let res = router
+        .execute_contract(
+            buyer,
+            minter_addr,
+            &ExecuteMsg::Mint {
+                referral: None,
+                recipient: None,
+            },
+            &coins(MIN_MINT_PRICE, NATIVE_DENOM),
+        )
+        .unwrap();
+    let token_id = get_token_id(&res);
+
+    let res: NftInfoResponse<Option<String>> = router
+        .wrap()
+        .query_wasm_smart(
+            sg721_addr,
+            &Cw721QueryMsg::NftInfo {
+                token_id: token_id.to_string(),
+            },
+        )
+        .unwrap();
+    assert_eq!(
+        res.token_uri.unwrap(),
+        format!("{}/{}.json", COLLECTION1_URI, token_id)
+    );
+}
+
+#[test]
+fn reset_limit_on_public() {
+    let mut router = custom_mock_app();
+    let num_tokens = 10;
+    let (creator, buyer) = setup_accounts(&mut router, num_tokens);
+
+    let (factory_addr, sg721_code_id) = setup_factory_contract(&mut router, &creator);
+    let whitelist_addr = setup_whitelist_contract(&mut router, &creator);
+    router
+        .execute_contract(
+            creator.clone(),
+            whitelist_addr.clone(),
+            &WhitelistExecuteMsg::AddMembers(AddMembersMsg {
+                to_add: vec![buyer.to_string()],
+            }),
+            &[],
+        )
+        .unwrap();
+
+    let creation_fee = coins(CREATION_FEE_PER_TOKEN * num_tokens as u128, NATIVE_DENOM);
+    let mut msg = create_minter_msg(&creator, sg721_code_id, num_tokens);
+    msg.init_msg.mint_price = coin(MINT_PRICE, NATIVE_DENOM);
+    msg.init_msg.per_address_limit = 2;
+    msg.init_msg.start_time = Timestamp::from_nanos(GENESIS_MINT_START_TIME + 10_000_000);
+    msg.init_msg.whitelist = Some(whitelist_addr.to_string());
+    msg.init_msg.reset_limit_on_public = Some(true);
+    let res = router
+        .execute_contract(
+            creator,
+            factory_addr,
+            &Sg2ExecuteMsg::CreateMinter(msg),
+            &creation_fee,
+        )
+        .unwrap();
+    let minter_addr = minter_addr_from(&res);
+    let mint_msg = ExecuteMsg::Mint {
+        referral: None,
+        recipient: None,
+    };
+
+    // The buyer uses up the whitelist limit
+    setup_block_time(&mut router, GENESIS_MINT_START_TIME + 101, None);
+    router
+        .execute_contract(
+            buyer.clone(),
+            minter_addr.clone(),
+            &mint_msg,
+            &coins(WHITELIST_AMOUNT, NATIVE_DENOM),
+        )
+        .unwrap();
+
+    // and gets the full public limit once the whitelist ends
+    setup_block_time(&mut router, GENESIS_MINT_START_TIME + 10_000_000, None);
+    for _ in 0..2 {
+        router
+            .execute_contract(
+                buyer.clone(),
+                minter_addr.clone(),
+                &mint_msg,
+                &coins(MINT_PRICE, NATIVE_DENOM),
+            )
+            .unwrap();
+    }
+    let err = router
+        .execute_contract(
+            buyer.clone(),
+            minter_addr.clone(),
+            &mint_msg,
+            &coins(MINT_PRICE, NATIVE_DENOM),
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::MaxPerAddressLimitExceeded {}.to_string(),
+        err.source().unwrap().to_string()
+    );
+
+    // The total mint count still includes the whitelist mint
+    let res: MintCountResponse = router
+        .wrap()
+        .query_wasm_smart(
+            minter_addr,
+            &QueryMsg::MintCount {
+                address: buyer.to_string(),
+            },
+        )
+        .unwrap();
+    assert_eq!(res.count, 3);
+}
+
+#[test]
+fn batch_mint_lists_token_ids() {
+    let mut router = custom_mock_app();
+    let num_tokens = 10;
+    let (creator, buyer) = setup_accounts(&mut router, num_tokens);
+    let (minter_addr, _) = setup_minter_contract(&mut router, &creator, num_tokens, None);
+
+    setup_block_time(&mut router, GENESIS_MINT_START_TIME + 1, None);
+    let res = router
+        .execute_contract(
+            buyer,
+            minter_addr,
+            &ExecuteMsg::BatchMint { num_mints: 3 },
+            &coins(MINT_PRICE * 3, NATIVE_DENOM),
+        )
+        .unwrap();
+
+    let wasm = res.events.iter().find(|e| e.ty == "wasm").unwrap();
+    let token_ids: Vec<String> = wasm
+        .attributes
+        .iter()
+        .filter(|attr| attr.key == "token_id")
+        .map(|attr| attr.value.clone())
+        .collect();
+    assert_eq!(token_ids.len(), 3);
+    let joined = token_ids.join(",");
+    assert!(wasm
+        .attributes
+        .iter()
+        .any(|attr| attr.key == "token_ids" && attr.value == joined));
+
+    let event = res
+        .events
+        .iter()
+        .find(|e| e.ty == "wasm-batch_mint")
+        .unwrap();
+    assert!(event
+        .attributes
+        .iter()
+        .any(|attr| attr.key == "token_ids" && attr.value == joined));
+}
+
+#[test]
+fn reopen_mint_after_sellout() {
+    let mut router = custom_mock_app();
+    let num_tokens = 2;
+    let (creator, buyer) = setup_accounts(&mut router, num_tokens);
+    let (minter_addr, config) = setup_minter_contract(&mut router, &creator, num_tokens, None);
+    let mint_msg = ExecuteMsg::Mint {
+        referral: None,
+        recipient: None,
+    };
+
+    setup_block_time(&mut router, GENESIS_MINT_START_TIME + 1, None);
+    for _ in 0..num_tokens {
+        router
+            .execute_contract(
+                buyer.clone(),
+                minter_addr.clone(),
+                &mint_msg,
+                &coins(MINT_PRICE, NATIVE_DENOM),
+            )
+            .unwrap();
+    }
+    let err = router
+        .execute_contract(
+            buyer.clone(),
+            minter_addr.clone(),
+            &mint_msg,
+            &coins(MINT_PRICE, NATIVE_DENOM),
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::SoldOut {}.to_string(),
+        err.source().unwrap().to_string()
+    );
+
+    let err = router
+        .execute_contract(
+            creator.clone(),
+            minter_addr.clone(),
+            &ExecuteMsg::ReopenMint {
+                additional_tokens: MAX_TOKEN_LIMIT,
+                new_base_token_uri: None,
+            },
+            &coins(
+                CREATION_FEE_PER_TOKEN * MAX_TOKEN_LIMIT as u128,
+                NATIVE_DENOM,
+            ),
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::MaxTokenLimitExceeded {
+            max: MAX_TOKEN_LIMIT
+        }
+        .to_string(),
+        err.source().unwrap().to_string()
+    );
+    // A new round counts the tokens already minted as well
+    let err = router
+        .execute_contract(
+            creator.clone(),
+            minter_addr.clone(),
+            &ExecuteMsg::SetTokenUri {
+                uri: COLLECTION2_URI.to_string(),
+                num_tokens: MAX_TOKEN_LIMIT,
+            },
+            &coins(
+                CREATION_FEE_PER_TOKEN * MAX_TOKEN_LIMIT as u128,
+                NATIVE_DENOM,
+            ),
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::MaxTokenLimitExceeded {
+            max: MAX_TOKEN_LIMIT
+        }
+        .to_string(),
+        err.source().unwrap().to_string()
+    );
+
+    router
+        .execute_contract(
+            creator,
+            minter_addr.clone(),
+            &ExecuteMsg::ReopenMint {
+                additional_tokens: 2,
+                new_base_token_uri: Some(COLLECTION2_URI.to_string()),
+            },
+            &coins(CREATION_FEE_PER_TOKEN * 2, NATIVE_DENOM),
+        )
+        .unwrap();
+
+    let res: MintableNumTokensResponse = router
+        .wrap()
+        .query_wasm_smart(minter_addr.clone(), &QueryMsg::MintableNumTokens {})
+        .unwrap();
+    assert_eq!(res.count, 2);
+
+    // The new ids follow the sold out ones
+    let mut token_ids = vec![];
+    for _ in 0..2 {
+        let res = router
+            .execute_contract(
+                buyer.clone(),
+                minter_addr.clone(),
+                &mint_msg,
+                &coins(MINT_PRICE, NATIVE_DENOM),
+            )
+            .unwrap();
+        token_ids.push(get_token_id(&res));
+    }
+    token_ids.sort_unstable();
+    assert_eq!(token_ids, vec![3, 4]);
+
+    let res: NftInfoResponse<Option<String>> = router
+        .wrap()
+        .query_wasm_smart(
+            config.sg721_address,
+            &Cw721QueryMsg::NftInfo {
+                token_id: "3".to_string(),
+            },
+        )
+        .unwrap();
+    assert_eq!(res.token_uri.unwrap(), format!("{}/3", COLLECTION2_URI));
+    let res: ConfigResponse = router
+        .wrap()
+        .query_wasm_smart(minter_addr, &QueryMsg::Config {})
+        .unwrap();
+    assert_eq!(res.num_tokens, 4);
+}
+
+#[test]
+fn instantiate_below_min_mint_price() {
+    let mut router = custom_mock_app();
+    let num_tokens = 10;
+    let (creator, _) = setup_accounts(&mut router, num_tokens);
+    let (factory_addr, sg721_code_id) = setup_factory_contract(&mut router, &creator);
+
+    let creation_fee = coins(CREATION_FEE_PER_TOKEN * num_tokens as u128, NATIVE_DENOM);
+    let mut msg = create_minter_msg(&creator, sg721_code_id, num_tokens);
+    msg.init_msg.min_mint_price = Some(coin(MINT_PRICE, NATIVE_DENOM));
+    let err = router
+        .execute_contract(
+            creator.clone(),
+            factory_addr.clone(),
+            &Sg2ExecuteMsg::CreateMinter(msg.clone()),
+            &creation_fee,
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::InsufficientMintPrice {
+            expected: MINT_PRICE,
+            got: MIN_MINT_PRICE
+        }
+        .to_string(),
+        err.source().unwrap().source().unwrap().to_string()
+    );
+
+    msg.init_msg.mint_price = coin(MINT_PRICE, NATIVE_DENOM);
+
+    // The floor applies to phase prices too
+    let mut phase_msg = msg.clone();
+    phase_msg.init_msg.phases = Some(vec![PhaseMsg {
+        start_time: phase_msg.init_msg.start_time.plus_seconds(100),
+        price: coin(MIN_MINT_PRICE, NATIVE_DENOM),
+        per_address_limit: 1,
+        allowlist: None,
+    }]);
+    let err = router
+        .execute_contract(
+            creator.clone(),
+            factory_addr.clone(),
+            &Sg2ExecuteMsg::CreateMinter(phase_msg),
+            &creation_fee,
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::InsufficientMintPrice {
+            expected: MINT_PRICE,
+            got: MIN_MINT_PRICE
+        }
+        .to_string(),
+        err.source().unwrap().source().unwrap().to_string()
+    );
+
+    let res = router
+        .execute_contract(
+            creator.clone(),
+            factory_addr,
+            &Sg2ExecuteMsg::CreateMinter(msg),
+            &creation_fee,
+        )
+        .unwrap();
+
+    // The floor also applies to price updates
+    let err = router
+        .execute_contract(
+            creator,
+            minter_addr_from(&res),
+            &ExecuteMsg::UpdateMintPrice {
+                price: MIN_MINT_PRICE,
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::InsufficientMintPrice {
+            expected: MINT_PRICE,
+            got: MIN_MINT_PRICE
+        }
+        .to_string(),
+        err.source().unwrap().to_string()
+    );
+}
+
+#[test]
+fn current_phase_query() {
+    let mut router = custom_mock_app();
+    let num_tokens = 10;
+    let (creator, _) = setup_accounts(&mut router, num_tokens);
+
+    let (factory_addr, sg721_code_id) = setup_factory_contract(&mut router, &creator);
+    let whitelist_addr = setup_whitelist_contract(&mut router, &creator);
+    let creation_fee = coins(CREATION_FEE_PER_TOKEN * num_tokens as u128, NATIVE_DENOM);
+
+    let start_time = Timestamp::from_nanos(GENESIS_MINT_START_TIME);
+    let mut msg = mock_create_minter(None);
+    msg.init_msg.mint_price = coin(MINT_PRICE, NATIVE_DENOM);
+    msg.init_msg.num_tokens = num_tokens;
+    msg.init_msg.phases = Some(vec![
+        PhaseMsg {
+            start_time: start_time.plus_seconds(100),
+            price: coin(60_000_000, NATIVE_DENOM),
+            per_address_limit: 1,
+            allowlist: Some(whitelist_addr.to_string()),
+        },
+        PhaseMsg {
+            start_time: start_time.plus_seconds(200),
+            price: coin(70_000_000, NATIVE_DENOM),
+            per_address_limit: 2,
+            allowlist: None,
+        },
+    ]);
+    msg.collection_params.code_id = sg721_code_id;
+    msg.collection_params.info.creator = creator.to_string();
+    let res = router
+        .execute_contract(
+            creator,
+            factory_addr,
+            &Sg2ExecuteMsg::CreateMinter(msg),
+            &creation_fee,
+        )
+        .unwrap();
+    let minter_addr = minter_addr_from(&res);
+
+    setup_block_time(&mut router, start_time.plus_seconds(100).nanos(), None);
+    let res: CurrentPhaseResponse = router
+        .wrap()
+        .query_wasm_smart(minter_addr.clone(), &QueryMsg::CurrentPhase {})
+        .unwrap();
+    assert_eq!(
+        res,
+        CurrentPhaseResponse {
+            requires_allowlist: true,
+            price: coin(60_000_000, NATIVE_DENOM),
+            per_address_limit: 1,
+        }
+    );
+
+    setup_block_time(&mut router, start_time.plus_seconds(200).nanos(), None);
+    let res: CurrentPhaseResponse = router
+        .wrap()
+        .query_wasm_smart(minter_addr, &QueryMsg::CurrentPhase {})
+        .unwrap();
+    assert_eq!(
+        res,
+        CurrentPhaseResponse {
+            requires_allowlist: false,
+            price: coin(70_000_000, NATIVE_DENOM),
+            per_address_limit: 2,
+        }
+    );
+}
+
+#[test]
+fn mint_price_display_metadata() {
+    let mut router = custom_mock_app();
+    let num_tokens = 10;
+    let (creator, _) = setup_accounts(&mut router, num_tokens);
+
+    // Defaults to the raw denom with 6 decimals
+    let (minter_addr, _) = setup_minter_contract(&mut router, &creator, num_tokens, None);
+    let res: MintPriceResponse = router
+        .wrap()
+        .query_wasm_smart(minter_addr, &QueryMsg::MintPrice {})
+        .unwrap();
+    assert_eq!(res.display_denom, NATIVE_DENOM);
+    assert_eq!(res.exponent, 6);
+
+    let (minter_addr, _) = setup_minter_contract_with(&mut router, &creator, num_tokens, |msg| {
+        msg.init_msg.display_denom = Some("STARS".to_string());
+        msg.init_msg.exponent = Some(6);
+    });
+
+    let res: MintPriceResponse = router
+        .wrap()
+        .query_wasm_smart(minter_addr, &QueryMsg::MintPrice {})
+        .unwrap();
+    assert_eq!(res.public_price, coin(MIN_MINT_PRICE, NATIVE_DENOM));
+    assert_eq!(res.display_denom, "STARS");
+    assert_eq!(res.exponent, 6);
+}
+
+#[test]
+fn self_referral_rejected() {
+    let mut router = custom_mock_app();
+    let num_tokens = 10;
+    let (creator, buyer) = setup_accounts(&mut router, num_tokens);
+
+    let (minter_addr, _) = setup_minter_contract_with(&mut router, &creator, num_tokens, |msg| {
+        msg.init_msg.mint_price = coin(MINT_PRICE, NATIVE_DENOM);
+        msg.init_msg.referral_bps = Some(500);
+        msg.init_msg.block_privileged_referrals = Some(true);
+    });
+
+    setup_block_time(&mut router, GENESIS_MINT_START_TIME + 1, None);
+
+    for referrer in [buyer.clone(), creator, minter_addr.clone()] {
+        let err = router
+            .execute_contract(
+                buyer.clone(),
+                minter_addr.clone(),
+                &ExecuteMsg::Mint {
+                    referral: Some(referrer.to_string()),
+                    recipient: None,
+                },
+                &coins(MINT_PRICE, NATIVE_DENOM),
+            )
+            .unwrap_err();
+        assert_eq!(
+            ContractError::InvalidReferral {
+                referral: referrer.to_string()
+            }
+            .to_string(),
+            err.source().unwrap().to_string()
+        );
+    }
+
+    let res: ReferralRewardsResponse = router
+        .wrap()
+        .query_wasm_smart(
+            minter_addr,
+            &QueryMsg::ReferralRewards {
+                address: buyer.to_string(),
+            },
+        )
+        .unwrap();
+    assert!(res.rewards.is_empty());
+}
+
+#[test]
+fn is_token_mintable() {
+    let mut router = custom_mock_app();
+    let num_tokens = 3;
+    let (creator, buyer) = setup_accounts(&mut router, num_tokens);
+    let (minter_addr, _) = setup_minter_contract(&mut router, &creator, num_tokens, None);
+
+    setup_block_time(&mut router, GENESIS_MINT_START_TIME + 1, None);
+
+    router
+        .execute_contract(
+            creator.clone(),
+            minter_addr.clone(),
+            &ExecuteMsg::MintFor {
+                token_id: 1,
+                recipient: buyer.to_string(),
+            },
+            &[],
+        )
+        .unwrap();
+
+    let res: IsTokenMintableResponse = router
+        .wrap()
+        .query_wasm_smart(
+            minter_addr.clone(),
+            &QueryMsg::IsTokenMintable { token_id: 1 },
+        )
+        .unwrap();
+    assert!(!res.mintable);
+    let res: IsTokenMintableResponse = router
+        .wrap()
+        .query_wasm_smart(
+            minter_addr.clone(),
+            &QueryMsg::IsTokenMintable { token_id: 2 },
+        )
+        .unwrap();
+    assert!(res.mintable);
+
+    // In a new round the next token id is mintable, ids of the last round aren't
+    router
+        .execute_contract(
+            creator,
+            minter_addr.clone(),
+            &ExecuteMsg::SetTokenUri {
+                uri: COLLECTION2_URI.to_string(),
+                num_tokens,
+            },
+            &coins(CREATION_FEE_PER_TOKEN * num_tokens as u128, NATIVE_DENOM),
+        )
+        .unwrap();
+    let res: NextTokenIdResponse = router
+        .wrap()
+        .query_wasm_smart(minter_addr.clone(), &QueryMsg::NextTokenId {})
+        .unwrap();
+    let next_token_id = res.token_id.unwrap();
+    assert_eq!(next_token_id, num_tokens + 1);
+    let res: IsTokenMintableResponse = router
+        .wrap()
+        .query_wasm_smart(
+            minter_addr.clone(),
+            &QueryMsg::IsTokenMintable {
+                token_id: next_token_id,
+            },
+        )
+        .unwrap();
+    assert!(res.mintable);
+    let res: IsTokenMintableResponse = router
+        .wrap()
+        .query_wasm_smart(minter_addr, &QueryMsg::IsTokenMintable { token_id: 1 })
+        .unwrap();
+    assert!(!res.mintable);
+}
+
+#[test]
+fn fully_frozen_minter_rejects_mints_and_withdraws() {
+    let mut router = custom_mock_app();
+    let num_tokens = 3;
+    let (creator, buyer) = setup_accounts(&mut router, num_tokens);
+    let (minter_addr, _) = setup_minter_contract(&mut router, &creator, num_tokens, None);
+
+    setup_block_time(&mut router, GENESIS_MINT_START_TIME + 1, None);
+
+    router
+        .wasm_sudo(
+            minter_addr.clone(),
+            &MinterSudoMsg::UpdateFullyFrozen { fully_frozen: true },
+        )
+        .unwrap();
+
+    let err = router
+        .execute_contract(
+            buyer.clone(),
+            minter_addr.clone(),
+            &ExecuteMsg::Mint {
+                referral: None,
+                recipient: None,
+            },
+            &coins(MINT_PRICE, NATIVE_DENOM),
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::FullyFrozen {}.to_string(),
+        err.source().unwrap().to_string()
+    );
+
+    // Even the admin can't mint or withdraw
+    let err = router
+        .execute_contract(
+            creator.clone(),
+            minter_addr.clone(),
+            &ExecuteMsg::MintTo {
+                recipient: buyer.to_string(),
+            },
+            &coins_for_msg(coin(ADMIN_MINT_PRICE, NATIVE_DENOM)),
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::FullyFrozen {}.to_string(),
+        err.source().unwrap().to_string()
+    );
+    let err = router
+        .execute_contract(
+            creator.clone(),
+            minter_addr.clone(),
+            &ExecuteMsg::Withdraw {},
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::FullyFrozen {}.to_string(),
+        err.source().unwrap().to_string()
+    );
+
+    router
+        .wasm_sudo(
+            minter_addr.clone(),
+            &MinterSudoMsg::UpdateFullyFrozen {
+                fully_frozen: false,
+            },
+        )
+        .unwrap();
+    let res = router.execute_contract(
+        creator,
+        minter_addr,
+        &ExecuteMsg::MintTo {
+            recipient: buyer.to_string(),
+        },
+        &coins_for_msg(coin(ADMIN_MINT_PRICE, NATIVE_DENOM)),
+    );
+    assert!(res.is_ok());
+}
+
+#[test]
+fn create_minter_refunds_excess_creation_fee() {
+    let mut router = custom_mock_app();
+    let num_tokens = 10;
+    let (creator, _) = setup_accounts(&mut router, num_tokens);
+
+    let (factory_addr, sg721_code_id) = setup_factory_contract(&mut router, &creator);
+    let creation_fee = CREATION_FEE_PER_TOKEN * num_tokens as u128;
+
+    let msg = create_minter_msg(&creator, sg721_code_id, num_tokens);
+
+    let creator_balance = router
+        .wrap()
+        .query_balance(creator.clone(), NATIVE_DENOM)
+        .unwrap()
+        .amount
+        .u128();
+    router
+        .execute_contract(
+            creator.clone(),
+            factory_addr.clone(),
+            &Sg2ExecuteMsg::CreateMinter(msg),
+            &coins(creation_fee + 1_000_000, NATIVE_DENOM),
+        )
+        .unwrap();
+
+    let balance = router
+        .wrap()
+        .query_balance(creator, NATIVE_DENOM)
+        .unwrap()
+        .amount
+        .u128();
+    assert_eq!(balance, creator_balance - creation_fee);
+    let factory_balance = router.wrap().query_all_balances(factory_addr).unwrap();
+    assert!(factory_balance.is_empty());
+}
+
+#[test]
+fn contract_version_query() {
+    let mut router = custom_mock_app();
+    let num_tokens = 2;
+    let (creator, _) = setup_accounts(&mut router, num_tokens);
+    let (minter_addr, _) = setup_minter_contract(&mut router, &creator, num_tokens, None);
+
+    let res: cw2::ContractVersion = router
+        .wrap()
+        .query_wasm_smart(minter_addr, &QueryMsg::ContractVersion {})
+        .unwrap();
+    assert_eq!(res.contract, "crates.io:sg-minter");
+    assert_eq!(res.version, env!("CARGO_PKG_VERSION"));
+}
+
+#[test]
+fn early_per_address_limit() {
+    let mut router = custom_mock_app();
+    let num_tokens = 10;
+    let (creator, buyer) = setup_accounts(&mut router, num_tokens);
+
+    let (minter_addr, _) = setup_minter_contract_with(&mut router, &creator, num_tokens, |msg| {
+        msg.init_msg.mint_price = coin(MINT_PRICE, NATIVE_DENOM);
+        msg.init_msg.per_address_limit = 1;
+        msg.init_msg.early_per_address_limit = Some(3);
+        msg.init_msg.early_window_secs = Some(3600);
+    });
+    let mint_msg = ExecuteMsg::Mint {
+        referral: None,
+        recipient: None,
+    };
+
+    // Two mints during the early window, above the normal limit of 1
+    setup_block_time(&mut router, GENESIS_MINT_START_TIME + 1, None);
+    for _ in 0..2 {
+        router
+            .execute_contract(
+                buyer.clone(),
+                minter_addr.clone(),
+                &mint_msg,
+                &coins(MINT_PRICE, NATIVE_DENOM),
+            )
+            .unwrap();
+    }
+    let res: EffectivePerAddressLimitResponse = router
+        .wrap()
+        .query_wasm_smart(minter_addr.clone(), &QueryMsg::EffectivePerAddressLimit {})
+        .unwrap();
+    assert_eq!(res.per_address_limit, 3);
+
+    // Capped at the normal limit afterwards
+    setup_block_time(
+        &mut router,
+        GENESIS_MINT_START_TIME + 3_600_000_000_000,
+        None,
+    );
+    let err = router
+        .execute_contract(
+            buyer,
+            minter_addr.clone(),
+            &mint_msg,
+            &coins(MINT_PRICE, NATIVE_DENOM),
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::MaxPerAddressLimitExceeded {}.to_string(),
+        err.source().unwrap().to_string()
+    );
+    let res: EffectivePerAddressLimitResponse = router
+        .wrap()
+        .query_wasm_smart(minter_addr, &QueryMsg::EffectivePerAddressLimit {})
+        .unwrap();
+    assert_eq!(res.per_address_limit, 1);
+}
+
+#[test]
+fn early_per_address_limit_validation() {
+    let mut router = custom_mock_app();
+    let num_tokens = 10;
+    let (creator, _) = setup_accounts(&mut router, num_tokens);
+    let (factory_addr, sg721_code_id) = setup_factory_contract(&mut router, &creator);
+    let creation_fee = coins(CREATION_FEE_PER_TOKEN * num_tokens as u128, NATIVE_DENOM);
+
+    // Either field alone is rejected
+    let mut msg = create_minter_msg(&creator, sg721_code_id, num_tokens);
+    msg.init_msg.early_per_address_limit = Some(5);
+    let err = router
+        .execute_contract(
+            creator.clone(),
+            factory_addr.clone(),
+            &Sg2ExecuteMsg::CreateMinter(msg),
+            &creation_fee,
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::InvalidEarlyWindow {}.to_string(),
+        err.source().unwrap().source().unwrap().to_string()
+    );
+
+    let mut msg = create_minter_msg(&creator, sg721_code_id, num_tokens);
+    msg.init_msg.early_window_secs = Some(3600);
+    let err = router
+        .execute_contract(
+            creator.clone(),
+            factory_addr.clone(),
+            &Sg2ExecuteMsg::CreateMinter(msg),
+            &creation_fee,
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::InvalidEarlyWindow {}.to_string(),
+        err.source().unwrap().source().unwrap().to_string()
+    );
+
+    // The early limit can't be below the normal limit
+    let mut msg = create_minter_msg(&creator, sg721_code_id, num_tokens);
+    msg.init_msg.per_address_limit = 3;
+    msg.init_msg.early_per_address_limit = Some(2);
+    msg.init_msg.early_window_secs = Some(3600);
+    let err = router
+        .execute_contract(
+            creator,
+            factory_addr,
+            &Sg2ExecuteMsg::CreateMinter(msg),
+            &creation_fee,
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::InvalidPerAddressLimit {
+            max: MAX_PER_ADDRESS_LIMIT,
+            min: 3,
+            got: 2,
+        }
+        .to_string(),
+        err.source().unwrap().source().unwrap().to_string()
+    );
+}
+
+#[test]
+fn start_time_too_far_out() {
+    let mut router = custom_mock_app();
+    let num_tokens = 2;
+    let (creator, _) = setup_accounts(&mut router, num_tokens);
+    let (factory_addr, sg721_code_id) = setup_factory_contract(&mut router, &creator);
+    let creation_fee = coins(CREATION_FEE_PER_TOKEN * num_tokens as u128, NATIVE_DENOM);
+    setup_block_time(&mut router, GENESIS_MINT_START_TIME - 1000, None);
+
+    let ten_years = 10 * 365 * 24 * 60 * 60;
+    let far_start_time = Timestamp::from_nanos(GENESIS_MINT_START_TIME).plus_seconds(ten_years);
+    let max_start_time =
+        Timestamp::from_nanos(GENESIS_MINT_START_TIME - 1000).plus_seconds(MAX_START_OFFSET_SECS);
+
+    let mut msg = create_minter_msg(&creator, sg721_code_id, num_tokens);
+    msg.init_msg.start_time = far_start_time;
+    let err = router
+        .execute_contract(
+            creator.clone(),
+            factory_addr.clone(),
+            &Sg2ExecuteMsg::CreateMinter(msg.clone()),
+            &creation_fee,
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::StartTimeTooFar {
+            max: max_start_time,
+            got: far_start_time
+        }
+        .to_string(),
+        err.source().unwrap().source().unwrap().to_string()
+    );
+
+    msg.init_msg.start_time = Timestamp::from_nanos(GENESIS_MINT_START_TIME);
+    let res = router
+        .execute_contract(
+            creator.clone(),
+            factory_addr,
+            &Sg2ExecuteMsg::CreateMinter(msg),
+            &creation_fee,
+        )
+        .unwrap();
+    let minter_addr = minter_addr_from(&res);
+
+    let err = router
+        .execute_contract(
+            creator,
+            minter_addr,
+            &ExecuteMsg::UpdateStartTime(far_start_time),
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::StartTimeTooFar {
+            max: max_start_time,
+            got: far_start_time
+        }
+        .to_string(),
+        err.source().unwrap().to_string()
+    );
+}
+
+#[test]
+fn supply_consistency() {
+    let mut router = custom_mock_app();
+    let num_tokens = 5;
+    let (creator, buyer) = setup_accounts(&mut router, num_tokens);
+    let (minter_addr, _) = setup_minter_contract(&mut router, &creator, num_tokens, None);
+
+    setup_block_time(&mut router, GENESIS_MINT_START_TIME + 1, None);
+    for _ in 0..3 {
+        router
+            .execute_contract(
+                buyer.clone(),
+                minter_addr.clone(),
+                &ExecuteMsg::Mint {
+                    referral: None,
+                    recipient: None,
+                },
+                &coins(MINT_PRICE, NATIVE_DENOM),
+            )
+            .unwrap();
+    }
+
+    let res: SupplyConsistencyResponse = router
+        .wrap()
+        .query_wasm_smart(minter_addr, &QueryMsg::SupplyConsistency {})
+        .unwrap();
+    assert_eq!(res.mintable_num_tokens, 2);
+    assert_eq!(res.mintable_token_ids, 2);
+}
+
+#[test]
+fn freeze_token_uri() {
+    let mut router = custom_mock_app();
+    let num_tokens = 3;
+    let (creator, buyer) = setup_accounts(&mut router, num_tokens);
+    let (minter_addr, config) = setup_minter_contract(&mut router, &creator, num_tokens, None);
+
+    // Only the admin can freeze
+    let freeze_msg = ExecuteMsg::FreezeTokenUri { token_id: 2 };
+    let err = router
+        .execute_contract(buyer.clone(), minter_addr.clone(), &freeze_msg, &[])
+        .unwrap_err();
+    assert_eq!(
+        ContractError::Unauthorized("Sender is not an admin".to_owned()).to_string(),
+        err.source().unwrap().to_string()
+    );
+    router
+        .execute_contract(creator.clone(), minter_addr.clone(), &freeze_msg, &[])
+        .unwrap();
+
+    // Repoint the base uri of the remaining tokens
+    router
+        .execute_contract(
+            creator.clone(),
+            minter_addr.clone(),
+            &ExecuteMsg::ReopenMint {
+                additional_tokens: 1,
+                new_base_token_uri: Some(COLLECTION2_URI.to_string()),
+            },
+            &coins(CREATION_FEE_PER_TOKEN, NATIVE_DENOM),
+        )
+        .unwrap();
+
+    setup_block_time(&mut router, GENESIS_MINT_START_TIME + 1, None);
+    for token_id in [1, 2] {
+        router
+            .execute_contract(
+                creator.clone(),
+                minter_addr.clone(),
+                &ExecuteMsg::MintFor {
+                    token_id,
+                    recipient: buyer.to_string(),
+                },
+                &coins_for_msg(coin(ADMIN_MINT_PRICE, NATIVE_DENOM)),
+            )
+            .unwrap();
+    }
+
+    for (token_id, base_token_uri) in [("1", COLLECTION2_URI), ("2", COLLECTION1_URI)] {
+        let res: NftInfoResponse<Option<String>> = router
+            .wrap()
+            .query_wasm_smart(
+                config.sg721_address.clone(),
+                &Cw721QueryMsg::NftInfo {
+                    token_id: token_id.to_string(),
+                },
+            )
+            .unwrap();
+        assert_eq!(
+            res.token_uri.unwrap(),
+            format!("{}/{}", base_token_uri, token_id)
+        );
+    }
+}
+
+#[test]
+fn mint_without_queryable_collection() {
+    // Per-address limits are tracked in the minter, a collection that can't be queried doesn't block mints
+    fn failing_query(
+        _deps: cosmwasm_std::Deps,
+        _env: cosmwasm_std::Env,
+        _msg: Sg721QueryMsg,
+    ) -> StdResult<cosmwasm_std::Binary> {
+        Err(cosmwasm_std::StdError::generic_err(
+            "collection unavailable",
+        ))
+    }
+
+    let mut router = custom_mock_app();
+    let num_tokens = 3;
+    let (creator, buyer) = setup_accounts(&mut router, num_tokens);
+    let (factory_addr, _) = setup_factory_contract(&mut router, &creator);
+    let sg721_code_id = router.store_code(Box::new(ContractWrapper::new(
+        sg721_base::entry::execute,
+        sg721_base::entry::instantiate,
+        failing_query,
+    )));
+    let creation_fee = coins(CREATION_FEE_PER_TOKEN * num_tokens as u128, NATIVE_DENOM);
+
+    let mut msg = create_minter_msg(&creator, sg721_code_id, num_tokens);
+    msg.init_msg.mint_price = coin(MINT_PRICE, NATIVE_DENOM);
+    msg.init_msg.per_address_limit = 2;
+    let res = router
+        .execute_contract(
+            creator,
+            factory_addr,
+            &Sg2ExecuteMsg::CreateMinter(msg),
+            &creation_fee,
+        )
+        .unwrap();
+    let minter_addr = minter_addr_from(&res);
+
+    setup_block_time(&mut router, GENESIS_MINT_START_TIME + 1, None);
+    let mint_msg = ExecuteMsg::Mint {
+        referral: None,
+        recipient: None,
+    };
+    for _ in 0..2 {
+        router
+            .execute_contract(
+                buyer.clone(),
+                minter_addr.clone(),
+                &mint_msg,
+                &coins(MINT_PRICE, NATIVE_DENOM),
+            )
+            .unwrap();
+    }
+    let err = router
+        .execute_contract(
+            buyer,
+            minter_addr,
+            &mint_msg,
+            &coins(MINT_PRICE, NATIVE_DENOM),
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::MaxPerAddressLimitExceeded {}.to_string(),
+        err.source().unwrap().to_string()
+    );
+}
+
+#[test]
+fn airdrop_in_chunks() {
+    let mut router = custom_mock_app();
+    let num_tokens = 25;
+    let (creator, buyer) = setup_accounts(&mut router, num_tokens);
+    let (minter_addr, config) = setup_minter_contract(&mut router, &creator, num_tokens, None);
+    setup_block_time(&mut router, GENESIS_MINT_START_TIME + 1, None);
+
+    let recipients = (0..20)
+        .map(|i| format!("recipient{}", i))
+        .collect::<Vec<_>>();
+
+    // Loads are capped
+    let err = router
+        .execute_contract(
+            creator.clone(),
+            minter_addr.clone(),
+            &ExecuteMsg::LoadAirdrop {
+                recipients: vec!["recipient".to_string(); 501],
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::TooManyAirdropRecipients { max: 500 }.to_string(),
+        err.source().unwrap().to_string()
+    );
+
+    // Only the admin can load an airdrop
+    let load_msg = ExecuteMsg::LoadAirdrop {
+        recipients: recipients.clone(),
+    };
+    let err = router
+        .execute_contract(buyer, minter_addr.clone(), &load_msg, &[])
+        .unwrap_err();
+    assert_eq!(
+        ContractError::Unauthorized("Sender is not an admin".to_owned()).to_string(),
+        err.source().unwrap().to_string()
+    );
+    router
+        .execute_contract(creator.clone(), minter_addr.clone(), &load_msg, &[])
+        .unwrap();
+
+    // Can't replace an unfinished airdrop
+    let err = router
+        .execute_contract(creator.clone(), minter_addr.clone(), &load_msg, &[])
+        .unwrap_err();
+    assert_eq!(
+        ContractError::AirdropInProgress { remaining: 20 }.to_string(),
+        err.source().unwrap().to_string()
+    );
+
+    // The last chunk only covers the 4 recipients left
+    let continue_msg = ExecuteMsg::ContinueAirdrop { count: 8 };
+    for num_mints in [8, 8, 4] {
+        router
+            .execute_contract(
+                creator.clone(),
+                minter_addr.clone(),
+                &continue_msg,
+                &coins_for_msg(coin(ADMIN_MINT_PRICE * num_mints, NATIVE_DENOM)),
+            )
+            .unwrap();
+    }
+
+    let err = router
+        .execute_contract(creator, minter_addr.clone(), &continue_msg, &[])
+        .unwrap_err();
+    assert_eq!(
+        ContractError::NoAirdropInProgress {}.to_string(),
+        err.source().unwrap().to_string()
+    );
+
+    let res: MintableNumTokensResponse = router
+        .wrap()
+        .query_wasm_smart(minter_addr, &QueryMsg::MintableNumTokens {})
+        .unwrap();
+    assert_eq!(res.count, num_tokens - 20);
+    for recipient in recipients {
+        let res: TokensResponse = router
+            .wrap()
+            .query_wasm_smart(
+                config.sg721_address.clone(),
+                &Cw721QueryMsg::Tokens {
+                    owner: recipient,
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap();
+        assert_eq!(res.tokens.len(), 1);
+    }
+}
+
+#[test]
+fn base_token_uri_frozen_with_collection_info() {
+    let mut router = custom_mock_app();
+    let num_tokens = 3;
+    let (creator, _) = setup_accounts(&mut router, num_tokens);
+    let (minter_addr, config) = setup_minter_contract(&mut router, &creator, num_tokens, None);
+
+    setup_block_time(&mut router, GENESIS_MINT_START_TIME, None);
+    router
+        .execute_contract(
+            creator.clone(),
+            minter_addr.clone(),
+            &ExecuteMsg::RequestFreeze {},
+            &[],
+        )
+        .unwrap();
+    setup_block_time(
+        &mut router,
+        GENESIS_MINT_START_TIME + config.freeze_delay_secs * 1_000_000_000,
+        None,
+    );
+    router
+        .execute_contract(
+            creator.clone(),
+            minter_addr.clone(),
+            &ExecuteMsg::ConfirmFreeze {},
+            &[],
+        )
+        .unwrap();
+
+    let err = router
+        .execute_contract(
+            creator.clone(),
+            minter_addr.clone(),
+            &ExecuteMsg::SetTokenUri {
+                uri: COLLECTION2_URI.to_string(),
+                num_tokens: 1,
+            },
+            &coins(CREATION_FEE_PER_TOKEN, NATIVE_DENOM),
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::CollectionInfoFrozen {}.to_string(),
+        err.source().unwrap().to_string()
+    );
+    let err = router
+        .execute_contract(
+            creator.clone(),
+            minter_addr.clone(),
+            &ExecuteMsg::ReopenMint {
+                additional_tokens: 1,
+                new_base_token_uri: Some(COLLECTION2_URI.to_string()),
+            },
+            &coins(CREATION_FEE_PER_TOKEN, NATIVE_DENOM),
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::CollectionInfoFrozen {}.to_string(),
+        err.source().unwrap().to_string()
+    );
+
+    // Adding ids under the same base token uri is still possible
+    router
+        .execute_contract(
+            creator,
+            minter_addr,
+            &ExecuteMsg::ReopenMint {
+                additional_tokens: 1,
+                new_base_token_uri: None,
+            },
+            &coins(CREATION_FEE_PER_TOKEN, NATIVE_DENOM),
+        )
+        .unwrap();
+}
+
+#[test]
+fn network_fees_query() {
+    let mut router = custom_mock_app();
+    let num_tokens = 5;
+    let (creator, buyer) = setup_accounts(&mut router, num_tokens);
+    let (minter_addr, _) = setup_minter_contract(&mut router, &creator, num_tokens, None);
+
+    setup_block_time(&mut router, GENESIS_MINT_START_TIME + 1, None);
+    for _ in 0..3 {
+        router
+            .execute_contract(
+                buyer.clone(),
+                minter_addr.clone(),
+                &ExecuteMsg::Mint {
+                    referral: None,
+                    recipient: None,
+                },
+                &coins(MINT_PRICE, NATIVE_DENOM),
+            )
+            .unwrap();
+    }
+
+    let res: NetworkFeesResponse = router
+        .wrap()
+        .query_wasm_smart(minter_addr, &QueryMsg::NetworkFees {})
+        .unwrap();
+    assert_eq!(res.fees, coins(3 * MINT_FEE, NATIVE_DENOM));
+}
+
+#[test]
+fn start_time_update_cooldown() {
+    let mut router = custom_mock_app();
+    let num_tokens = 2;
+    let (creator, _) = setup_accounts(&mut router, num_tokens);
+    let (minter_addr, _) = setup_minter_contract_with(&mut router, &creator, num_tokens, |msg| {
+        msg.init_msg.start_time_update_cooldown_secs = Some(600);
+    });
+
+    let now = GENESIS_MINT_START_TIME - 10_000_000_000_000;
+    setup_block_time(&mut router, now, None);
+    let start_time = Timestamp::from_nanos(GENESIS_MINT_START_TIME);
+    router
+        .execute_contract(
+            creator.clone(),
+            minter_addr.clone(),
+            &ExecuteMsg::UpdateStartTime(start_time.plus_seconds(1000)),
+            &[],
+        )
+        .unwrap();
+
+    // A second update within the cooldown fails
+    setup_block_time(&mut router, now + 599_000_000_000, None);
+    let err = router
+        .execute_contract(
+            creator.clone(),
+            minter_addr.clone(),
+            &ExecuteMsg::UpdateStartTime(start_time.plus_seconds(2000)),
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::StartTimeUpdateCooldown {
+            next_update_time: Timestamp::from_nanos(now).plus_seconds(600)
+        }
+        .to_string(),
+        err.source().unwrap().to_string()
+    );
+
+    setup_block_time(&mut router, now + 600_000_000_000, None);
+    router
+        .execute_contract(
+            creator,
+            minter_addr.clone(),
+            &ExecuteMsg::UpdateStartTime(start_time.plus_seconds(2000)),
+            &[],
+        )
+        .unwrap();
+    let res: ConfigResponse = router
+        .wrap()
+        .query_wasm_smart(minter_addr, &QueryMsg::Config {})
+        .unwrap();
+    assert_eq!(res.start_time, start_time.plus_seconds(2000));
+}
+
+#[test]
+fn public_sale_opened_event() {
+    let mut router = custom_mock_app();
+    let num_tokens = 10;
+    let (creator, buyer) = setup_accounts(&mut router, num_tokens);
+    let (factory_addr, sg721_code_id) = setup_factory_contract(&mut router, &creator);
+    let whitelist_addr = setup_whitelist_contract(&mut router, &creator);
+    router
+        .execute_contract(
+            creator.clone(),
+            whitelist_addr.clone(),
+            &WhitelistExecuteMsg::AddMembers(AddMembersMsg {
+                to_add: vec![buyer.to_string()],
+            }),
+            &[],
+        )
+        .unwrap();
+    let creation_fee = coins(CREATION_FEE_PER_TOKEN * num_tokens as u128, NATIVE_DENOM);
+
+    let mut msg = create_minter_msg(&creator, sg721_code_id, num_tokens);
+    msg.init_msg.mint_price = coin(MINT_PRICE, NATIVE_DENOM);
+    msg.init_msg.whitelist = Some(whitelist_addr.to_string());
+    let res = router
+        .execute_contract(
+            creator,
+            factory_addr,
+            &Sg2ExecuteMsg::CreateMinter(msg),
+            &creation_fee,
+        )
+        .unwrap();
+    let minter_addr = minter_addr_from(&res);
+    let mint_msg = ExecuteMsg::Mint {
+        referral: None,
+        recipient: None,
+    };
+    let opened = |res: &AppResponse| {
+        res.events
+            .iter()
+            .filter(|event| event.ty == "wasm-public_sale_opened")
+            .count()
+    };
+
+    // Whitelist mints don't open the public sale
+    setup_block_time(&mut router, GENESIS_MINT_START_TIME + 101, None);
+    let res = router
+        .execute_contract(
+            buyer.clone(),
+            minter_addr.clone(),
+            &mint_msg,
+            &coins(WHITELIST_AMOUNT, NATIVE_DENOM),
+        )
+        .unwrap();
+    assert_eq!(opened(&res), 0);
+
+    // Only the first public mint after the whitelist ended emits the event
+    setup_block_time(&mut router, GENESIS_MINT_START_TIME + 20_000_000, None);
+    for expected in [1, 0] {
+        let res = router
+            .execute_contract(
+                buyer.clone(),
+                minter_addr.clone(),
+                &mint_msg,
+                &coins(MINT_PRICE, NATIVE_DENOM),
+            )
+            .unwrap();
+        assert_eq!(opened(&res), expected);
+    }
+}
+
+#[test]
+fn zero_royalty_share_rejected() {
+    let mut router = custom_mock_app();
+    let num_tokens = 2;
+    let (creator, _) = setup_accounts(&mut router, num_tokens);
+    let (factory_addr, sg721_code_id) = setup_factory_contract(&mut router, &creator);
+    let creation_fee = coins(CREATION_FEE_PER_TOKEN * num_tokens as u128, NATIVE_DENOM);
+
+    let mut msg = create_minter_msg(&creator, sg721_code_id, num_tokens);
+    msg.collection_params.info.royalty_info = Some(RoyaltyInfoResponse {
+        payment_address: creator.to_string(),
+        share: Decimal::zero(),
+    });
+    let err = router
+        .execute_contract(
+            creator.clone(),
+            factory_addr.clone(),
+            &Sg2ExecuteMsg::CreateMinter(msg.clone()),
+            &creation_fee,
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::ZeroRoyaltyShare {}.to_string(),
+        err.source().unwrap().source().unwrap().to_string()
+    );
+
+    msg.collection_params.info.royalty_info = None;
+    router
+        .execute_contract(
+            creator,
+            factory_addr,
+            &Sg2ExecuteMsg::CreateMinter(msg),
+            &creation_fee,
+        )
+        .unwrap();
+}
+
+#[test]
+fn can_batch_mint_query() {
+    let mut router = custom_mock_app();
+    let num_tokens = 10;
+    let (creator, buyer) = setup_accounts(&mut router, num_tokens);
+    let (minter_addr, _) = setup_minter_contract(&mut router, &creator, num_tokens, None);
+
+    setup_block_time(&mut router, GENESIS_MINT_START_TIME + 1, None);
+    let res: CanBatchMintResponse = router
+        .wrap()
+        .query_wasm_smart(
+            minter_addr.clone(),
+            &QueryMsg::CanBatchMint {
+                address: buyer.to_string(),
+                num_mints: 5,
+            },
+        )
+        .unwrap();
+    assert!(res.allowed);
+    assert_eq!(res.reason, None);
+
+    // The per address limit is 5
+    let res: CanBatchMintResponse = router
+        .wrap()
+        .query_wasm_smart(
+            minter_addr,
+            &QueryMsg::CanBatchMint {
+                address: buyer.to_string(),
+                num_mints: 6,
+            },
+        )
+        .unwrap();
+    assert!(!res.allowed);
+    assert_eq!(
+        res.reason,
+        Some(ContractError::MaxPerAddressLimitExceeded {}.to_string())
+    );
+}
+
+fn setup_presale_minter(router: &mut StargazeApp, creator: &Addr, num_tokens: u32) -> Addr {
+    let (factory_addr, sg721_code_id) = setup_factory_contract(router, creator);
+    let creation_fee = coins(CREATION_FEE_PER_TOKEN * num_tokens as u128, NATIVE_DENOM);
+
+    let mut msg = create_minter_msg(creator, sg721_code_id, num_tokens);
+    msg.init_msg.presale_deposits = Some(true);
+    let res = router
+        .execute_contract(
+            creator.clone(),
+            factory_addr,
+            &Sg2ExecuteMsg::CreateMinter(msg),
+            &creation_fee,
+        )
+        .unwrap();
+    minter_addr_from(&res)
+}
+
+#[test]
+fn presale_deposit_claim_mint() {
+    let mut router = custom_mock_app();
+    let num_tokens = 2;
+    let (creator, buyer) = setup_accounts(&mut router, num_tokens);
+    let minter_addr = setup_presale_minter(&mut router, &creator, num_tokens);
+
+    // Deposit more than the mint price before the start time
+    setup_block_time(&mut router, GENESIS_MINT_START_TIME - 100, None);
+    router
+        .execute_contract(
+            buyer.clone(),
+            minter_addr.clone(),
+            &ExecuteMsg::Deposit {},
+            &coins(MIN_MINT_PRICE + 10_000_000, NATIVE_DENOM),
+        )
+        .unwrap();
+    let balance = router.wrap().query_balance(&buyer, NATIVE_DENOM).unwrap();
+    assert_eq!(
+        balance.amount.u128(),
+        INITIAL_BALANCE - MIN_MINT_PRICE - 10_000_000
+    );
+
+    // Claiming before launch fails
+    let err = router
+        .execute_contract(
+            buyer.clone(),
+            minter_addr.clone(),
+            &ExecuteMsg::ClaimMint {},
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::BeforeMintStartTime {}.to_string(),
+        err.source().unwrap().to_string()
+    );
+
+    // At launch the deposit pays for the mint and the excess is refunded
+    setup_block_time(&mut router, GENESIS_MINT_START_TIME + 1, None);
+    router
+        .execute_contract(
+            buyer.clone(),
+            minter_addr.clone(),
+            &ExecuteMsg::ClaimMint {},
+            &[],
+        )
+        .unwrap();
+    let balance = router.wrap().query_balance(&buyer, NATIVE_DENOM).unwrap();
+    assert_eq!(balance.amount.u128(), INITIAL_BALANCE - MIN_MINT_PRICE);
+    let res: MintCountResponse = router
+        .wrap()
+        .query_wasm_smart(
+            minter_addr.clone(),
+            &QueryMsg::MintCount {
+                address: buyer.to_string(),
+            },
+        )
+        .unwrap();
+    assert_eq!(res.count, 1);
+
+    // The deposit is spent
+    let err = router
+        .execute_contract(buyer, minter_addr, &ExecuteMsg::ClaimMint {}, &[])
+        .unwrap_err();
+    assert_eq!(
+        ContractError::NoDeposit {}.to_string(),
+        err.source().unwrap().to_string()
+    );
+}
+
+#[test]
+fn presale_refund_deposit_before_launch() {
+    let mut router = custom_mock_app();
+    let num_tokens = 2;
+    let (creator, buyer) = setup_accounts(&mut router, num_tokens);
+    let minter_addr = setup_presale_minter(&mut router, &creator, num_tokens);
+
+    setup_block_time(&mut router, GENESIS_MINT_START_TIME - 100, None);
+    router
+        .execute_contract(
+            buyer.clone(),
+            minter_addr.clone(),
+            &ExecuteMsg::Deposit {},
+            &coins(MIN_MINT_PRICE, NATIVE_DENOM),
+        )
+        .unwrap();
+
+    // Deposits can't be withdrawn by the admin
+    let err = router
+        .execute_contract(creator, minter_addr.clone(), &ExecuteMsg::Withdraw {}, &[])
+        .unwrap_err();
+    assert_eq!(
+        ContractError::NoFundsToWithdraw {}.to_string(),
+        err.source().unwrap().to_string()
+    );
+
+    router
+        .execute_contract(
+            buyer.clone(),
+            minter_addr.clone(),
+            &ExecuteMsg::RefundDeposit {},
+            &[],
+        )
+        .unwrap();
+    let balance = router.wrap().query_balance(&buyer, NATIVE_DENOM).unwrap();
+    assert_eq!(balance.amount.u128(), INITIAL_BALANCE);
+
+    // Deposits close at launch
+    setup_block_time(&mut router, GENESIS_MINT_START_TIME + 1, None);
+    let err = router
+        .execute_contract(
+            buyer,
+            minter_addr,
+            &ExecuteMsg::Deposit {},
+            &coins(MIN_MINT_PRICE, NATIVE_DENOM),
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::AlreadyStarted {}.to_string(),
+        err.source().unwrap().to_string()
+    );
+}
+
+#[test]
+fn duplicate_reserved_token_ids_rejected() {
+    let mut router = custom_mock_app();
+    let num_tokens = 10;
+    let (creator, _) = setup_accounts(&mut router, num_tokens);
+    let (factory_addr, sg721_code_id) = setup_factory_contract(&mut router, &creator);
+    let creation_fee = coins(CREATION_FEE_PER_TOKEN * num_tokens as u128, NATIVE_DENOM);
+
+    let mut msg = create_minter_msg(&creator, sg721_code_id, num_tokens);
+    msg.init_msg.reserved_token_ids = Some(vec![3, 7, 3]);
+    let err = router
+        .execute_contract(
+            creator.clone(),
+            factory_addr.clone(),
+            &Sg2ExecuteMsg::CreateMinter(msg.clone()),
+            &creation_fee,
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::DuplicateTokenId { token_id: 3 }.to_string(),
+        err.source().unwrap().source().unwrap().to_string()
+    );
+
+    msg.init_msg.reserved_token_ids = Some(vec![3, 11]);
+    let err = router
+        .execute_contract(
+            creator.clone(),
+            factory_addr.clone(),
+            &Sg2ExecuteMsg::CreateMinter(msg.clone()),
+            &creation_fee,
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::InvalidReservedTokenId { token_id: 11 }.to_string(),
+        err.source().unwrap().source().unwrap().to_string()
+    );
+
+    msg.init_msg.reserved_token_ids = Some(vec![7, 3]);
+    router
+        .execute_contract(
+            creator,
+            factory_addr,
+            &Sg2ExecuteMsg::CreateMinter(msg),
+            &creation_fee,
+        )
+        .unwrap();
+    let res: ReservedTokensResponse = router
+        .wrap()
+        .query_wasm_smart(
+            "contract1",
+            &QueryMsg::ReservedTokens {
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(res.token_ids, vec![3, 7]);
+    let res: MintableNumTokensResponse = router
+        .wrap()
+        .query_wasm_smart("contract1", &QueryMsg::MintableNumTokens {})
+        .unwrap();
+    assert_eq!(res.count, 8);
+}
+
+#[test]
+fn phase_allowlists_checked_per_phase() {
+    let mut router = custom_mock_app();
+    let num_tokens = 10;
+    let (creator, og_buyer) = setup_accounts(&mut router, num_tokens);
+    let wl_buyer = Addr::unchecked("wl_buyer");
+    router
+        .sudo(SudoMsg::Bank(BankSudo::Mint {
+            to_address: wl_buyer.to_string(),
+            amount: coins(INITIAL_BALANCE, NATIVE_DENOM),
+        }))
+        .unwrap();
+
+    let (factory_addr, sg721_code_id) = setup_factory_contract(&mut router, &creator);
+    let og_list = setup_whitelist_contract(&mut router, &creator);
+    let wl_list = setup_whitelist_contract(&mut router, &creator);
+    for (list, member) in [(&og_list, &og_buyer), (&wl_list, &wl_buyer)] {
+        router
+            .execute_contract(
+                creator.clone(),
+                list.clone(),
+                &WhitelistExecuteMsg::AddMembers(AddMembersMsg {
+                    to_add: vec![member.to_string()],
+                }),
+                &[],
+            )
+            .unwrap();
+    }
+    let creation_fee = coins(CREATION_FEE_PER_TOKEN * num_tokens as u128, NATIVE_DENOM);
+
+    let start_time = Timestamp::from_nanos(GENESIS_MINT_START_TIME);
+    let mut msg = mock_create_minter(None);
+    msg.init_msg.mint_price = coin(MINT_PRICE, NATIVE_DENOM);
+    msg.init_msg.num_tokens = num_tokens;
+    msg.init_msg.phases = Some(vec![
+        PhaseMsg {
+            start_time: start_time.plus_seconds(100),
+            price: coin(60_000_000, NATIVE_DENOM),
+            per_address_limit: 1,
+            allowlist: Some(og_list.to_string()),
+        },
+        PhaseMsg {
+            start_time: start_time.plus_seconds(200),
+            price: coin(70_000_000, NATIVE_DENOM),
+            per_address_limit: 1,
+            allowlist: Some(wl_list.to_string()),
+        },
+    ]);
+    msg.collection_params.code_id = sg721_code_id;
+    msg.collection_params.info.creator = creator.to_string();
+
+    // A phase allowlist must be a whitelist contract
+    let mut invalid_msg = msg.clone();
+    invalid_msg.init_msg.phases.as_mut().unwrap()[1].allowlist = Some(factory_addr.to_string());
+    let err = router
+        .execute_contract(
+            creator.clone(),
+            factory_addr.clone(),
+            &Sg2ExecuteMsg::CreateMinter(invalid_msg),
+            &creation_fee,
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::InvalidPhaseAllowlist {
+            allowlist: factory_addr.to_string()
+        }
+        .to_string(),
+        err.source().unwrap().source().unwrap().to_string()
+    );
+
+    let res = router
+        .execute_contract(
+            creator,
+            factory_addr,
+            &Sg2ExecuteMsg::CreateMinter(msg),
+            &creation_fee,
+        )
+        .unwrap();
+    let minter_addr = minter_addr_from(&res);
+    let mint_msg = ExecuteMsg::Mint {
+        referral: None,
+        recipient: None,
+    };
+
+    // Only the OG list can mint in the first phase
+    setup_block_time(&mut router, start_time.plus_seconds(100).nanos(), None);
+    let err = router
+        .execute_contract(
+            wl_buyer.clone(),
+            minter_addr.clone(),
+            &mint_msg,
+            &coins(60_000_000, NATIVE_DENOM),
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::NotWhitelisted {
+            addr: wl_buyer.to_string()
+        }
+        .to_string(),
+        err.source().unwrap().to_string()
+    );
+    router
+        .execute_contract(
+            og_buyer.clone(),
+            minter_addr.clone(),
+            &mint_msg,
+            &coins(60_000_000, NATIVE_DENOM),
+        )
+        .unwrap();
+
+    // The second phase checks its own list
+    setup_block_time(&mut router, start_time.plus_seconds(200).nanos(), None);
+    let err = router
+        .execute_contract(
+            og_buyer.clone(),
+            minter_addr.clone(),
+            &mint_msg,
+            &coins(70_000_000, NATIVE_DENOM),
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::NotWhitelisted {
+            addr: og_buyer.to_string()
+        }
+        .to_string(),
+        err.source().unwrap().to_string()
+    );
+    router
+        .execute_contract(
+            wl_buyer,
+            minter_addr,
+            &mint_msg,
+            &coins(70_000_000, NATIVE_DENOM),
+        )
+        .unwrap();
+}
+
+#[test]
+fn mint_receipts_record_price_paid() {
+    let mut router = custom_mock_app();
+    let num_tokens = 10;
+    let (creator, buyer) = setup_accounts(&mut router, num_tokens);
+    let (factory_addr, sg721_code_id) = setup_factory_contract(&mut router, &creator);
+    let creation_fee = coins(CREATION_FEE_PER_TOKEN * num_tokens as u128, NATIVE_DENOM);
+
+    // The price drops from one phase to the next
+    let start_time = Timestamp::from_nanos(GENESIS_MINT_START_TIME);
+    let mut msg = mock_create_minter(None);
+    msg.init_msg.mint_price = coin(MINT_PRICE, NATIVE_DENOM);
+    msg.init_msg.num_tokens = num_tokens;
+    msg.init_msg.record_receipts = Some(true);
+    msg.init_msg.phases = Some(vec![
+        PhaseMsg {
+            start_time: start_time.plus_seconds(100),
+            price: coin(80_000_000, NATIVE_DENOM),
+            per_address_limit: 1,
+            allowlist: None,
+        },
+        PhaseMsg {
+            start_time: start_time.plus_seconds(200),
+            price: coin(60_000_000, NATIVE_DENOM),
+            per_address_limit: 2,
+            allowlist: None,
+        },
+    ]);
+    msg.collection_params.code_id = sg721_code_id;
+    msg.collection_params.info.creator = creator.to_string();
+    let res = router
+        .execute_contract(
+            creator,
+            factory_addr,
+            &Sg2ExecuteMsg::CreateMinter(msg),
+            &creation_fee,
+        )
+        .unwrap();
+    let minter_addr = minter_addr_from(&res);
+    let mint_msg = ExecuteMsg::Mint {
+        referral: None,
+        recipient: None,
+    };
+
+    setup_block_time(&mut router, start_time.plus_seconds(100).nanos(), Some(100));
+    router
+        .execute_contract(
+            buyer.clone(),
+            minter_addr.clone(),
+            &mint_msg,
+            &coins(80_000_000, NATIVE_DENOM),
+        )
+        .unwrap();
+    setup_block_time(&mut router, start_time.plus_seconds(200).nanos(), Some(200));
+    let res = router
+        .execute_contract(
+            buyer,
+            minter_addr.clone(),
+            &mint_msg,
+            &coins(60_000_000, NATIVE_DENOM),
+        )
+        .unwrap();
+    assert!(res
+        .events
+        .iter()
+        .any(|event| event.ty == "wasm-mint_receipt"));
+
+    let first: MintReceiptResponse = router
+        .wrap()
+        .query_wasm_smart(minter_addr.clone(), &QueryMsg::MintReceipt { token_id: 1 })
+        .unwrap();
+    assert_eq!(
+        first.receipt,
+        Some(MintReceipt {
+            height: 100,
+            price: coin(80_000_000, NATIVE_DENOM),
+        })
+    );
+    let second: MintReceiptResponse = router
+        .wrap()
+        .query_wasm_smart(minter_addr, &QueryMsg::MintReceipt { token_id: 2 })
+        .unwrap();
+    assert_eq!(
+        second.receipt,
+        Some(MintReceipt {
+            height: 200,
+            price: coin(60_000_000, NATIVE_DENOM),
+        })
+    );
+    assert_ne!(first.receipt, second.receipt);
+}
+
+#[test]
+fn height_gate_blocks_early_mints() {
+    let mut router = custom_mock_app();
+    let num_tokens = 2;
+    let (creator, buyer) = setup_accounts(&mut router, num_tokens);
+    let (minter_addr, _) = setup_minter_contract_with(&mut router, &creator, num_tokens, |msg| {
+        msg.init_msg.require_height_gate = Some(1_000);
+    });
+    let mint_msg = ExecuteMsg::Mint {
+        referral: None,
+        recipient: None,
+    };
+
+    // After the start time but before the gate height
+    setup_block_time(&mut router, GENESIS_MINT_START_TIME + 1, Some(999));
+    let err = router
+        .execute_contract(
+            buyer.clone(),
+            minter_addr.clone(),
+            &mint_msg,
+            &coins(MIN_MINT_PRICE, NATIVE_DENOM),
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::BeforeMintStartHeight { height: 1_000 }.to_string(),
+        err.source().unwrap().to_string()
+    );
+
+    setup_block_time(&mut router, GENESIS_MINT_START_TIME + 1, Some(1_000));
+    router
+        .execute_contract(
+            buyer,
+            minter_addr,
+            &mint_msg,
+            &coins(MIN_MINT_PRICE, NATIVE_DENOM),
+        )
+        .unwrap();
+}
+
+#[test]
+fn mint_price_above_max_rejected() {
+    let mut router = custom_mock_app();
+    let num_tokens = 2;
+    let (creator, _) = setup_accounts(&mut router, num_tokens);
+    let (factory_addr, sg721_code_id) = setup_factory_contract(&mut router, &creator);
+    let creation_fee = coins(CREATION_FEE_PER_TOKEN * num_tokens as u128, NATIVE_DENOM);
+
+    let mut msg = create_minter_msg(&creator, sg721_code_id, num_tokens);
+    msg.init_msg.mint_price = coin(MAX_MINT_PRICE * 1000, NATIVE_DENOM);
+    let err = router
+        .execute_contract(
+            creator,
+            factory_addr,
+            &Sg2ExecuteMsg::CreateMinter(msg),
+            &creation_fee,
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::MintPriceTooHigh {
+            max: MAX_MINT_PRICE,
+            got: MAX_MINT_PRICE * 1000
+        }
+        .to_string(),
+        err.source().unwrap().source().unwrap().to_string()
+    );
+}
+
+#[test]
+fn phases_query_marks_active_phase() {
+    let mut router = custom_mock_app();
+    let num_tokens = 10;
+    let (creator, _) = setup_accounts(&mut router, num_tokens);
+    let (factory_addr, sg721_code_id) = setup_factory_contract(&mut router, &creator);
+    let creation_fee = coins(CREATION_FEE_PER_TOKEN * num_tokens as u128, NATIVE_DENOM);
+
+    let start_time = Timestamp::from_nanos(GENESIS_MINT_START_TIME);
+    let mut msg = create_minter_msg(&creator, sg721_code_id, num_tokens);
+    msg.init_msg.mint_price = coin(MINT_PRICE, NATIVE_DENOM);
+    msg.init_msg.phases = Some(
+        (1..=3)
+            .map(|i| PhaseMsg {
+                start_time: start_time.plus_seconds(i * 100),
+                price: coin(60_000_000, NATIVE_DENOM),
+                per_address_limit: i as u32,
+                allowlist: None,
+            })
+            .collect(),
+    );
+    let res = router
+        .execute_contract(
+            creator,
+            factory_addr,
+            &Sg2ExecuteMsg::CreateMinter(msg),
+            &creation_fee,
+        )
+        .unwrap();
+    let minter_addr = minter_addr_from(&res);
+
+    let active_phases = |router: &StargazeApp| -> Vec<u32> {
+        let res: PhasesResponse = router
+            .wrap()
+            .query_wasm_smart(minter_addr.clone(), &QueryMsg::Phases {})
+            .unwrap();
+        assert_eq!(res.phases.len(), 3);
+        res.phases
+            .into_iter()
+            .filter(|phase| phase.active)
+            .map(|phase| phase.index)
+            .collect()
+    };
+
+    // None is active before the first phase
+    setup_block_time(&mut router, start_time.plus_seconds(99).nanos(), None);
+    assert!(active_phases(&router).is_empty());
+
+    setup_block_time(&mut router, start_time.plus_seconds(250).nanos(), None);
+    assert_eq!(active_phases(&router), vec![1]);
+
+    // The last phase runs until the mint ends
+    setup_block_time(&mut router, start_time.plus_seconds(1000).nanos(), None);
+    assert_eq!(active_phases(&router), vec![2]);
+}
+
+#[test]
+fn per_address_limit_survives_transfers() {
+    let mut router = custom_mock_app();
+    let num_tokens = 10;
+    let (creator, buyer) = setup_accounts(&mut router, num_tokens);
+    let (minter_addr, config) = setup_minter_contract(&mut router, &creator, num_tokens, None);
+    let mint_msg = ExecuteMsg::Mint {
+        referral: None,
+        recipient: None,
+    };
+
+    setup_block_time(&mut router, GENESIS_MINT_START_TIME + 1, None);
+    for _ in 0..config.per_address_limit {
+        router
+            .execute_contract(
+                buyer.clone(),
+                minter_addr.clone(),
+                &mint_msg,
+                &coins(MINT_PRICE, NATIVE_DENOM),
+            )
+            .unwrap();
+    }
+
+    // Transferring the tokens out doesn't free up the limit
+    for token_id in 1..=config.per_address_limit {
+        router
+            .execute_contract(
+                buyer.clone(),
+                Addr::unchecked(&config.sg721_address),
+                &Cw721ExecuteMsg::<Empty, Empty>::TransferNft {
+                    recipient: creator.to_string(),
+                    token_id: token_id.to_string(),
+                },
+                &[],
+            )
+            .unwrap();
+    }
+    let err = router
+        .execute_contract(
+            buyer,
+            minter_addr,
+            &mint_msg,
+            &coins(MINT_PRICE, NATIVE_DENOM),
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::MaxPerAddressLimitExceeded {}.to_string(),
+        err.source().unwrap().to_string()
+    );
+}
+
+// Overwrites a raw key of a contract's storage, using the cw-multi-test storage layout
+fn set_contract_storage(router: &mut StargazeApp, contract: &Addr, key: &[u8], value: &[u8]) {
+    let contract_namespace = format!("contract_data/{}", contract);
+    let mut full_key = vec![];
+    for namespace in [b"wasm".as_slice(), contract_namespace.as_bytes()] {
+        full_key.extend_from_slice(&(namespace.len() as u16).to_be_bytes());
+        full_key.extend_from_slice(namespace);
+    }
+    full_key.extend_from_slice(key);
+    router.init_modules(|_, _, storage| storage.set(&full_key, value));
+}
+
+#[test]
+fn inconsistent_mintable_state_rejected() {
+    let mut router = custom_mock_app();
+    let num_tokens = 2;
+    let (creator, buyer) = setup_accounts(&mut router, num_tokens);
+    let (minter_addr, _) = setup_minter_contract(&mut router, &creator, num_tokens, None);
+    let mint = |router: &mut StargazeApp| {
+        router.execute_contract(
+            buyer.clone(),
+            minter_addr.clone(),
+            &ExecuteMsg::Mint {
+                referral: None,
+                recipient: None,
+            },
+            &coins(MINT_PRICE, NATIVE_DENOM),
+        )
+    };
+
+    setup_block_time(&mut router, GENESIS_MINT_START_TIME + 1, None);
+    mint(&mut router).unwrap();
+
+    // The counter says sold out while an id is left
+    set_contract_storage(&mut router, &minter_addr, b"mintable_num_tokens", b"0");
+    let err = mint(&mut router).unwrap_err();
+    assert_eq!(
+        ContractError::InconsistentState {}.to_string(),
+        err.source().unwrap().to_string()
+    );
+
+    // The counter says tokens remain after the last id is minted
+    set_contract_storage(&mut router, &minter_addr, b"mintable_num_tokens", b"2");
+    mint(&mut router).unwrap();
+    let err = mint(&mut router).unwrap_err();
+    assert_eq!(
+        ContractError::InconsistentState {}.to_string(),
+        err.source().unwrap().to_string()
+    );
+}
+
+#[test]
+fn cancel_launch_before_first_mint() {
+    let mut router = custom_mock_app();
+    let num_tokens = 2;
+    let (creator, buyer) = setup_accounts(&mut router, num_tokens);
+    let minter_addr = setup_presale_minter(&mut router, &creator, num_tokens);
+
+    setup_block_time(&mut router, GENESIS_MINT_START_TIME - 100, None);
+    router
+        .execute_contract(
+            buyer.clone(),
+            minter_addr.clone(),
+            &ExecuteMsg::Deposit {},
+            &coins(MIN_MINT_PRICE, NATIVE_DENOM),
+        )
+        .unwrap();
+
+    // Only the admin can cancel
+    let err = router
+        .execute_contract(
+            buyer.clone(),
+            minter_addr.clone(),
+            &ExecuteMsg::CancelLaunch {},
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::Unauthorized("Sender is not an admin".to_owned()).to_string(),
+        err.source().unwrap().to_string()
+    );
+
+    let res = router
+        .execute_contract(
+            creator,
+            minter_addr.clone(),
+            &ExecuteMsg::CancelLaunch {},
+            &[],
+        )
+        .unwrap();
+    assert!(res
+        .events
+        .iter()
+        .any(|event| event.ty == "wasm-launch_cancelled"));
+
+    // The depositor pulls their deposit back
+    router
+        .execute_contract(
+            buyer.clone(),
+            minter_addr.clone(),
+            &ExecuteMsg::RefundDeposit {},
+            &[],
+        )
+        .unwrap();
+    let balance = router.wrap().query_balance(&buyer, NATIVE_DENOM).unwrap();
+    assert_eq!(balance.amount.u128(), INITIAL_BALANCE);
+
+    setup_block_time(&mut router, GENESIS_MINT_START_TIME + 1, None);
+    let err = router
+        .execute_contract(
+            buyer,
+            minter_addr,
+            &ExecuteMsg::Mint {
+                referral: None,
+                recipient: None,
+            },
+            &coins(MIN_MINT_PRICE, NATIVE_DENOM),
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::LaunchCancelled {}.to_string(),
+        err.source().unwrap().to_string()
+    );
+}
+
+#[test]
+fn cancel_launch_after_creator_reserve() {
+    let mut router = custom_mock_app();
+    let num_tokens = 2;
+    let (creator, _) = setup_accounts(&mut router, num_tokens);
+    let (minter_addr, _) = setup_minter_contract_with(&mut router, &creator, num_tokens, |msg| {
+        msg.init_msg.creator_reserve = Some(1);
+    });
+
+    let err = router
+        .execute_contract(creator, minter_addr, &ExecuteMsg::CancelLaunch {}, &[])
+        .unwrap_err();
+    assert_eq!(
+        ContractError::CancelAfterMint {}.to_string(),
+        err.source().unwrap().to_string()
+    );
+}
+
+#[test]
+fn eligibility_at_future_times() {
+    let mut router = custom_mock_app();
+    let num_tokens = 10;
+    let (creator, buyer) = setup_accounts(&mut router, num_tokens);
+    let (factory_addr, sg721_code_id) = setup_factory_contract(&mut router, &creator);
+    let whitelist_addr = setup_whitelist_contract(&mut router, &creator);
+    router
+        .execute_contract(
+            creator.clone(),
+            whitelist_addr.clone(),
+            &WhitelistExecuteMsg::AddMembers(AddMembersMsg {
+                to_add: vec![buyer.to_string()],
+            }),
+            &[],
+        )
+        .unwrap();
+    let creation_fee = coins(CREATION_FEE_PER_TOKEN * num_tokens as u128, NATIVE_DENOM);
+
+    let mut msg = create_minter_msg(&creator, sg721_code_id, num_tokens);
+    msg.init_msg.mint_price = coin(MINT_PRICE, NATIVE_DENOM);
+    msg.init_msg.whitelist = Some(whitelist_addr.to_string());
+    let res = router
+        .execute_contract(
+            creator.clone(),
+            factory_addr,
+            &Sg2ExecuteMsg::CreateMinter(msg),
+            &creation_fee,
+        )
+        .unwrap();
+    let minter_addr = minter_addr_from(&res);
+    let eligibility_at = |address: &str, nanos: u64| -> EligibilityAtResponse {
+        router
+            .wrap()
+            .query_wasm_smart(
+                minter_addr.clone(),
+                &QueryMsg::EligibilityAt {
+                    address: address.to_string(),
+                    at: Timestamp::from_nanos(nanos),
+                },
+            )
+            .unwrap()
+    };
+
+    // The whitelist runs from 100 to 10,000,000 nanos after genesis
+    let whitelist_time = GENESIS_MINT_START_TIME + 100;
+    let public_time = GENESIS_MINT_START_TIME + 10_000_000;
+    assert_eq!(
+        eligibility_at(buyer.as_str(), whitelist_time),
+        EligibilityAtResponse {
+            eligible: true,
+            price: coin(WHITELIST_AMOUNT, NATIVE_DENOM),
+            reason: None,
+        }
+    );
+    assert_eq!(
+        eligibility_at(buyer.as_str(), public_time),
+        EligibilityAtResponse {
+            eligible: true,
+            price: coin(MINT_PRICE, NATIVE_DENOM),
+            reason: None,
+        }
+    );
+
+    let res = eligibility_at("outsider", whitelist_time);
+    assert!(!res.eligible);
+    assert_eq!(
+        res.reason,
+        Some(
+            ContractError::NotWhitelisted {
+                addr: "outsider".to_string()
+            }
+            .to_string()
+        )
+    );
+
+    // Same checks as a mint, so a pause shows up at any time
+    router
+        .execute_contract(
+            creator,
+            minter_addr.clone(),
+            &ExecuteMsg::SetMintingPause { pause: true },
+            &[],
+        )
+        .unwrap();
+    let res: EligibilityAtResponse = router
+        .wrap()
+        .query_wasm_smart(
+            minter_addr,
+            &QueryMsg::EligibilityAt {
+                address: buyer.to_string(),
+                at: Timestamp::from_nanos(public_time),
+            },
+        )
+        .unwrap();
+    assert!(!res.eligible);
+    assert_eq!(
+        res.reason,
+        Some(ContractError::MintingPaused {}.to_string())
+    );
+}
+
+#[test]
+fn proceeds_routed_to_treasury_contract() {
+    let mut router = custom_mock_app();
+    let num_tokens = 2;
+    let (creator, buyer) = setup_accounts(&mut router, num_tokens);
+    let (factory_addr, sg721_code_id) = setup_factory_contract(&mut router, &creator);
+    let treasury_code_id = router.store_code(contract_treasury());
+    let treasury_addr = router
+        .instantiate_contract(
+            treasury_code_id,
+            creator.clone(),
+            &Empty {},
+            &[],
+            "treasury",
+            None,
+        )
+        .unwrap();
+    let creation_fee = coins(CREATION_FEE_PER_TOKEN * num_tokens as u128, NATIVE_DENOM);
+
+    let mut msg = mock_create_minter(None);
+    msg.init_msg.num_tokens = num_tokens;
+    msg.init_msg.proceeds_target = Some(ProceedsTargetMsg::Wasm {
+        contract: treasury_addr.to_string(),
+        msg: to_binary(&TreasuryExecuteMsg::Deposit {
+            memo: "mint proceeds".to_string(),
+        })
+        .unwrap(),
+    });
+    msg.collection_params.code_id = sg721_code_id;
+    msg.collection_params.info.creator = creator.to_string();
+
+    // Can't be combined with payout splits
+    let mut invalid_msg = msg.clone();
+    invalid_msg.init_msg.payout_splits = Some(vec![PayoutSplitMsg {
+        address: creator.to_string(),
+        bps: 10_000,
+    }]);
+    let err = router
+        .execute_contract(
+            creator.clone(),
+            factory_addr.clone(),
+            &Sg2ExecuteMsg::CreateMinter(invalid_msg),
+            &creation_fee,
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::ConflictingProceedsTarget {}.to_string(),
+        err.source().unwrap().source().unwrap().to_string()
+    );
+
+    let res = router
+        .execute_contract(
+            creator,
+            factory_addr,
+            &Sg2ExecuteMsg::CreateMinter(msg),
+            &creation_fee,
+        )
+        .unwrap();
+    let minter_addr = minter_addr_from(&res);
+
+    setup_block_time(&mut router, GENESIS_MINT_START_TIME + 1, None);
+    let res = router
+        .execute_contract(
+            buyer,
+            minter_addr,
+            &ExecuteMsg::Mint {
+                referral: None,
+                recipient: None,
+            },
+            &coins(MIN_MINT_PRICE, NATIVE_DENOM),
+        )
+        .unwrap();
+
+    // The treasury is executed with the proceeds after the network fee
+    let proceeds = MIN_MINT_PRICE - MIN_MINT_PRICE * MINT_FEE_BPS as u128 / 10_000;
+    let treasury_event = res
+        .events
+        .iter()
+        .find(|event| {
+            event.ty == "wasm"
+                && event
+                    .attributes
+                    .iter()
+                    .any(|attr| attr.key == "_contract_addr" && attr.value == treasury_addr)
+        })
+        .unwrap();
+    assert!(treasury_event
+        .attributes
+        .iter()
+        .any(|attr| attr.key == "memo" && attr.value == "mint proceeds"));
+    let balance = router
+        .wrap()
+        .query_balance(&treasury_addr, NATIVE_DENOM)
+        .unwrap();
+    assert_eq!(balance.amount.u128(), proceeds);
+}
+
+#[test]
+fn generative_seeds_distinct_and_deterministic() {
+    // Reserves one token for the creator, mints two more at a fixed block and returns all seeds
+    let mint_seeds = || -> Vec<HexBinary> {
+        let mut router = custom_mock_app();
+        let num_tokens = 3;
+        let (creator, buyer) = setup_accounts(&mut router, num_tokens);
+        let (factory_addr, sg721_code_id) = setup_factory_contract(&mut router, &creator);
+        let creation_fee = coins(CREATION_FEE_PER_TOKEN * num_tokens as u128, NATIVE_DENOM);
+
+        let mut msg = create_minter_msg(&creator, sg721_code_id, num_tokens);
+        msg.init_msg.generative = Some(true);
+        msg.init_msg.creator_reserve = Some(1);
+        let res = router
+            .execute_contract(
+                creator,
+                factory_addr,
+                &Sg2ExecuteMsg::CreateMinter(msg),
+                &creation_fee,
+            )
+            .unwrap();
+        let minter_addr = minter_addr_from(&res);
+
+        setup_block_time(&mut router, GENESIS_MINT_START_TIME + 1, Some(12_345));
+        router
+            .execute_contract(
+                buyer,
+                minter_addr.clone(),
+                &ExecuteMsg::BatchMint { num_mints: 2 },
+                &coins(MIN_MINT_PRICE * 2, NATIVE_DENOM),
+            )
+            .unwrap();
+        (1..=3)
+            .map(|token_id| {
+                let res: TokenSeedResponse = router
+                    .wrap()
+                    .query_wasm_smart(minter_addr.clone(), &QueryMsg::TokenSeed { token_id })
+                    .unwrap();
+                res.seed.unwrap()
+            })
+            .collect()
+    };
+
+    let seeds = mint_seeds();
+    assert_ne!(seeds[0], seeds[1]);
+    assert_ne!(seeds[1], seeds[2]);
+    // The same block data gives the same seeds
+    assert_eq!(seeds, mint_seeds());
+}
+
+#[test]
+fn reroll_seeds_the_replacement() {
+    let mut router = custom_mock_app();
+    let num_tokens = 3;
+    let (creator, buyer) = setup_accounts(&mut router, num_tokens);
+    let reroll_fee = 5_000_000;
+    let (minter_addr, config) =
+        setup_minter_contract_with(&mut router, &creator, num_tokens, |msg| {
+            msg.init_msg.generative = Some(true);
+            msg.init_msg.reroll_fee = Some(coin(reroll_fee, NATIVE_DENOM));
+        });
+    let token_seed = |router: &StargazeApp, token_id: u32| {
+        let res: TokenSeedResponse = router
+            .wrap()
+            .query_wasm_smart(minter_addr.clone(), &QueryMsg::TokenSeed { token_id })
+            .unwrap();
+        res.seed
+    };
+
+    setup_block_time(&mut router, GENESIS_MINT_START_TIME + 1, None);
+    let res = router
+        .execute_contract(
+            buyer.clone(),
+            minter_addr.clone(),
+            &ExecuteMsg::Mint {
+                referral: None,
+                recipient: None,
+            },
+            &coins(MIN_MINT_PRICE, NATIVE_DENOM),
+        )
+        .unwrap();
+    let token_id = get_token_id(&res);
+    let seed = token_seed(&router, token_id).unwrap();
+
+    let approve_msg: Cw721ExecuteMsg<Empty, Empty> = Cw721ExecuteMsg::Approve {
+        spender: minter_addr.to_string(),
+        token_id: token_id.to_string(),
+        expires: None,
+    };
+    router
+        .execute_contract(
+            buyer.clone(),
+            Addr::unchecked(config.sg721_address),
+            &approve_msg,
+            &[],
+        )
+        .unwrap();
+    let res = router
+        .execute_contract(
+            buyer,
+            minter_addr.clone(),
+            &ExecuteMsg::Reroll { token_id },
+            &coins(reroll_fee, NATIVE_DENOM),
+        )
+        .unwrap();
+    let new_token_id = get_token_id(&res);
+
+    // The burned id loses its seed and the replacement gets a new one
+    assert_eq!(token_seed(&router, token_id), None);
+    let new_seed = token_seed(&router, new_token_id).unwrap();
+    assert_ne!(seed, new_seed);
+}
+
+#[test]
+fn batch_mint_counts_against_daily_limit() {
+    let mut router = custom_mock_app();
+    let num_tokens = 10;
+    let (creator, buyer) = setup_accounts(&mut router, num_tokens);
+    let (minter_addr, _) = setup_minter_contract_with(&mut router, &creator, num_tokens, |msg| {
+        msg.init_msg.daily_mint_limit = Some(3);
+    });
+    let batch_mint = |router: &mut StargazeApp, num_mints: u32| {
+        router.execute_contract(
+            buyer.clone(),
+            minter_addr.clone(),
+            &ExecuteMsg::BatchMint { num_mints },
+            &coins(MIN_MINT_PRICE * num_mints as u128, NATIVE_DENOM),
+        )
+    };
+
+    setup_block_time(&mut router, GENESIS_MINT_START_TIME + 1, None);
+    batch_mint(&mut router, 2).unwrap();
+
+    // A batch that would cross the limit is rejected whole
+    let err = batch_mint(&mut router, 2).unwrap_err();
+    assert_eq!(
+        ContractError::DailyLimitExceeded { limit: 3 }.to_string(),
+        err.source().unwrap().to_string()
+    );
+    let res: MintCountResponse = router
+        .wrap()
+        .query_wasm_smart(
+            minter_addr.clone(),
+            &QueryMsg::MintCount {
+                address: buyer.to_string(),
+            },
+        )
+        .unwrap();
+    assert_eq!(res.count, 2);
+
+    // The limit resets the next day
+    setup_block_time(
+        &mut router,
+        GENESIS_MINT_START_TIME + 24 * 60 * 60 * 1_000_000_000,
+        None,
+    );
+    batch_mint(&mut router, 2).unwrap();
+}
+
+#[test]
+fn per_address_limit_capped_by_factory_params() {
+    let mut router = custom_mock_app();
+    setup_block_time(&mut router, GENESIS_MINT_START_TIME - 1, None);
+
+    // 40 is above the whitelist's hardcoded 30 but under the governance cap of 50
+    let num_tokens = 5000;
+    let (creator, _) = setup_accounts(&mut router, num_tokens);
+    let minter_code_id = router.store_code(contract_minter());
+    let creation_fee = coins(CREATION_FEE_PER_TOKEN * num_tokens as u128, NATIVE_DENOM);
+
+    let factory_code_id = router.store_code(contract_factory());
+    let mut params = mock_params();
+    params.code_id = minter_code_id;
+    assert_eq!(params.extension.max_per_address_limit, 50);
+
+    let factory_addr = router
+        .instantiate_contract(
+            factory_code_id,
+            creator.clone(),
+            &serial_print_factory::msg::InstantiateMsg { params },
+            &[],
+            "factory",
+            None,
+        )
+        .unwrap();
+
+    let sg721_code_id = router.store_code(contract_sg721());
+    let mut msg = create_minter_msg(&creator, sg721_code_id, num_tokens);
+    msg.init_msg.mint_price = coin(MINT_PRICE, NATIVE_DENOM);
+    msg.init_msg.per_address_limit = 40;
+
+    let res = router
+        .execute_contract(
+            creator,
+            factory_addr,
+            &Sg2ExecuteMsg::CreateMinter(msg),
+            &creation_fee,
+        )
+        .unwrap();
+
+    let config: ConfigResponse = router
+        .wrap()
+        .query_wasm_smart(minter_addr_from(&res), &QueryMsg::Config {})
+        .unwrap();
+    assert_eq!(config.per_address_limit, 40);
+}
+
+#[test]
+fn mint_with_ibc_denom() {
+    let mut router = custom_mock_app();
+    setup_block_time(&mut router, GENESIS_MINT_START_TIME - 1, None);
+    let num_tokens = 10;
+    let (creator, buyer) = setup_accounts(&mut router, num_tokens);
+    let (factory_addr, sg721_code_id) = setup_factory_contract(&mut router, &creator);
+    let creation_fee = coins(CREATION_FEE_PER_TOKEN * num_tokens as u128, NATIVE_DENOM);
+    let ibc_denom = "ibc/27394FB092D2ECCD56123C74F36E4C1F926001CEADA9CA97EA622B25F41E5EB2";
+
+    let create_msg_with_price = |price: Coin| {
+        let mut msg = create_minter_msg(&creator, sg721_code_id, num_tokens);
+        msg.init_msg.mint_price = price;
+        Sg2ExecuteMsg::CreateMinter(msg)
+    };
+    let create_msg = |denom: &str| create_msg_with_price(coin(MINT_PRICE, denom));
+
+    // Malformed denoms are rejected
+    for denom in ["ibc/ABC123", "factory/creator/", "uosmo"] {
+        router
+            .execute_contract(
+                creator.clone(),
+                factory_addr.clone(),
+                &create_msg(denom),
+                &creation_fee,
+            )
+            .unwrap_err();
+    }
+
+    // The native minimum doesn't apply to an IBC price
+    router
+        .execute_contract(
+            creator.clone(),
+            factory_addr.clone(),
+            &create_msg_with_price(coin(MIN_MINT_PRICE - 1, ibc_denom)),
+            &creation_fee,
+        )
+        .unwrap();
+
+    let res = router
+        .execute_contract(
+            creator.clone(),
+            factory_addr,
+            &create_msg(ibc_denom),
+            &creation_fee,
+        )
+        .unwrap();
+    let minter_addr = minter_addr_from(&res);
+
+    router
+        .sudo(SudoMsg::Bank(BankSudo::Mint {
+            to_address: buyer.to_string(),
+            amount: coins(MINT_PRICE, ibc_denom),
+        }))
+        .unwrap();
+    setup_block_time(&mut router, GENESIS_MINT_START_TIME + 1, None);
+
+    // The native denom doesn't pay for an IBC priced mint
+    router
+        .execute_contract(
+            buyer.clone(),
+            minter_addr.clone(),
+            &ExecuteMsg::Mint {
+                referral: None,
+                recipient: None,
+            },
+            &coins(MINT_PRICE, NATIVE_DENOM),
+        )
+        .unwrap_err();
+
+    let creator_balance = router
+        .wrap()
+        .query_balance(&creator, ibc_denom)
+        .unwrap()
+        .amount
+        .u128();
+    router
+        .execute_contract(
+            buyer.clone(),
+            minter_addr,
+            &ExecuteMsg::Mint {
+                referral: None,
+                recipient: None,
+            },
+            &coins(MINT_PRICE, ibc_denom),
+        )
+        .unwrap();
+
+    let balance = |addr: &str| {
+        router
+            .wrap()
+            .query_balance(addr, ibc_denom)
+            .unwrap()
+            .amount
+            .u128()
+    };
+    assert_eq!(balance(buyer.as_str()), 0);
+    assert_eq!(balance("fairburn_pool"), MINT_FEE);
+    assert_eq!(
+        balance(creator.as_str()),
+        creator_balance + MINT_PRICE - MINT_FEE
+    );
+}
+
+#[test]
+fn withdraw_sweeps_funds_sent_to_minter() {
+    let mut router = custom_mock_app();
+    setup_block_time(&mut router, GENESIS_MINT_START_TIME - 1, None);
+    let num_tokens = 10;
+    let (creator, buyer) = setup_accounts(&mut router, num_tokens);
+    let (minter_addr, _) = setup_minter_contract(&mut router, &creator, num_tokens, None);
+
+    // Funds sent to the minter outside of a mint can only leave through withdraw
+    let stray = 1_000_000;
+    router
+        .sudo(SudoMsg::Bank(BankSudo::Mint {
+            to_address: minter_addr.to_string(),
+            amount: coins(stray, NATIVE_DENOM),
+        }))
+        .unwrap();
+
+    let err = router
+        .execute_contract(buyer, minter_addr.clone(), &ExecuteMsg::Withdraw {}, &[])
+        .unwrap_err();
+    assert_eq!(
+        err.source().unwrap().to_string(),
+        ContractError::Unauthorized("Sender is not an admin".to_owned()).to_string()
+    );
+
+    let creator_balance = router
+        .wrap()
+        .query_balance(&creator, NATIVE_DENOM)
+        .unwrap()
+        .amount
+        .u128();
+    let res = router
+        .execute_contract(
+            creator.clone(),
+            minter_addr.clone(),
+            &ExecuteMsg::Withdraw {},
+            &[],
+        )
+        .unwrap();
+    let withdraw_event = res.events.iter().find(|e| e.ty == "wasm").unwrap();
+    assert!(withdraw_event
+        .attributes
+        .iter()
+        .any(|a| a.key == "action" && a.value == "withdraw"));
+    assert!(withdraw_event
+        .attributes
+        .iter()
+        .any(|a| a.key == "amount" && a.value == coin(stray, NATIVE_DENOM).to_string()));
+
+    let minter_balance = router
+        .wrap()
+        .query_balance(&minter_addr, NATIVE_DENOM)
+        .unwrap();
+    assert!(minter_balance.amount.is_zero());
+    let balance = router
+        .wrap()
+        .query_balance(&creator, NATIVE_DENOM)
+        .unwrap()
+        .amount
+        .u128();
+    assert_eq!(balance, creator_balance + stray);
+}
+
+#[test]
+fn royalty_capped_by_factory_params() {
+    let mut router = custom_mock_app();
+    setup_block_time(&mut router, GENESIS_MINT_START_TIME - 1, None);
+    let num_tokens = 10;
+    let (creator, _) = setup_accounts(&mut router, num_tokens);
+    let (factory_addr, sg721_code_id) = setup_factory_contract(&mut router, &creator);
+    let creation_fee = coins(CREATION_FEE_PER_TOKEN * num_tokens as u128, NATIVE_DENOM);
+
+    let mut msg = create_minter_msg(&creator, sg721_code_id, num_tokens);
+    msg.collection_params.info.royalty_info = Some(RoyaltyInfoResponse {
+        payment_address: creator.to_string(),
+        share: Decimal::percent(12),
+    });
+
+    // 12% is over the 10% governance cap
+    let err = router
+        .execute_contract(
+            creator.clone(),
+            factory_addr.clone(),
+            &Sg2ExecuteMsg::CreateMinter(msg.clone()),
+            &creation_fee,
+        )
+        .unwrap_err();
+    assert_eq!(
+        serial_print_factory::ContractError::RoyaltyTooHigh {
+            max: Decimal::percent(10),
+            got: Decimal::percent(12),
+        }
+        .to_string(),
+        err.source().unwrap().to_string()
+    );
+
+    // Exactly at the cap is allowed
+    msg.collection_params.info.royalty_info = Some(RoyaltyInfoResponse {
+        payment_address: creator.to_string(),
+        share: Decimal::percent(10),
+    });
+    router
+        .execute_contract(
+            creator,
+            factory_addr,
+            &Sg2ExecuteMsg::CreateMinter(msg),
+            &creation_fee,
+        )
+        .unwrap();
+}
+
+#[test]
+fn claim_window_reserves_member_allocations() {
+    let mut router = custom_mock_app();
+    setup_block_time(&mut router, GENESIS_MINT_START_TIME - 1, None);
+    let num_tokens = 5;
+    let (creator, buyer) = setup_accounts(&mut router, num_tokens);
+    let whitelist_addr = setup_whitelist_contract(&mut router, &creator);
+    let (factory_addr, sg721_code_id) = setup_factory_contract(&mut router, &creator);
+    let creation_fee = coins(CREATION_FEE_PER_TOKEN * num_tokens as u128, NATIVE_DENOM);
+
+    // Both members are guaranteed 2 mints for the first 100 seconds of the whitelist
+    router
+        .execute_contract(
+            creator.clone(),
+            whitelist_addr.clone(),
+            &WhitelistExecuteMsg::AddMembers(AddMembersMsg {
+                to_add: vec![buyer.to_string(), "member2".to_string()],
+            }),
+            &[],
+        )
+        .unwrap();
+    router
+        .execute_contract(
+            creator.clone(),
+            whitelist_addr.clone(),
+            &WhitelistExecuteMsg::UpdatePerAddressLimit(5),
+            &[],
+        )
+        .unwrap();
+
+    let mut msg = create_minter_msg(&creator, sg721_code_id, num_tokens);
+    msg.init_msg.mint_price = coin(MINT_PRICE, NATIVE_DENOM);
+    msg.init_msg.whitelist = Some(whitelist_addr.to_string());
+    msg.init_msg.claim_allocation = Some(2);
+    msg.init_msg.claim_window_secs = Some(100);
+    let res = router
+        .execute_contract(
+            creator.clone(),
+            factory_addr,
+            &Sg2ExecuteMsg::CreateMinter(msg),
+            &creation_fee,
+        )
+        .unwrap();
+    let minter_addr = minter_addr_from(&res);
+    let mint_msg = ExecuteMsg::Mint {
+        referral: None,
+        recipient: None,
+    };
+
+    // The member claims within the window, up to the allocation
+    setup_block_time(&mut router, GENESIS_MINT_START_TIME + 200, None);
+    for _ in 0..2 {
+        router
+            .execute_contract(
+                buyer.clone(),
+                minter_addr.clone(),
+                &mint_msg,
+                &coins(WHITELIST_AMOUNT, NATIVE_DENOM),
+            )
+            .unwrap();
+    }
+    let err = router
+        .execute_contract(
+            buyer.clone(),
+            minter_addr.clone(),
+            &mint_msg,
+            &coins(WHITELIST_AMOUNT, NATIVE_DENOM),
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.source().unwrap().to_string(),
+        ContractError::ClaimAllocationExceeded { allocation: 2 }.to_string()
+    );
+
+    let res: ClaimAllocationResponse = router
+        .wrap()
+        .query_wasm_smart(
+            &minter_addr,
+            &QueryMsg::ClaimAllocation {
+                address: buyer.to_string(),
+            },
+        )
+        .unwrap();
+    assert_eq!(res.claimed, 2);
+    assert_eq!(res.total_claimed, 2);
+    // member2 hasn't claimed yet
+    assert_eq!(res.unclaimed_reserve, 2);
+
+    // The window closes with the whitelist, so the public isn't held back by the reserve
+    let public = Addr::unchecked("public");
+    router
+        .sudo(SudoMsg::Bank(BankSudo::Mint {
+            to_address: public.to_string(),
+            amount: coins(INITIAL_BALANCE, NATIVE_DENOM),
+        }))
+        .unwrap();
+    setup_block_time(&mut router, GENESIS_MINT_START_TIME + 1_000_000_000, None);
+    let res: ClaimAllocationResponse = router
+        .wrap()
+        .query_wasm_smart(
+            &minter_addr,
+            &QueryMsg::ClaimAllocation {
+                address: buyer.to_string(),
+            },
+        )
+        .unwrap();
+    assert_eq!(res.unclaimed_reserve, 0);
+    assert_eq!(
+        res.window_end,
+        Some(Timestamp::from_nanos(GENESIS_MINT_START_TIME + 10000000))
+    );
+    for _ in 0..3 {
+        router
+            .execute_contract(
+                public.clone(),
+                minter_addr.clone(),
+                &mint_msg,
+                &coins(MINT_PRICE, NATIVE_DENOM),
+            )
+            .unwrap();
+    }
+}
+
+#[test]
+fn claim_allocation_must_fit() {
+    let mut router = custom_mock_app();
+    setup_block_time(&mut router, GENESIS_MINT_START_TIME - 1, None);
+    let num_tokens = 5;
+    let (creator, buyer) = setup_accounts(&mut router, num_tokens);
+    let whitelist_addr = setup_whitelist_contract(&mut router, &creator);
+    let (factory_addr, sg721_code_id) = setup_factory_contract(&mut router, &creator);
+    let creation_fee = coins(CREATION_FEE_PER_TOKEN * num_tokens as u128, NATIVE_DENOM);
+    router
+        .execute_contract(
+            creator.clone(),
+            whitelist_addr.clone(),
+            &WhitelistExecuteMsg::AddMembers(AddMembersMsg {
+                to_add: vec![buyer.to_string(), "member2".to_string()],
+            }),
+            &[],
+        )
+        .unwrap();
+    router
+        .execute_contract(
+            creator.clone(),
+            whitelist_addr.clone(),
+            &WhitelistExecuteMsg::UpdatePerAddressLimit(5),
+            &[],
+        )
+        .unwrap();
+
+    let mut msg = create_minter_msg(&creator, sg721_code_id, num_tokens);
+    msg.init_msg.mint_price = coin(MINT_PRICE, NATIVE_DENOM);
+    msg.init_msg.whitelist = Some(whitelist_addr.to_string());
+    msg.init_msg.claim_window_secs = Some(100);
+
+    // Above the whitelist per address limit
+    msg.init_msg.claim_allocation = Some(6);
+    let err = router
+        .execute_contract(
+            creator.clone(),
+            factory_addr.clone(),
+            &Sg2ExecuteMsg::CreateMinter(msg.clone()),
+            &creation_fee,
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::InvalidClaimAllocation { allocation: 6 }.to_string(),
+        err.source().unwrap().source().unwrap().to_string()
+    );
+
+    // 2 members can't each claim 3 of the 5 tokens
+    msg.init_msg.claim_allocation = Some(3);
+    let err = router
+        .execute_contract(
+            creator,
+            factory_addr,
+            &Sg2ExecuteMsg::CreateMinter(msg),
+            &creation_fee,
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::InvalidClaimAllocation { allocation: 3 }.to_string(),
+        err.source().unwrap().source().unwrap().to_string()
+    );
+}
+
+#[test]
+fn mint_with_alt_price_denom() {
+    let mut router = custom_mock_app();
+    setup_block_time(&mut router, GENESIS_MINT_START_TIME - 1, None);
+    let num_tokens = 10;
+    let (creator, buyer) = setup_accounts(&mut router, num_tokens);
+    let (factory_addr, sg721_code_id) = setup_factory_contract(&mut router, &creator);
+    let creation_fee = coins(CREATION_FEE_PER_TOKEN * num_tokens as u128, NATIVE_DENOM);
+    let usdc = "ibc/D189335C6E4A68B513C10AB227BF1C1D38C746766278BA3EEB4FB14124F1D858";
+    let usdc_price = 10_000_000;
+
+    let mut msg = create_minter_msg(&creator, sg721_code_id, num_tokens);
+    msg.init_msg.mint_price = coin(MINT_PRICE, NATIVE_DENOM);
+
+    // Alternative prices go through the same floor and ceiling as the mint price,
+    // the ceiling only applies in its own denom
+    let update_msg = serial_print_factory::msg::SudoMsg::UpdateParams(Box::new(
+        serial_print_factory::msg::VendingUpdateParamsMsg {
+            code_id: None,
+            creation_fee: None,
+            min_mint_price: None,
+            mint_fee_bps: None,
+            max_trading_offset_secs: None,
+            extension: serial_print_factory::msg::VendingUpdateParamsExtension {
+                max_per_address_limit: None,
+                airdrop_mint_price: None,
+                airdrop_mint_fee_bps: None,
+                require_explicit_content: None,
+                max_start_offset_secs: None,
+                max_mint_price: Some(coin(10 * usdc_price, usdc)),
+                max_royalty_bps: None,
+                max_token_limit: None,
+            },
+        },
+    ));
+    router.wasm_sudo(factory_addr.clone(), &update_msg).unwrap();
+    msg.init_msg.alt_mint_prices = Some(vec![coin(100 * usdc_price, usdc)]);
+    let err = router
+        .execute_contract(
+            creator.clone(),
+            factory_addr.clone(),
+            &Sg2ExecuteMsg::CreateMinter(msg.clone()),
+            &creation_fee,
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::MintPriceTooHigh {
+            max: 10 * usdc_price,
+            got: 100 * usdc_price
+        }
+        .to_string(),
+        err.source().unwrap().source().unwrap().to_string()
+    );
+    msg.init_msg.alt_mint_prices = Some(vec![coin(usdc_price, usdc)]);
+    msg.init_msg.min_mint_price = Some(coin(2 * usdc_price, usdc));
+    let err = router
+        .execute_contract(
+            creator.clone(),
+            factory_addr.clone(),
+            &Sg2ExecuteMsg::CreateMinter(msg.clone()),
+            &creation_fee,
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::InsufficientMintPrice {
+            expected: 2 * usdc_price,
+            got: usdc_price
+        }
+        .to_string(),
+        err.source().unwrap().source().unwrap().to_string()
+    );
+
+    msg.init_msg.min_mint_price = None;
+    let res = router
+        .execute_contract(
+            creator.clone(),
+            factory_addr,
+            &Sg2ExecuteMsg::CreateMinter(msg),
+            &creation_fee,
+        )
+        .unwrap();
+    let minter_addr = minter_addr_from(&res);
+
+    let res: AcceptedDenomsResponse = router
+        .wrap()
+        .query_wasm_smart(&minter_addr, &QueryMsg::AcceptedDenoms {})
+        .unwrap();
+    assert_eq!(
+        res.prices,
+        vec![coin(MINT_PRICE, NATIVE_DENOM), coin(usdc_price, usdc)]
+    );
+
+    router
+        .sudo(SudoMsg::Bank(BankSudo::Mint {
+            to_address: buyer.to_string(),
+            amount: vec![coin(usdc_price, usdc), coin(MINT_PRICE, "uatom")],
+        }))
+        .unwrap();
+    setup_block_time(&mut router, GENESIS_MINT_START_TIME + 1, None);
+    let mint_msg = ExecuteMsg::Mint {
+        referral: None,
+        recipient: None,
+    };
+
+    let err = router
+        .execute_contract(
+            buyer.clone(),
+            minter_addr.clone(),
+            &mint_msg,
+            &coins(MINT_PRICE, "uatom"),
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.source().unwrap().to_string(),
+        ContractError::UnsupportedDenom {}.to_string()
+    );
+
+    // Either denom pays for a mint, the proceeds are forwarded in the denom paid
+    router
+        .execute_contract(
+            buyer.clone(),
+            minter_addr.clone(),
+            &mint_msg,
+            &coins(usdc_price, usdc),
+        )
+        .unwrap();
+    let usdc_fee = usdc_price * MINT_FEE_BPS as u128 / 10_000;
+    let creator_usdc = router.wrap().query_balance(&creator, usdc).unwrap();
+    assert_eq!(creator_usdc.amount.u128(), usdc_price - usdc_fee);
+
+    router
+        .execute_contract(
+            buyer,
+            minter_addr.clone(),
+            &mint_msg,
+            &coins(MINT_PRICE, NATIVE_DENOM),
+        )
+        .unwrap();
+
+    // Fees and payouts are tracked per denom
+    let res: NetworkFeesResponse = router
+        .wrap()
+        .query_wasm_smart(&minter_addr, &QueryMsg::NetworkFees {})
+        .unwrap();
+    assert_eq!(
+        res.fees,
+        vec![coin(usdc_fee, usdc), coin(MINT_FEE, NATIVE_DENOM)]
+    );
+    let res: PayoutTotalsResponse = router
+        .wrap()
+        .query_wasm_smart(minter_addr, &QueryMsg::PayoutTotals {})
+        .unwrap();
+    assert_eq!(
+        res.totals,
+        vec![
+            PayoutTotal {
+                address: creator.to_string(),
+                amount: coin(usdc_price - usdc_fee, usdc),
+            },
+            PayoutTotal {
+                address: creator.to_string(),
+                amount: coin(MINT_PRICE - MINT_FEE, NATIVE_DENOM),
+            },
+        ]
+    );
+}
+
+#[test]
+fn presale_refund_deposit_after_sell_out() {
+    let mut router = custom_mock_app();
+    let num_tokens = 1;
+    let (creator, buyer) = setup_accounts(&mut router, num_tokens);
+    let minter_addr = setup_presale_minter(&mut router, &creator, num_tokens);
+
+    setup_block_time(&mut router, GENESIS_MINT_START_TIME - 100, None);
+    router
+        .execute_contract(
+            buyer.clone(),
+            minter_addr.clone(),
+            &ExecuteMsg::Deposit {},
+            &coins(MIN_MINT_PRICE, NATIVE_DENOM),
+        )
+        .unwrap();
+
+    // Someone else buys the only token before the depositor claims
+    let other = Addr::unchecked("other");
+    router
+        .sudo(SudoMsg::Bank(BankSudo::Mint {
+            to_address: other.to_string(),
+            amount: coins(MIN_MINT_PRICE, NATIVE_DENOM),
+        }))
+        .unwrap();
+    setup_block_time(&mut router, GENESIS_MINT_START_TIME + 1, None);
+    router
+        .execute_contract(
+            other,
+            minter_addr.clone(),
+            &ExecuteMsg::Mint {
+                referral: None,
+                recipient: None,
+            },
+            &coins(MIN_MINT_PRICE, NATIVE_DENOM),
+        )
+        .unwrap();
+
+    let err = router
+        .execute_contract(
+            buyer.clone(),
+            minter_addr.clone(),
+            &ExecuteMsg::ClaimMint {},
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::SoldOut {}.to_string(),
+        err.source().unwrap().to_string()
+    );
+
+    // The unclaimed deposit is still refundable after the start
+    router
+        .execute_contract(
+            buyer.clone(),
+            minter_addr.clone(),
+            &ExecuteMsg::RefundDeposit {},
+            &[],
+        )
+        .unwrap();
+    let balance = router.wrap().query_balance(&buyer, NATIVE_DENOM).unwrap();
+    assert_eq!(balance.amount.u128(), INITIAL_BALANCE);
+    let balance = router
+        .wrap()
+        .query_balance(&minter_addr, NATIVE_DENOM)
+        .unwrap();
+    assert!(balance.amount.is_zero());
+}
@@ -1,6 +1,11 @@
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Coin, Timestamp};
-use serial_print_factory::{msg::VendingMinterCreateMsg, state::VendingMinterParams};
+use cosmwasm_std::{Coin, Decimal, HexBinary, Timestamp, Uint128};
+use serial_print_factory::{
+    msg::{VendingMinterCreateMsg, VestingConfig},
+    state::VendingMinterParams,
+};
+
+use crate::state::{MintReceipt, PayoutSplit, ProceedsTarget};
 
 #[cw_serde]
 pub struct InstantiateMsg {
@@ -10,11 +15,16 @@ pub struct InstantiateMsg {
 
 #[cw_serde]
 pub enum ExecuteMsg {
-    Mint {},
+    Mint {
+        referral: Option<String>,
+        /// Address receiving the token, defaults to the sender who pays for it
+        recipient: Option<String>,
+    },
     SetWhitelist {
         whitelist: String,
     },
     Purge {},
+    /// Updates the price in the mint price denom. Alternative prices are fixed at instantiate.
     UpdateMintPrice {
         price: u128,
     },
@@ -31,6 +41,15 @@ pub enum ExecuteMsg {
         token_id: u32,
         recipient: String,
     },
+    /// Stores the recipients of an airdrop that is too large for one transaction, at most 500
+    LoadAirdrop {
+        recipients: Vec<String>,
+    },
+    /// Mints one token to each of the next `count` airdrop recipients.
+    /// `count` is capped at the recipients left, the payment is the airdrop price for each mint.
+    ContinueAirdrop {
+        count: u32,
+    },
     BurnRemaining {},
     SetTokenUri {
         uri: String,
@@ -39,21 +58,173 @@ pub enum ExecuteMsg {
     SetMintingPause {
         pause: bool,
     },
+    /// Adds `additional_tokens` new ids after the current max, e.g. to re-drop a sold out collection
+    ReopenMint {
+        additional_tokens: u32,
+        new_base_token_uri: Option<String>,
+    },
+    /// Sends the proceeds held by the minter to the payment address
+    Withdraw {},
+    BatchMint {
+        num_mints: u32,
+    },
+    SetBatchMintEnabled {
+        enabled: bool,
+    },
+    /// Deposits the sent funds towards a mint, before the start time with `presale_deposits`
+    Deposit {},
+    /// Mints one token paid for by the sender's deposit and refunds the rest of it
+    ClaimMint {},
+    /// Returns the sender's unclaimed deposit
+    RefundDeposit {},
+    /// Burns a token owned by the sender and mints the next mintable id in its place.
+    /// The sender must approve the minter for `token_id` and pay the reroll fee.
+    Reroll {
+        token_id: u32,
+    },
+    /// Takes token ids out of the mintable pool
+    ReserveTokens {
+        token_ids: Vec<u32>,
+    },
+    /// Returns reserved token ids to the mintable pool
+    ReleaseTokens {
+        token_ids: Vec<u32>,
+    },
+    /// Re-issues the collection instantiation of a minter left without a collection
+    /// after it failed with the original code id
+    RetryCollectionInstantiate {
+        sg721_code_id: u64,
+    },
+    /// Mint against an allocation proven by a Merkle proof of the `sender:allocation` leaf
+    MerkleMint {
+        allocation: u32,
+        proof: Vec<HexBinary>,
+    },
+    /// Marks the collection as revealed, releasing the held proceeds
+    Reveal {},
+    /// Burns a token and refunds its proceeds once the reveal deadline passed without a reveal.
+    /// The sender must approve the minter for `token_id`.
+    ClaimRefund {
+        token_id: u32,
+    },
+    /// Overrides the collection royalty for a single token of the current round.
+    /// The share is capped by the factory `max_royalty_bps`. Query the collection for the royalty.
+    SetTokenRoyalty {
+        token_id: u32,
+        share: Decimal,
+        recipient: String,
+    },
+    /// Pins the current uri of a mintable token, so later base uri updates don't change it
+    FreezeTokenUri {
+        token_id: u32,
+    },
+    /// Closes the minter for good before anything is minted, including a creator reserve.
+    /// Presale deposits stay refundable with `RefundDeposit`.
+    CancelLaunch {},
+    /// Starts the delay before the collection info can be frozen
+    RequestFreeze {},
+    /// Freezes the collection info once the freeze delay has elapsed
+    ConfirmFreeze {},
+    CancelFreeze {},
+}
+
+/// Governance messages. `UpdateStatus` matches `sg4::SudoMsg`.
+#[cw_serde]
+pub enum SudoMsg {
+    UpdateStatus {
+        is_verified: bool,
+        is_blocked: bool,
+        is_explicit: bool,
+    },
+    /// Rejects every mint and withdraw, admin ones included, while set.
+    /// Unlike `SetMintingPause` the admin can't lift it.
+    UpdateFullyFrozen { fully_frozen: bool },
 }
 
 #[cw_serde]
 pub enum QueryMsg {
     Config {},
     MintableNumTokens {},
+    /// `MINTABLE_NUM_TOKENS` next to the number of stored mintable ids.
+    /// Iterates every mintable id, so it can run out of gas on large collections.
+    SupplyConsistency {},
     StartTime {},
     MintPrice {},
-    MintCount { address: String },
+    MintCount {
+        address: String,
+    },
+    /// Mint counts of up to 50 addresses
+    MintCounts {
+        addresses: Vec<String>,
+    },
     Status {},
+    Phases {},
+    UniqueMinters {},
+    MintHistory {
+        start_after: Option<u32>,
+        limit: Option<u32>,
+    },
+    ReferralRewards {
+        address: String,
+    },
+    /// Token id the next public mint would assign, `None` when sold out
+    NextTokenId {},
+    /// Whether `token_id` can still be minted, takes the absolute id like `NextTokenId`
+    IsTokenMintable {
+        token_id: u32,
+    },
+    /// Current price and whether minting is open, personalized when `address` is set
+    MintInfo {
+        address: Option<String>,
+    },
+    /// Whether `address` could mint at `at` and at what price, using the current mint counts and height
+    EligibilityAt {
+        address: String,
+        at: Timestamp,
+    },
+    /// Whether `address` can batch mint `num_mints` tokens right now
+    CanBatchMint {
+        address: String,
+        num_mints: u32,
+    },
+    ReservedTokens {
+        start_after: Option<u32>,
+        limit: Option<u32>,
+    },
+    /// Per-address limit that applies at the current block time
+    EffectivePerAddressLimit {},
+    /// Price, per-address limit and allowlist requirement that apply at the current block time
+    CurrentPhase {},
+    /// Whether only whitelist members can ever mint, because the whitelist never expires
+    AllowlistOnly {},
+    /// Denoms accepted for minting, with the current price in each
+    AcceptedDenoms {},
+    Admin {},
+    /// cw2 contract name and version, returns `cw2::ContractVersion`
+    ContractVersion {},
+    /// Total network fees paid by mints in each denom.
+    /// Native fees are half burned and half sent to the fair burn pool, other denoms fund the pool.
+    NetworkFees {},
+    /// Proceeds paid out so far to each payout recipient, one entry per denom
+    PayoutTotals {},
+    /// Generative seed of a token, `None` when the collection isn't generative
+    TokenSeed {
+        token_id: u32,
+    },
+    /// Height and price of a token's mint, `None` when it wasn't recorded
+    MintReceipt {
+        token_id: u32,
+    },
+    /// Claimed and reserved mints of the whitelist claim window
+    ClaimAllocation {
+        address: String,
+    },
 }
 
 #[cw_serde]
 pub struct ConfigResponse {
     pub admin: String,
+    pub creator: String,
     pub base_token_uri: String,
     pub num_tokens: u32,
     pub per_address_limit: u32,
@@ -63,6 +234,40 @@ pub struct ConfigResponse {
     pub mint_price: Coin,
     pub whitelist: Option<String>,
     pub factory: String,
+    pub soft_cap: Option<u32>,
+    pub batch_mint_enabled: bool,
+    pub record_history: bool,
+    pub mint_to_allowlist: Option<Vec<String>>,
+    pub vesting: Option<VestingConfig>,
+    pub referral_bps: Option<u64>,
+    pub whitelist_total_cap: Option<u32>,
+    pub reroll_fee: Option<Coin>,
+    pub allocation_merkle_root: Option<HexBinary>,
+    pub global_mint_interval_secs: Option<u64>,
+    pub auto_withdraw_threshold: Option<Uint128>,
+    pub allow_contract_recipients: bool,
+    pub public_on_whitelist_sellout: bool,
+    pub reveal_deadline: Option<Timestamp>,
+    pub payout_splits: Option<Vec<PayoutSplit>>,
+    pub freeze_delay_secs: u64,
+    pub token_uri_suffix: Option<String>,
+    pub reset_limit_on_public: bool,
+    pub min_mint_price: Option<Coin>,
+    pub display_denom: String,
+    pub exponent: u32,
+    pub block_privileged_referrals: bool,
+    pub early_per_address_limit: Option<u32>,
+    pub early_window_secs: Option<u64>,
+    pub start_time_update_cooldown_secs: Option<u64>,
+    pub presale_deposits: bool,
+    pub record_receipts: bool,
+    pub require_height_gate: Option<u64>,
+    pub daily_mint_limit: Option<u32>,
+    pub proceeds_target: Option<ProceedsTarget>,
+    pub generative: bool,
+    pub claim_allocation: Option<u32>,
+    pub claim_window_secs: Option<u64>,
+    pub alt_mint_prices: Vec<Coin>,
 }
 
 #[cw_serde]
@@ -70,6 +275,12 @@ pub struct MintableNumTokensResponse {
     pub count: u32,
 }
 
+#[cw_serde]
+pub struct SupplyConsistencyResponse {
+    pub mintable_num_tokens: u32,
+    pub mintable_token_ids: u32,
+}
+
 #[cw_serde]
 pub struct StartTimeResponse {
     pub start_time: String,
@@ -81,6 +292,155 @@ pub struct MintPriceResponse {
     pub airdrop_price: Coin,
     pub whitelist_price: Option<Coin>,
     pub current_price: Coin,
+    /// Prices are shown as `amount / 10^exponent` `display_denom`
+    pub display_denom: String,
+    pub exponent: u32,
+}
+
+#[cw_serde]
+pub struct PhaseResponse {
+    pub index: u32,
+    pub start_time: Timestamp,
+    pub price: Coin,
+    pub per_address_limit: u32,
+    pub requires_allowlist: bool,
+    pub allowlist: Option<String>,
+    /// Whether this is the phase running at the current block time
+    pub active: bool,
+}
+
+#[cw_serde]
+pub struct PhasesResponse {
+    pub phases: Vec<PhaseResponse>,
+}
+
+#[cw_serde]
+pub struct MintEvent {
+    pub token_id: u32,
+    pub minter: String,
+    pub time: Timestamp,
+}
+
+#[cw_serde]
+pub struct MintHistoryResponse {
+    pub mints: Vec<MintEvent>,
+}
+
+#[cw_serde]
+pub struct ClaimAllocationResponse {
+    pub allocation: u32,
+    pub claimed: u32,
+    /// Allocation of all members not claimed yet, held back from public mints
+    pub unclaimed_reserve: u32,
+    pub total_claimed: u32,
+    /// `None` when there is no claim window
+    pub window_end: Option<Timestamp>,
+}
+
+#[cw_serde]
+pub struct TokenSeedResponse {
+    pub token_id: u32,
+    pub seed: Option<HexBinary>,
+}
+
+#[cw_serde]
+pub struct MintReceiptResponse {
+    pub token_id: u32,
+    pub receipt: Option<MintReceipt>,
+}
+
+#[cw_serde]
+pub struct PayoutTotal {
+    pub address: String,
+    pub amount: Coin,
+}
+
+#[cw_serde]
+pub struct PayoutTotalsResponse {
+    pub totals: Vec<PayoutTotal>,
+}
+
+#[cw_serde]
+pub struct NetworkFeesResponse {
+    pub fees: Vec<Coin>,
+}
+
+#[cw_serde]
+pub struct AdminResponse {
+    pub admin: String,
+}
+
+#[cw_serde]
+pub struct AcceptedDenomsResponse {
+    pub prices: Vec<Coin>,
+}
+
+#[cw_serde]
+pub struct AllowlistOnlyResponse {
+    pub allowlist_only: bool,
+}
+
+#[cw_serde]
+pub struct CurrentPhaseResponse {
+    /// Whether only allowlist or whitelist members can mint
+    pub requires_allowlist: bool,
+    pub price: Coin,
+    pub per_address_limit: u32,
+}
+
+#[cw_serde]
+pub struct EffectivePerAddressLimitResponse {
+    pub per_address_limit: u32,
+}
+
+#[cw_serde]
+pub struct ReservedTokensResponse {
+    pub token_ids: Vec<u32>,
+}
+
+#[cw_serde]
+pub struct UniqueMintersResponse {
+    pub count: u32,
+}
+
+#[cw_serde]
+pub struct MintInfoResponse {
+    pub current_price: Coin,
+    pub can_mint: bool,
+    /// Why minting is not possible, when `can_mint` is false
+    pub reason: Option<String>,
+    pub remaining_for_address: Option<u32>,
+}
+
+#[cw_serde]
+pub struct EligibilityAtResponse {
+    pub eligible: bool,
+    pub price: Coin,
+    /// Why the address couldn't mint, when `eligible` is false
+    pub reason: Option<String>,
+}
+
+#[cw_serde]
+pub struct CanBatchMintResponse {
+    pub allowed: bool,
+    /// Why the batch mint would fail, when `allowed` is false
+    pub reason: Option<String>,
+}
+
+#[cw_serde]
+pub struct NextTokenIdResponse {
+    pub token_id: Option<u32>,
+}
+
+#[cw_serde]
+pub struct IsTokenMintableResponse {
+    pub mintable: bool,
+}
+
+#[cw_serde]
+pub struct ReferralRewardsResponse {
+    pub address: String,
+    pub rewards: Vec<Coin>,
 }
 
 #[cw_serde]
@@ -1,33 +1,180 @@
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Addr, Timestamp};
+use cosmwasm_std::{Addr, Binary, Coin, HexBinary, Timestamp, Uint128};
 use cw_storage_plus::{Item, Map};
+use serial_print_factory::msg::VestingConfig;
 use sg4::{MinterConfig, Status};
+use sg721::InstantiateMsg as Sg721InstantiateMsg;
 
 #[cw_serde]
 pub struct ConfigExtension {
     pub admin: Addr,
+    /// Collection creator, forwarded to the collection info. `admin` keeps operational control.
+    pub creator: Addr,
     pub payment_address: Option<Addr>,
     pub base_token_uri: String,
     pub num_tokens: u32,
     pub whitelist: Option<Addr>,
     pub start_time: Timestamp,
     pub per_address_limit: u32,
+    pub soft_cap: Option<u32>,
+    pub batch_mint_enabled: bool,
+    pub record_history: bool,
+    pub mint_to_allowlist: Option<Vec<Addr>>,
+    pub vesting: Option<VestingConfig>,
+    pub referral_bps: Option<u64>,
+    pub whitelist_total_cap: Option<u32>,
+    pub reroll_fee: Option<Coin>,
+    pub allocation_merkle_root: Option<HexBinary>,
+    pub global_mint_interval_secs: Option<u64>,
+    pub auto_withdraw_threshold: Option<Uint128>,
+    pub allow_contract_recipients: bool,
+    pub public_on_whitelist_sellout: bool,
+    pub reveal_deadline: Option<Timestamp>,
+    pub payout_splits: Option<Vec<PayoutSplit>>,
+    pub freeze_delay_secs: u64,
+    pub token_uri_suffix: Option<String>,
+    pub reset_limit_on_public: bool,
+    pub min_mint_price: Option<Coin>,
+    pub display_denom: String,
+    pub exponent: u32,
+    pub block_privileged_referrals: bool,
+    pub early_per_address_limit: Option<u32>,
+    pub early_window_secs: Option<u64>,
+    pub start_time_update_cooldown_secs: Option<u64>,
+    pub presale_deposits: bool,
+    pub record_receipts: bool,
+    pub require_height_gate: Option<u64>,
+    pub daily_mint_limit: Option<u32>,
+    pub proceeds_target: Option<ProceedsTarget>,
+    pub generative: bool,
+    pub claim_allocation: Option<u32>,
+    pub claim_window_secs: Option<u64>,
+    pub alt_mint_prices: Vec<Coin>,
 }
 pub type Config = MinterConfig<ConfigExtension>;
 
+/// Share of the proceeds paid to `address`, in bps
+#[cw_serde]
+pub struct PayoutSplit {
+    pub address: Addr,
+    pub bps: u64,
+}
+
+/// Receiver of the proceeds in place of the payment address
+#[cw_serde]
+pub enum ProceedsTarget {
+    Bank {
+        address: Addr,
+    },
+    /// Executes `msg` on `contract` with the proceeds as funds
+    Wasm {
+        contract: Addr,
+        msg: Binary,
+    },
+}
+
+/// Block height and unit price of a mint
+#[cw_serde]
+pub struct MintReceipt {
+    pub height: u64,
+    pub price: Coin,
+}
+
+/// A mint phase runs from its `start_time` until the next phase starts.
+/// `per_address_limit` caps the total mints of an address up to and including this phase.
+#[cw_serde]
+pub struct Phase {
+    pub start_time: Timestamp,
+    pub price: Coin,
+    pub per_address_limit: u32,
+    pub allowlist: Option<Addr>,
+}
+
 pub const CONFIG: Item<Config> = Item::new("config");
 pub const SG721_ADDRESS: Item<Addr> = Item::new("sg721_address");
+/// The collection instantiate msg, kept until the collection is instantiated so a
+/// failed instantiation can be retried with another code id
+pub const COLLECTION_INSTANTIATE_MSG: Item<Sg721InstantiateMsg> = Item::new("collection_msg");
 // map of token ids. Bool is just a placeholder
 pub const MINTABLE_TOKEN_IDS: Map<u32, bool> = Map::new("mt");
 pub const MINTABLE_NUM_TOKENS: Item<u32> = Item::new("mintable_num_tokens");
+/// Token ids held back from the mintable pool by the admin
+pub const RESERVED_TOKEN_IDS: Map<u32, bool> = Map::new("rt");
 pub const MINTER_ADDRS: Map<&Addr, u32> = Map::new("ma");
+/// Public mints per address, only written when `reset_limit_on_public` is enabled
+pub const PUBLIC_MINTER_ADDRS: Map<&Addr, u32> = Map::new("pma");
+/// Number of distinct addresses in `MINTER_ADDRS`
+pub const UNIQUE_MINTERS: Item<u32> = Item::new("unique_minters");
+/// Number of leading token ids minted to the creator once the collection is created
+pub const CREATOR_RESERVE: Item<u32> = Item::new("creator_reserve");
+/// Number of tokens minted while the whitelist was active
+pub const WHITELIST_MINTED: Item<u32> = Item::new("whitelist_minted");
+/// Set once the whitelist sells out with `public_on_whitelist_sellout`.
+/// Ends the whitelist and opens the public mint regardless of the start time.
+pub const EARLY_PUBLIC: Item<bool> = Item::new("early_public");
+/// Set by the first public mint after the whitelist ended
+pub const PUBLIC_OPENED: Item<bool> = Item::new("public_opened");
+/// Total rewards paid to each referrer, per denom
+pub const REFERRAL_REWARDS: Map<(&Addr, &str), Uint128> = Map::new("rr");
+/// Token uris frozen before mint, keyed by collection token id. Base uri updates don't apply to them.
+pub const FROZEN_TOKEN_URIS: Map<u32, String> = Map::new("ftu");
+/// Total proceeds paid out to each recipient, per denom
+pub const PAYOUT_TOTALS: Map<(&Addr, &str), Uint128> = Map::new("pt");
+/// Proceeds already withdrawn by the admin
+pub const WITHDRAWN: Item<Uint128> = Item::new("withdrawn");
+/// Network fees sent to fair burn by mints, per denom
+pub const NETWORK_FEES: Map<&str, Uint128> = Map::new("nf");
+/// Minter and block time of each token id, only written when `record_history` is enabled
+pub const MINT_EVENTS: Map<u32, (Addr, Timestamp)> = Map::new("me");
+/// Receipt of each token id, only written when `record_receipts` is enabled
+pub const MINT_RECEIPTS: Map<u32, MintReceipt> = Map::new("mrc");
+/// Seed of each token id, only written for `generative` collections
+pub const TOKEN_SEEDS: Map<u32, HexBinary> = Map::new("ts");
+/// Time the admin requested to freeze the collection info
+pub const FREEZE_REQUESTED_AT: Item<Timestamp> = Item::new("freeze_requested_at");
+pub const REVEALED: Item<bool> = Item::new("revealed");
+/// Proceeds refundable per token id if the collection misses its reveal deadline
+pub const MINT_REFUNDS: Map<u32, Uint128> = Map::new("mr");
+/// Mint phases ordered by start time. Empty when the minter uses whitelist / public pricing.
+pub const PHASES: Item<Vec<Phase>> = Item::new("phases");
+/// Total number of tokens minted through the minter, across all rounds, creator reserve included
+pub const TOTAL_MINTED: Item<u32> = Item::new("total_minted");
+/// Number of paid mints, which is what counts toward the soft cap
+pub const PAID_MINTED: Item<u32> = Item::new("paid_minted");
+/// Day number and public mint count of an address on that day, for `daily_mint_limit`
+pub const DAILY_MINTS: Map<&Addr, (u64, u32)> = Map::new("dm");
+/// Time of the last public mint, used to pace mints with `global_mint_interval_secs`
+pub const LAST_GLOBAL_MINT: Item<Timestamp> = Item::new("last_global_mint");
+/// Presale deposits in the mint price denom, spent by `ClaimMint` or returned by `RefundDeposit`
+pub const DEPOSITS: Map<&Addr, Uint128> = Map::new("dep");
+/// Sum of `DEPOSITS`, held back from withdrawals
+pub const TOTAL_DEPOSITS: Item<Uint128> = Item::new("total_deposits");
+/// Mints claimed by each whitelist member during the claim window
+pub const CLAIMED_ALLOCATIONS: Map<&Addr, u32> = Map::new("ca");
+/// Sum of `CLAIMED_ALLOCATIONS`
+pub const TOTAL_CLAIMED: Item<u32> = Item::new("total_claimed");
+/// Time of the last start time update, used with `start_time_update_cooldown_secs`
+pub const LAST_START_TIME_UPDATE: Item<Timestamp> = Item::new("last_start_time_update");
 
 /// Holds the status of the minter. Can be changed with on-chain governance proposals.
 pub const STATUS: Item<Status> = Item::new("status");
 
+/// Recipients of the airdrop loaded with `LoadAirdrop` by position, removed once minted
+pub const AIRDROP_RECIPIENTS: Map<u32, Addr> = Map::new("ar");
+/// Number of recipients loaded with `LoadAirdrop`
+pub const AIRDROP_LEN: Item<u32> = Item::new("airdrop_len");
+/// Index of the next airdrop recipient
+pub const AIRDROP_CURSOR: Item<u32> = Item::new("airdrop_cursor");
+
 /// Set New URI
 pub const BASE_TOKEN_ID: Item<u32> = Item::new("base_token_id");
+/// Tokens minted before the current round, counted when a new round starts with `SetTokenUri`.
+/// `num_tokens` minus this is the size of the current round.
 pub const MINTED_NUM_TOKENS: Item<u32> = Item::new("minted_num_tokens");
 
 /// Set Pause
 pub const MINTING_PAUSED: Item<bool> = Item::new("mintable on/off");
+/// Set by `CancelLaunch`, closes the minter for good
+pub const LAUNCH_CANCELLED: Item<bool> = Item::new("launch_cancelled");
+/// Set by governance to stop all mints and withdrawals of a fraudulent collection
+pub const FULLY_FROZEN: Item<bool> = Item::new("fully_frozen");
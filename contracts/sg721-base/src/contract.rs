@@ -3,8 +3,8 @@ use cw721_base::MintMsg;
 use url::Url;
 
 use cosmwasm_std::{
-    to_binary, Binary, ContractInfoResponse, Decimal, Deps, DepsMut, Empty, Env, Event,
-    MessageInfo, StdResult, Timestamp, WasmQuery,
+    to_binary, Binary, ContractInfoResponse, Decimal, Deps, DepsMut, Env, Event, MessageInfo,
+    StdResult, Timestamp, WasmQuery,
 };
 
 use cw721::{ContractInfoResponse as CW721ContractInfoResponse, Cw721Execute};
@@ -17,7 +17,10 @@ use sg721::{
 };
 use sg_std::Response;
 
-use crate::msg::{CollectionInfoResponse, QueryMsg};
+use crate::msg::{
+    CollectionInfoResponse, ExtensionMsg, FrozenCollectionInfoResponse, QueryMsg,
+    TokenRoyaltyResponse,
+};
 use crate::{ContractError, Sg721Contract};
 
 const MAX_DESCRIPTION_LENGTH: u32 = 512;
@@ -102,7 +105,7 @@ where
         deps: DepsMut,
         env: Env,
         info: MessageInfo,
-        msg: ExecuteMsg<T, Empty>,
+        msg: ExecuteMsg<T, ExtensionMsg>,
     ) -> Result<Response, ContractError> {
         match msg {
             ExecuteMsg::TransferNft {
@@ -150,9 +153,14 @@ where
             ExecuteMsg::UpdateStartTradingTime(start_time) => {
                 self.update_start_trading_time(deps, env, info, start_time)
             }
-            ExecuteMsg::FreezeCollectionInfo {} => self.freeze_collection_info(deps, env, info),
+            ExecuteMsg::FreezeCollectionInfo => self.freeze_collection_info(deps, env, info),
             ExecuteMsg::Mint(msg) => self.mint(deps, env, info, msg),
-            ExecuteMsg::Extension { msg: _ } => todo!(),
+            ExecuteMsg::Extension { msg } => match msg {
+                ExtensionMsg::SetTokenRoyalty {
+                    token_id,
+                    royalty_info,
+                } => self.set_token_royalty(deps, env, info, token_id, royalty_info),
+            },
         }
     }
 
@@ -257,8 +265,10 @@ where
         _env: Env,
         info: MessageInfo,
     ) -> Result<Response, ContractError> {
+        // the minter can freeze on behalf of the creator, after its own checks
         let collection = self.query_collection_info(deps.as_ref())?;
-        if collection.creator != info.sender {
+        let minter = self.parent.minter.load(deps.storage)?;
+        if collection.creator != info.sender && minter != info.sender {
             return Err(ContractError::Unauthorized {});
         }
 
@@ -268,6 +278,36 @@ where
         Ok(Response::new().add_event(event))
     }
 
+    /// Called by the minter, which checks the token id and the share against its own limits
+    pub fn set_token_royalty(
+        &self,
+        deps: DepsMut,
+        _env: Env,
+        info: MessageInfo,
+        token_id: String,
+        royalty_info: RoyaltyInfoResponse,
+    ) -> Result<Response, ContractError> {
+        let minter = self.parent.minter.load(deps.storage)?;
+        if minter != info.sender {
+            return Err(ContractError::Unauthorized {});
+        }
+        if self.frozen_collection_info.load(deps.storage)? {
+            return Err(ContractError::CollectionInfoFrozen {});
+        }
+
+        let royalty_info = RoyaltyInfo {
+            payment_address: deps.api.addr_validate(&royalty_info.payment_address)?,
+            share: share_validate(royalty_info.share)?,
+        };
+        self.token_royalties
+            .save(deps.storage, &token_id, &royalty_info)?;
+
+        let event = Event::new("set_token_royalty")
+            .add_attribute("sender", info.sender)
+            .add_attribute("token_id", token_id);
+        Ok(Response::new().add_event(event))
+    }
+
     pub fn mint(
         &self,
         deps: DepsMut,
@@ -307,10 +347,32 @@ where
     pub fn query(&self, deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
         match msg {
             QueryMsg::CollectionInfo {} => to_binary(&self.query_collection_info(deps)?),
+            QueryMsg::FrozenCollectionInfo {} => to_binary(&FrozenCollectionInfoResponse {
+                frozen: self.frozen_collection_info.load(deps.storage)?,
+            }),
+            QueryMsg::TokenRoyalty { token_id } => {
+                to_binary(&self.query_token_royalty(deps, token_id)?)
+            }
             _ => self.parent.query(deps, env, msg.into()),
         }
     }
 
+    pub fn query_token_royalty(
+        &self,
+        deps: Deps,
+        token_id: String,
+    ) -> StdResult<TokenRoyaltyResponse> {
+        let royalty_info = match self.token_royalties.may_load(deps.storage, &token_id)? {
+            Some(royalty_info) => Some(royalty_info),
+            None => self.collection_info.load(deps.storage)?.royalty_info,
+        };
+
+        Ok(TokenRoyaltyResponse {
+            token_id,
+            royalty_info: royalty_info.map(|royalty_info| royalty_info.to_response()),
+        })
+    }
+
     pub fn query_collection_info(&self, deps: Deps) -> StdResult<CollectionInfoResponse> {
         let info = self.collection_info.load(deps.storage)?;
 
@@ -55,8 +55,11 @@ mod tests {
     pub const AIRDROP_MINT_PRICE: u128 = 15_000_000;
     pub const MINT_FEE_BPS: u64 = 1_000; // 10%
     pub const AIRDROP_MINT_FEE_BPS: u64 = 10_000; // 100%
-    pub const MAX_TOKEN_LIMIT: u32 = 10_000;
     pub const MAX_PER_ADDRESS_LIMIT: u32 = 50;
+    pub const MAX_MINT_PRICE: u128 = 1_000_000_000_000;
+    pub const MAX_ROYALTY_BPS: u64 = 1_000; // 10%
+    pub const MAX_TOKEN_LIMIT: u32 = 10_000;
+    pub const MAX_START_OFFSET_SECS: u64 = 5 * 365 * 24 * 60 * 60; // the mock chain starts years before genesis
 
     fn custom_mock_app() -> StargazeApp {
         StargazeApp::default()
@@ -71,6 +74,43 @@ mod tests {
             mint_price: coin(MIN_MINT_PRICE, NATIVE_DENOM),
             per_address_limit: 5,
             whitelist: None,
+            creator_reserve: None,
+            soft_cap: None,
+            phases: None,
+            record_history: None,
+            mint_to_allowlist: None,
+            vesting: None,
+            referral_bps: None,
+            whitelist_total_cap: None,
+            reroll_fee: None,
+            allocation_merkle_root: None,
+            admin: None,
+            global_mint_interval_secs: None,
+            auto_withdraw_threshold: None,
+            allow_contract_recipients: None,
+            public_on_whitelist_sellout: None,
+            reveal_deadline: None,
+            payout_splits: None,
+            freeze_delay_secs: None,
+            token_uri_suffix: None,
+            reset_limit_on_public: None,
+            min_mint_price: None,
+            display_denom: None,
+            exponent: None,
+            block_privileged_referrals: None,
+            early_per_address_limit: None,
+            early_window_secs: None,
+            start_time_update_cooldown_secs: None,
+            presale_deposits: None,
+            record_receipts: None,
+            require_height_gate: None,
+            daily_mint_limit: None,
+            proceeds_target: None,
+            generative: None,
+            reserved_token_ids: None,
+            claim_allocation: None,
+            claim_window_secs: None,
+            alt_mint_prices: None,
         }
     }
 
@@ -85,6 +125,11 @@ mod tests {
                 max_per_address_limit: MAX_PER_ADDRESS_LIMIT,
                 airdrop_mint_price: coin(AIRDROP_MINT_PRICE, NATIVE_DENOM),
                 airdrop_mint_fee_bps: AIRDROP_MINT_FEE_BPS,
+                require_explicit_content: false,
+                max_start_offset_secs: MAX_START_OFFSET_SECS,
+                max_mint_price: coin(MAX_MINT_PRICE, NATIVE_DENOM),
+                max_royalty_bps: MAX_ROYALTY_BPS,
+                max_token_limit: MAX_TOKEN_LIMIT,
             },
             max_trading_offset_secs: 60 * 60 * 24 * 7,
         }
@@ -9,7 +9,7 @@ pub use crate::state::Sg721Contract;
 use cosmwasm_std::Empty;
 use cw721_base::Extension;
 
-pub type ExecuteMsg = sg721::ExecuteMsg<Extension, Empty>;
+pub type ExecuteMsg = sg721::ExecuteMsg<Extension, msg::ExtensionMsg>;
 pub type QueryMsg = cw721_base::QueryMsg<Empty>;
 
 pub mod entry {
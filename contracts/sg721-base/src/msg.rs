@@ -43,6 +43,16 @@ pub enum ExecuteMsg<T, E> {
     Extension { msg: E },
 }
 
+/// Messages of the sg721 `Extension` variant
+#[cw_serde]
+pub enum ExtensionMsg {
+    /// Called by the minter to override the collection royalty of a single token
+    SetTokenRoyalty {
+        token_id: String,
+        royalty_info: RoyaltyInfoResponse,
+    },
+}
+
 #[cw_serde]
 pub enum QueryMsg {
     OwnerOf {
@@ -84,6 +94,12 @@ pub enum QueryMsg {
     },
     Minter {},
     CollectionInfo {},
+    /// Whether the collection info has been frozen
+    FrozenCollectionInfo {},
+    /// Royalty of a token, its override set by the minter or else the collection royalty
+    TokenRoyalty {
+        token_id: String,
+    },
 }
 
 impl From<QueryMsg> for Cw721QueryMsg<Empty> {
@@ -161,3 +177,14 @@ pub struct CollectionInfoResponse {
     pub start_trading_time: Option<Timestamp>,
     pub royalty_info: Option<RoyaltyInfoResponse>,
 }
+
+#[cw_serde]
+pub struct FrozenCollectionInfoResponse {
+    pub frozen: bool,
+}
+
+#[cw_serde]
+pub struct TokenRoyaltyResponse {
+    pub token_id: String,
+    pub royalty_info: Option<RoyaltyInfoResponse>,
+}
@@ -1,5 +1,5 @@
 use cosmwasm_std::Empty;
-use cw_storage_plus::Item;
+use cw_storage_plus::{Item, Map};
 use serde::{de::DeserializeOwned, Serialize};
 use sg721::{CollectionInfo, RoyaltyInfo};
 use sg_std::StargazeMsgWrapper;
@@ -16,6 +16,9 @@ where
 
     /// Instantiate set to false by the minter, then true by creator to freeze collection info
     pub frozen_collection_info: Item<'a, bool>,
+
+    /// Royalties of individual tokens set by the minter, overriding the collection royalty
+    pub token_royalties: Map<'a, &'a str, RoyaltyInfo>,
 }
 
 impl<'a, T> Default for Sg721Contract<'a, T>
@@ -27,6 +30,7 @@ where
             parent: cw721_base::Cw721Contract::default(),
             collection_info: Item::new("collection_info"),
             frozen_collection_info: Item::new("frozen_collection_info"),
+            token_royalties: Map::new("token_royalties"),
         }
     }
 }
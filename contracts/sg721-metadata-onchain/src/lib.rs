@@ -1,5 +1,3 @@
-use cosmwasm_std::Empty;
-
 pub use sg721_base::ContractError;
 use sg_metadata::Metadata;
 
@@ -9,7 +7,7 @@ const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
 pub type Sg721MetadataContract<'a> = sg721_base::Sg721Contract<'a, Metadata>;
 pub type InstantiateMsg = sg721::InstantiateMsg;
-pub type ExecuteMsg = sg721::ExecuteMsg<Metadata, Empty>;
+pub type ExecuteMsg = sg721::ExecuteMsg<Metadata, sg721_base::msg::ExtensionMsg>;
 pub type QueryMsg = sg721_base::msg::QueryMsg;
 
 #[cfg(not(feature = "library"))]
@@ -2,13 +2,13 @@ use crate::error::ContractError;
 use crate::msg::{
     AddMembersMsg, ConfigResponse, ExecuteMsg, HasEndedResponse, HasMemberResponse,
     HasStartedResponse, InstantiateMsg, IsActiveResponse, MembersResponse, QueryMsg,
-    RemoveMembersMsg,
+    RemoveMembersMsg, UpdateMembersMsg, WhitelistSnapshotResponse,
 };
-use crate::state::{Config, CONFIG, WHITELIST};
+use crate::state::{Config, CONFIG, JOIN_CODE_HASH, WHITELIST, WHITELIST_LOCKED};
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
-use cosmwasm_std::{to_binary, Binary, Deps, DepsMut, Env, MessageInfo, StdResult};
-use cosmwasm_std::{Order, Timestamp};
+use cosmwasm_std::{to_binary, Binary, Deps, DepsMut, Env, MessageInfo, StdError, StdResult};
+use cosmwasm_std::{HexBinary, Order, Storage, Timestamp};
 use cw2::set_contract_version;
 use cw_storage_plus::Bound;
 use cw_utils::{may_pay, maybe_addr, must_pay};
@@ -16,6 +16,7 @@ use rust_decimal::prelude::ToPrimitive;
 use rust_decimal::Decimal;
 use sg1::checked_fair_burn;
 use sg_std::{Response, GENESIS_MINT_START_TIME, NATIVE_DENOM};
+use sha2::{Digest, Sha256};
 
 // version info for migration info
 const CONTRACT_NAME: &str = "crates.io:sg-whitelist";
@@ -26,6 +27,7 @@ const MAX_MEMBERS: u32 = 5000;
 const PRICE_PER_1000_MEMBERS: u128 = 100_000_000;
 const MIN_MINT_PRICE: u128 = 25_000_000;
 const MAX_PER_ADDRESS_LIMIT: u32 = 30;
+const MAX_WHITELIST_ADD_PER_TX: u32 = 500;
 
 // queries
 const PAGINATION_DEFAULT_LIMIT: u32 = 25;
@@ -137,6 +139,7 @@ pub fn instantiate(
         let addr = deps.api.addr_validate(&member.clone())?;
         WHITELIST.save(deps.storage, addr, &true)?;
     }
+    WHITELIST_LOCKED.save(deps.storage, &false)?;
 
     Ok(res
         .add_attribute("action", "instantiate")
@@ -157,12 +160,17 @@ pub fn execute(
         ExecuteMsg::UpdateEndTime(time) => execute_update_end_time(deps, env, info, time),
         ExecuteMsg::AddMembers(msg) => execute_add_members(deps, env, info, msg),
         ExecuteMsg::RemoveMembers(msg) => execute_remove_members(deps, env, info, msg),
+        ExecuteMsg::UpdateMembers(msg) => execute_update_members(deps, env, info, msg),
         ExecuteMsg::UpdatePerAddressLimit(per_address_limit) => {
             execute_update_per_address_limit(deps, info, per_address_limit)
         }
         ExecuteMsg::IncreaseMemberLimit(member_limit) => {
             execute_increase_member_limit(deps, info, member_limit)
         }
+        ExecuteMsg::RecomputeWhitelistCount {} => execute_recompute_whitelist_count(deps, info),
+        ExecuteMsg::UpdateJoinCodeHash(hash) => execute_update_join_code_hash(deps, info, hash),
+        ExecuteMsg::JoinWhitelist { code } => execute_join_whitelist(deps, info, code),
+        ExecuteMsg::LockWhitelist {} => execute_lock_whitelist(deps, info),
     }
 }
 
@@ -176,6 +184,7 @@ pub fn execute_update_start_time(
     if info.sender != config.admin {
         return Err(ContractError::Unauthorized {});
     }
+    check_unlocked(deps.storage)?;
 
     // don't allow updating start time if whitelist is active
     if env.block.time >= config.start_time {
@@ -211,6 +220,7 @@ pub fn execute_update_end_time(
     if info.sender != config.admin {
         return Err(ContractError::Unauthorized {});
     }
+    check_unlocked(deps.storage)?;
 
     // don't allow updating end time if whitelist is active
     if env.block.time >= config.start_time {
@@ -239,22 +249,31 @@ pub fn execute_add_members(
     if info.sender != config.admin {
         return Err(ContractError::Unauthorized {});
     }
+    check_unlocked(deps.storage)?;
+    // large imports have to be chunked so each batch fits in a block
+    if msg.to_add.len() > MAX_WHITELIST_ADD_PER_TX as usize {
+        return Err(ContractError::TooManyMembersPerTx {
+            max: MAX_WHITELIST_ADD_PER_TX,
+            got: msg.to_add.len() as u32,
+        });
+    }
 
     // remove duplicate members
     msg.to_add.sort_unstable();
     msg.to_add.dedup();
 
+    // Existing members are skipped so resubmitting the same batch is a no-op
     for add in msg.to_add.into_iter() {
+        let addr = deps.api.addr_validate(&add)?;
+        if WHITELIST.has(deps.storage, addr.clone()) {
+            continue;
+        }
         if config.num_members >= config.member_limit {
             return Err(ContractError::MembersExceeded {
                 expected: config.member_limit,
                 actual: config.num_members,
             });
         }
-        let addr = deps.api.addr_validate(&add)?;
-        if WHITELIST.has(deps.storage, addr.clone()) {
-            return Err(ContractError::DuplicateMember(addr.to_string()));
-        }
         WHITELIST.save(deps.storage, addr, &true)?;
         config.num_members += 1;
     }
@@ -266,6 +285,51 @@ pub fn execute_add_members(
         .add_attribute("sender", info.sender))
 }
 
+/// Removes then adds members in one transaction.
+/// An address in both lists is rejected instead of depending on the order.
+pub fn execute_update_members(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: UpdateMembersMsg,
+) -> Result<Response, ContractError> {
+    // Compare validated addresses, so the same member spelled differently still counts as overlap
+    let validate = |addrs: Vec<String>| -> StdResult<Vec<String>> {
+        addrs
+            .iter()
+            .map(|addr| Ok(deps.api.addr_validate(addr)?.to_string()))
+            .collect()
+    };
+    let to_add = validate(msg.to_add)?;
+    let to_remove = validate(msg.to_remove)?;
+    if let Some(addr) = to_add.iter().find(|addr| to_remove.contains(addr)) {
+        return Err(ContractError::AddedAndRemoved(addr.clone()));
+    }
+
+    let mut res = Response::new()
+        .add_attribute("action", "update_members")
+        .add_attribute("sender", info.sender.clone());
+    if !to_remove.is_empty() {
+        let remove_res = execute_remove_members(
+            deps.branch(),
+            env.clone(),
+            info.clone(),
+            RemoveMembersMsg { to_remove },
+        )?;
+        res = res
+            .add_attributes(remove_res.attributes)
+            .add_events(remove_res.events);
+    }
+    if !to_add.is_empty() {
+        let add_res = execute_add_members(deps.branch(), env, info, AddMembersMsg { to_add })?;
+        res = res
+            .add_attributes(add_res.attributes)
+            .add_events(add_res.events);
+    }
+
+    Ok(res)
+}
+
 pub fn execute_remove_members(
     deps: DepsMut,
     env: Env,
@@ -276,6 +340,7 @@ pub fn execute_remove_members(
     if info.sender != config.admin {
         return Err(ContractError::Unauthorized {});
     }
+    check_unlocked(deps.storage)?;
 
     if env.block.time >= config.start_time {
         return Err(ContractError::AlreadyStarted {});
@@ -364,6 +429,113 @@ pub fn execute_increase_member_limit(
         .add_attribute("member_limit", member_limit.to_string()))
 }
 
+/// Permanently freeze the members and the whitelist window
+pub fn execute_lock_whitelist(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.admin {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    WHITELIST_LOCKED.save(deps.storage, &true)?;
+    Ok(Response::new()
+        .add_attribute("action", "lock_whitelist")
+        .add_attribute("sender", info.sender))
+}
+
+fn check_unlocked(storage: &dyn Storage) -> Result<(), ContractError> {
+    if WHITELIST_LOCKED.may_load(storage)?.unwrap_or_default() {
+        return Err(ContractError::WhitelistLocked {});
+    }
+    Ok(())
+}
+
+pub fn execute_update_join_code_hash(
+    deps: DepsMut,
+    info: MessageInfo,
+    hash: Option<HexBinary>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.admin {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    match hash {
+        Some(hash) => JOIN_CODE_HASH.save(deps.storage, &hash)?,
+        None => JOIN_CODE_HASH.remove(deps.storage),
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "update_join_code_hash")
+        .add_attribute("sender", info.sender))
+}
+
+pub fn execute_join_whitelist(
+    deps: DepsMut,
+    info: MessageInfo,
+    code: String,
+) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+    check_unlocked(deps.storage)?;
+    let join_code_hash = JOIN_CODE_HASH
+        .may_load(deps.storage)?
+        .ok_or(ContractError::JoinDisabled {})?;
+
+    let hash = Sha256::digest(code.as_bytes());
+    if hash.as_slice() != join_code_hash.as_slice() {
+        return Err(ContractError::InvalidJoinCode {});
+    }
+
+    // Joining again is a no-op, same as re-adding a member
+    if !WHITELIST.has(deps.storage, info.sender.clone()) {
+        if config.num_members >= config.member_limit {
+            return Err(ContractError::MembersExceeded {
+                expected: config.member_limit,
+                actual: config.num_members,
+            });
+        }
+        WHITELIST.save(deps.storage, info.sender.clone(), &true)?;
+        config.num_members += 1;
+        CONFIG.save(deps.storage, &config)?;
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "join_whitelist")
+        .add_attribute("sender", info.sender))
+}
+
+/// Fix a drifted `num_members` counter by counting the stored members.
+/// Gas cost grows with the number of members, which is capped at `MAX_MEMBERS`.
+pub fn execute_recompute_whitelist_count(
+    deps: DepsMut,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+    if info.sender != config.admin {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    // count one past the cap so an oversized list fails instead of being truncated
+    let num_members = WHITELIST
+        .keys(deps.storage, None, None, Order::Ascending)
+        .take(MAX_MEMBERS as usize + 1)
+        .count() as u32;
+    if num_members > MAX_MEMBERS {
+        return Err(ContractError::MembersExceeded {
+            expected: MAX_MEMBERS,
+            actual: num_members,
+        });
+    }
+
+    let previous = config.num_members;
+    config.num_members = num_members;
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "recompute_whitelist_count")
+        .add_attribute("previous_num_members", previous.to_string())
+        .add_attribute("num_members", num_members.to_string()))
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
@@ -376,6 +548,7 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
         QueryMsg::IsActive {} => to_binary(&query_is_active(deps, env)?),
         QueryMsg::HasMember { member } => to_binary(&query_has_member(deps, member)?),
         QueryMsg::Config {} => to_binary(&query_config(deps, env)?),
+        QueryMsg::WhitelistSnapshot {} => to_binary(&query_whitelist_snapshot(deps)?),
     }
 }
 
@@ -396,10 +569,16 @@ fn query_has_ended(deps: Deps, env: Env) -> StdResult<HasEndedResponse> {
 fn query_is_active(deps: Deps, env: Env) -> StdResult<IsActiveResponse> {
     let config = CONFIG.load(deps.storage)?;
     Ok(IsActiveResponse {
-        is_active: (env.block.time >= config.start_time) && (env.block.time < config.end_time),
+        is_active: is_active(&config, env.block.time),
     })
 }
 
+/// The whitelist is active from `start_time` (inclusive) until `end_time` (exclusive).
+/// At exactly `end_time` the whitelist has ended, so minters fall back to the public mint.
+fn is_active(config: &Config, now: Timestamp) -> bool {
+    now >= config.start_time && now < config.end_time
+}
+
 fn query_members(
     deps: Deps,
     start_after: Option<String>,
@@ -427,6 +606,35 @@ fn query_has_member(deps: Deps, member: String) -> StdResult<HasMemberResponse>
     })
 }
 
+/// Hashes every member in ascending order, each followed by a newline so adjacent
+/// addresses can't run together. Fails rather than hashing only the first `MAX_MEMBERS`.
+fn query_whitelist_snapshot(deps: Deps) -> StdResult<WhitelistSnapshotResponse> {
+    let mut hasher = Sha256::new();
+    let mut count = 0u32;
+    for addr in WHITELIST
+        .keys(deps.storage, None, None, Order::Ascending)
+        .take(MAX_MEMBERS as usize + 1)
+    {
+        if count == MAX_MEMBERS {
+            return Err(StdError::generic_err(
+                ContractError::MembersExceeded {
+                    expected: MAX_MEMBERS,
+                    actual: count + 1,
+                }
+                .to_string(),
+            ));
+        }
+        hasher.update(addr?.as_bytes());
+        hasher.update(b"\n");
+        count += 1;
+    }
+
+    Ok(WhitelistSnapshotResponse {
+        count,
+        hash: HexBinary::from(hasher.finalize().to_vec()),
+    })
+}
+
 fn query_config(deps: Deps, env: Env) -> StdResult<ConfigResponse> {
     let config = CONFIG.load(deps.storage)?;
     Ok(ConfigResponse {
@@ -435,8 +643,8 @@ fn query_config(deps: Deps, env: Env) -> StdResult<ConfigResponse> {
         member_limit: config.member_limit,
         start_time: config.start_time,
         end_time: config.end_time,
+        is_active: is_active(&config, env.block.time),
         mint_price: config.mint_price,
-        is_active: (env.block.time >= config.start_time) && (env.block.time < config.end_time),
     })
 }
 
@@ -446,6 +654,7 @@ mod tests {
     use cosmwasm_std::{
         coin,
         testing::{mock_dependencies, mock_env, mock_info},
+        Addr,
     };
     use sg_std::NATIVE_DENOM;
 
@@ -605,7 +814,10 @@ mod tests {
         let res = query_members(deps.as_ref(), None, None).unwrap();
         assert_eq!(res.members.len(), 2);
 
-        execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap_err();
+        // re-adding existing members is a no-op
+        execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+        let res = query_members(deps.as_ref(), None, None).unwrap();
+        assert_eq!(res.members.len(), 2);
 
         let remove_msg = RemoveMembersMsg {
             to_remove: vec!["adsfsa1".to_string()],
@@ -617,6 +829,84 @@ mod tests {
         assert_eq!(res.members.len(), 1);
     }
 
+    #[test]
+    fn update_members_rejects_overlap() {
+        let mut deps = mock_dependencies();
+        setup_contract(deps.as_mut());
+        let info = mock_info(ADMIN, &[]);
+
+        let msg = ExecuteMsg::UpdateMembers(UpdateMembersMsg {
+            to_add: vec!["adsfsa1".to_string(), "adsfsa2".to_string()],
+            to_remove: vec!["adsfsa2".to_string()],
+        });
+        let err = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            ContractError::AddedAndRemoved("adsfsa2".to_string()).to_string()
+        );
+
+        // Without overlap removes and adds both apply
+        let msg = ExecuteMsg::UpdateMembers(UpdateMembersMsg {
+            to_add: vec!["adsfsa1".to_string()],
+            to_remove: vec!["adsfsa".to_string()],
+        });
+        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        let actions: Vec<_> = res
+            .attributes
+            .iter()
+            .filter(|attr| attr.key == "action")
+            .map(|attr| attr.value.as_str())
+            .collect();
+        assert_eq!(
+            actions,
+            vec!["update_members", "remove_members", "add_members"]
+        );
+        let res = query_members(deps.as_ref(), None, None).unwrap();
+        assert_eq!(res.members, vec!["adsfsa1".to_string()]);
+    }
+
+    #[test]
+    fn whitelist_snapshot_changes_with_members() {
+        let mut deps = mock_dependencies();
+        setup_contract(deps.as_mut());
+
+        let before = query_whitelist_snapshot(deps.as_ref()).unwrap();
+        assert_eq!(before.count, 1);
+        assert_eq!(before, query_whitelist_snapshot(deps.as_ref()).unwrap());
+
+        let msg = ExecuteMsg::AddMembers(AddMembersMsg {
+            to_add: vec!["adsfsa1".to_string()],
+        });
+        execute(deps.as_mut(), mock_env(), mock_info(ADMIN, &[]), msg).unwrap();
+
+        let after = query_whitelist_snapshot(deps.as_ref()).unwrap();
+        assert_eq!(after.count, 2);
+        assert_ne!(after.hash, before.hash);
+
+        // A list above the cap can't be snapshotted in full
+        for i in 0..MAX_MEMBERS {
+            WHITELIST
+                .save(
+                    &mut deps.storage,
+                    Addr::unchecked(format!("member{}", i)),
+                    &true,
+                )
+                .unwrap();
+        }
+        let err = query_whitelist_snapshot(deps.as_ref()).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            StdError::generic_err(
+                ContractError::MembersExceeded {
+                    expected: MAX_MEMBERS,
+                    actual: MAX_MEMBERS + 1,
+                }
+                .to_string()
+            )
+            .to_string()
+        );
+    }
+
     #[test]
     fn too_many_members_check() {
         let mut deps = mock_dependencies();
@@ -627,9 +917,15 @@ mod tests {
             members.push(format!("adsfsa{}", i));
         }
 
-        let inner_msg = AddMembersMsg { to_add: members };
-        let msg = ExecuteMsg::AddMembers(inner_msg);
         let info = mock_info(ADMIN, &[]);
+        let mut chunks = members.chunks(MAX_WHITELIST_ADD_PER_TX as usize);
+        let msg = ExecuteMsg::AddMembers(AddMembersMsg {
+            to_add: chunks.next().unwrap().to_vec(),
+        });
+        execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+        let msg = ExecuteMsg::AddMembers(AddMembersMsg {
+            to_add: chunks.next().unwrap().to_vec(),
+        });
         let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
         assert_eq!(
             ContractError::MembersExceeded {
@@ -641,6 +937,182 @@ mod tests {
         );
     }
 
+    #[test]
+    fn too_many_members_per_tx() {
+        let mut deps = mock_dependencies();
+        setup_contract(deps.as_mut());
+
+        let members = (0..600).map(|i| format!("adsfsa{}", i)).collect();
+        let msg = ExecuteMsg::AddMembers(AddMembersMsg { to_add: members });
+        let info = mock_info(ADMIN, &[]);
+        let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        assert_eq!(
+            ContractError::TooManyMembersPerTx {
+                max: MAX_WHITELIST_ADD_PER_TX,
+                got: 600
+            }
+            .to_string(),
+            err.to_string()
+        );
+        let res = query_config(deps.as_ref(), mock_env()).unwrap();
+        assert_eq!(1, res.num_members);
+    }
+
+    #[test]
+    fn add_members_is_idempotent() {
+        let mut deps = mock_dependencies();
+        setup_contract(deps.as_mut());
+
+        let add_msg = ExecuteMsg::AddMembers(AddMembersMsg {
+            to_add: vec!["adsfsa1".to_string(), "adsfsa2".to_string()],
+        });
+        let info = mock_info(ADMIN, &[]);
+        execute(deps.as_mut(), mock_env(), info.clone(), add_msg.clone()).unwrap();
+        let res = query_config(deps.as_ref(), mock_env()).unwrap();
+        assert_eq!(3, res.num_members);
+
+        // retrying the same batch leaves the count unchanged
+        execute(deps.as_mut(), mock_env(), info, add_msg).unwrap();
+        let res = query_config(deps.as_ref(), mock_env()).unwrap();
+        assert_eq!(3, res.num_members);
+        let res = query_members(deps.as_ref(), None, None).unwrap();
+        assert_eq!(3, res.members.len());
+    }
+
+    #[test]
+    fn join_whitelist() {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {
+            members: vec!["adsfsa".to_string()],
+            start_time: GENESIS_START_TIME,
+            end_time: END_TIME,
+            mint_price: coin(UNIT_AMOUNT, NATIVE_DENOM),
+            per_address_limit: 1,
+            member_limit: 2,
+        };
+        let info = mock_info(ADMIN, &[coin(100_000_000, "ustars")]);
+        instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let join_msg = ExecuteMsg::JoinWhitelist {
+            code: "let me in".to_string(),
+        };
+
+        // joining is disabled until the admin sets a code
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("joiner1", &[]),
+            join_msg.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(err.to_string(), ContractError::JoinDisabled {}.to_string());
+
+        let hash = HexBinary::from(Sha256::digest(b"let me in").to_vec());
+        let msg = ExecuteMsg::UpdateJoinCodeHash(Some(hash));
+        execute(deps.as_mut(), mock_env(), mock_info(ADMIN, &[]), msg).unwrap();
+
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("joiner1", &[]),
+            ExecuteMsg::JoinWhitelist {
+                code: "wrong".to_string(),
+            },
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            ContractError::InvalidJoinCode {}.to_string()
+        );
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("joiner1", &[]),
+            join_msg.clone(),
+        )
+        .unwrap();
+        let res = query_has_member(deps.as_ref(), "joiner1".to_string()).unwrap();
+        assert!(res.has_member);
+        let res = query_config(deps.as_ref(), mock_env()).unwrap();
+        assert_eq!(2, res.num_members);
+
+        // the member limit is reached
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("joiner2", &[]),
+            join_msg,
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            ContractError::MembersExceeded {
+                expected: 2,
+                actual: 2
+            }
+            .to_string()
+        );
+    }
+
+    #[test]
+    fn recompute_whitelist_count() {
+        let mut deps = mock_dependencies();
+        setup_contract(deps.as_mut());
+
+        let add_msg = AddMembersMsg {
+            to_add: vec!["adsfsa1".to_string(), "adsfsa2".to_string()],
+        };
+        let info = mock_info(ADMIN, &[]);
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            ExecuteMsg::AddMembers(add_msg),
+        )
+        .unwrap();
+
+        // skew the counter
+        let mut config = CONFIG.load(&deps.storage).unwrap();
+        config.num_members = 10;
+        CONFIG.save(&mut deps.storage, &config).unwrap();
+
+        // only admin can recompute
+        let msg = ExecuteMsg::RecomputeWhitelistCount {};
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("other", &[]),
+            msg.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(err.to_string(), ContractError::Unauthorized {}.to_string());
+
+        execute(deps.as_mut(), mock_env(), info.clone(), msg.clone()).unwrap();
+        let res = query_config(deps.as_ref(), mock_env()).unwrap();
+        assert_eq!(3, res.num_members);
+
+        // more stored members than the cap is an error, not a truncated count
+        for i in 0..MAX_MEMBERS {
+            WHITELIST
+                .save(
+                    &mut deps.storage,
+                    Addr::unchecked(format!("member{}", i)),
+                    &true,
+                )
+                .unwrap();
+        }
+        let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            ContractError::MembersExceeded {
+                expected: MAX_MEMBERS,
+                actual: MAX_MEMBERS + 1,
+            }
+            .to_string()
+        );
+    }
+
     #[test]
     fn update_per_address_limit() {
         let mut deps = mock_dependencies();
@@ -794,4 +1266,50 @@ mod tests {
             "Invalid member limit. min: 4002, max: 5000, got: 6000"
         );
     }
+
+    #[test]
+    fn unlocked_without_lock_flag() {
+        let mut deps = mock_dependencies();
+        setup_contract(deps.as_mut());
+
+        // Whitelists instantiated before locking existed have no flag stored
+        WHITELIST_LOCKED.remove(&mut deps.storage);
+        let msg = ExecuteMsg::AddMembers(AddMembersMsg {
+            to_add: vec!["adsfsa1".to_string()],
+        });
+        execute(deps.as_mut(), mock_env(), mock_info(ADMIN, &[]), msg).unwrap();
+    }
+
+    #[test]
+    fn lock_whitelist() {
+        let mut deps = mock_dependencies();
+        setup_contract(deps.as_mut());
+
+        // Only the admin can lock
+        let msg = ExecuteMsg::LockWhitelist {};
+        let info = mock_info("anyone", &[]);
+        let err = execute(deps.as_mut(), mock_env(), info, msg.clone()).unwrap_err();
+        assert_eq!(err.to_string(), ContractError::Unauthorized {}.to_string());
+        let info = mock_info(ADMIN, &[]);
+        execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let msgs = vec![
+            ExecuteMsg::UpdateStartTime(Timestamp::from_nanos(GENESIS_MINT_START_TIME + 10)),
+            ExecuteMsg::UpdateEndTime(Timestamp::from_nanos(GENESIS_MINT_START_TIME + 100)),
+            ExecuteMsg::AddMembers(AddMembersMsg {
+                to_add: vec!["adsfsa1".to_string()],
+            }),
+            ExecuteMsg::RemoveMembers(RemoveMembersMsg {
+                to_remove: vec!["adsfsa1".to_string()],
+            }),
+        ];
+        for msg in msgs {
+            let info = mock_info(ADMIN, &[]);
+            let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+            assert_eq!(
+                err.to_string(),
+                ContractError::WhitelistLocked {}.to_string()
+            );
+        }
+    }
 }
@@ -20,6 +20,9 @@ pub enum ContractError {
     #[error("InvalidDenom: {0}")]
     InvalidDenom(String),
 
+    #[error("Member {0} is both added and removed")]
+    AddedAndRemoved(String),
+
     #[error("NoMemberFound: {0}")]
     NoMemberFound(String),
 
@@ -35,9 +38,21 @@ pub enum ContractError {
     #[error("Invalid minting limit per address. max: {max}, got: {got}")]
     InvalidPerAddressLimit { max: String, got: String },
 
+    #[error("Too many members added in one transaction. max: {max}, got: {got}")]
+    TooManyMembersPerTx { max: u32, got: u32 },
+
     #[error("Invalid member limit. min: {min}, max: {max}, got: {got}")]
     InvalidMemberLimit { min: u32, max: u32, got: u32 },
 
+    #[error("Joining the whitelist is disabled")]
+    JoinDisabled {},
+
+    #[error("Invalid join code")]
+    InvalidJoinCode {},
+
+    #[error("Whitelist is locked")]
+    WhitelistLocked {},
+
     #[error("Max minting limit per address exceeded")]
     MaxPerAddressLimitExceeded {},
 
@@ -1,5 +1,8 @@
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Coin, Timestamp};
+use cosmwasm_std::{Coin, HexBinary, Timestamp};
+
+/// `end_time` of a whitelist that never expires. Minters gated by it never open a public sale.
+pub const NEVER_EXPIRES: Timestamp = Timestamp::from_nanos(u64::MAX);
 
 #[cw_serde]
 pub struct InstantiateMsg {
@@ -17,8 +20,21 @@ pub enum ExecuteMsg {
     UpdateEndTime(Timestamp),
     AddMembers(AddMembersMsg),
     RemoveMembers(RemoveMembersMsg),
+    /// Removes `to_remove` then adds `to_add`. An address can't be in both lists.
+    UpdateMembers(UpdateMembersMsg),
     UpdatePerAddressLimit(u32),
     IncreaseMemberLimit(u32),
+    /// Recount the members stored in the whitelist and overwrite `num_members`.
+    /// Iterates every member, bounded by `MAX_MEMBERS`.
+    RecomputeWhitelistCount {},
+    /// Set the sha256 hash of the code members can use to join. `None` disables joining.
+    UpdateJoinCodeHash(Option<HexBinary>),
+    /// Add the sender to the whitelist if the sha256 of `code` matches the join code hash
+    JoinWhitelist {
+        code: String,
+    },
+    /// Permanently prevent changes to the members and the start / end times
+    LockWhitelist {},
 }
 
 #[cw_serde]
@@ -31,6 +47,12 @@ pub struct RemoveMembersMsg {
     pub to_remove: Vec<String>,
 }
 
+#[cw_serde]
+pub struct UpdateMembersMsg {
+    pub to_add: Vec<String>,
+    pub to_remove: Vec<String>,
+}
+
 #[cw_serde]
 pub enum QueryMsg {
     HasStarted {},
@@ -44,6 +66,9 @@ pub enum QueryMsg {
         member: String,
     },
     Config {},
+    /// Member count and a sha256 hash over the sorted member set, so buyers can check
+    /// the whitelist hasn't changed
+    WhitelistSnapshot {},
 }
 
 #[cw_serde]
@@ -76,6 +101,12 @@ pub struct MintPriceResponse {
     pub mint_price: Coin,
 }
 
+#[cw_serde]
+pub struct WhitelistSnapshotResponse {
+    pub count: u32,
+    pub hash: HexBinary,
+}
+
 #[cw_serde]
 pub struct ConfigResponse {
     pub num_members: u32,
@@ -1,5 +1,5 @@
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Addr, Coin, Timestamp};
+use cosmwasm_std::{Addr, Coin, HexBinary, Timestamp};
 use cw_storage_plus::{Item, Map};
 
 #[cw_serde]
@@ -15,3 +15,7 @@ pub struct Config {
 
 pub const CONFIG: Item<Config> = Item::new("config");
 pub const WHITELIST: Map<Addr, bool> = Map::new("wl");
+/// sha256 hash of the code that lets an address add itself to the whitelist
+pub const JOIN_CODE_HASH: Item<HexBinary> = Item::new("join_code_hash");
+/// Once set, members and the start / end times can no longer change
+pub const WHITELIST_LOCKED: Item<bool> = Item::new("whitelist_locked");
@@ -3,7 +3,7 @@ use cosmwasm_std::Addr;
 
 const PREFIX: &str = "stars";
 
-trait ToStars {
+pub trait ToStars {
     fn to_stars(&self) -> Result<String, Error>;
 }
 
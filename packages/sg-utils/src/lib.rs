@@ -1 +1 @@
-mod bech32_convert;
+pub mod bech32_convert;